@@ -9,10 +9,14 @@
 // --------------------------------------------------
 use quote::{quote, format_ident};
 use syn::{
+    Lit,
     Data,
+    Attribute,
+    Expr,
     Path,
     Type,
     Token,
+    ExprLit,
     parse::{
         Parse,
         ParseStream,
@@ -36,19 +40,120 @@ use proc_macro2::TokenStream as TokenStream2;
 /// Any variants missing from the macro will automatically be added
 /// using the [`String`] datatype
 /// 
+/// The same initialization is used for both `crosstalk::BoundedNode`
+/// and `crosstalk::UnboundedNode`
+/// 
 /// The enum variants and the datatypes are formatted in the following fashion:
 /// 
 /// ```rust ignore
 /// crosstalk::init!{
 ///     TopicEnum::Variant1 => bool,
 ///     TopicEnum::Variant2 => String,
-///     TopicEnum::Variant3 => i32,
+///     TopicEnum::Variant3 => i32 @ 4096,
 /// }
 /// ```
 /// 
 /// Where `TopicEnum::<VariantName>` is the name of the enum, followed by 
 /// a `=>` and the datatype to be used on that topic of communication
 /// 
+/// Optionally, the datatype can be followed by a `@` and the capacity of
+/// the buffer for that topic. Topics without a capacity use the size given
+/// to `crosstalk::BoundedNode::new`. The capacity is ignored by a
+/// `crosstalk::UnboundedNode`
+/// 
+/// By default, every topic broadcasts every sample to every subscriber.
+/// The kind of a topic can be changed by prefixing it with an attribute:
+/// 
+/// * `#[watch]` - subscribers only observe the latest sample, and late
+///   subscribers immediately receive the current value
+/// * `#[queue]` - each sample is received by exactly one subscriber, where
+///   subscribers compete for samples (load-balancing). Requires the `queue`
+///   feature of `crosstalk`
+/// 
+/// ```rust ignore
+/// crosstalk::init!{
+///     #[watch]
+///     TopicEnum::Variant4 => Mode,
+///     #[queue]
+///     TopicEnum::Variant5 => Image @ 64,
+/// }
+/// ```
+/// 
+/// Broadcast topics can keep their latest samples for late subscribers with
+/// `#[history(<Depth>)]`, where every new subscriber first receives (up to)
+/// the last `<Depth>` samples published before it subscribed
+/// 
+/// ```rust ignore
+/// crosstalk::init!{
+///     #[history(4)]
+///     TopicEnum::Variant6 => Config,
+/// }
+/// ```
+/// 
+/// Samples which are only relevant for a while can be given a lifespan
+/// with `#[lifespan(<Duration>)]`, where subscribers skip (and count, see
+/// `crosstalk::Subscriber::expired_total`) samples read longer than
+/// `<Duration>` after they were published
+/// 
+/// ```rust ignore
+/// crosstalk::init!{
+///     #[lifespan(std::time::Duration::from_millis(100))]
+///     TopicEnum::Variant7 => Pose,
+/// }
+/// ```
+/// 
+/// Periodic topics can declare the maximum time expected between two
+/// samples with `#[deadline(<Duration>)]`. Publishers report whether they are
+/// late (see `crosstalk::Publisher::is_late`), and subscribers receive
+/// `crosstalk::RecvError::DeadlineMissed` when no sample was published within
+/// `<Duration>` of the previous one
+/// 
+/// ```rust ignore
+/// crosstalk::init!{
+///     #[deadline(std::time::Duration::from_millis(50))]
+///     TopicEnum::Variant8 => Heartbeat,
+/// }
+/// ```
+/// 
+/// Topics declared with `#[stamp]` stamp every sample with a sequence number,
+/// the id of its publisher and the time it was published, as read with
+/// `crosstalk::Subscriber::read_sample`. Samples of other topics only carry
+/// the id of their publisher, except for topics with a lifespan or a deadline,
+/// which are always stamped
+/// 
+/// ```rust ignore
+/// crosstalk::init!{
+///     #[stamp]
+///     TopicEnum::Variant9 => Frame,
+/// }
+/// ```
+/// 
+/// With the `serde` feature of `crosstalk`, topics can declare how their
+/// samples are serialized with `#[serde(<Format>)]`, where `<Format>` is one of
+/// `bincode`, `json` or `cbor`. Such topics can be published to and subscribed
+/// to as bytes, without knowing their data type, through
+/// `crosstalk::BoundedNode::byte_publisher` and `crosstalk::BoundedNode::byte_subscriber`
+/// 
+/// ```rust ignore
+/// crosstalk::init!{
+///     #[serde(json)]
+///     TopicEnum::Variant10 => Config,
+/// }
+/// ```
+/// 
+/// With the `queue` feature of `crosstalk`, a topic can also be declared as
+/// a request/response service, by following the request datatype with a `->`
+/// and the response datatype. Services are used through
+/// `crosstalk::BoundedNode::service_server` and `crosstalk::BoundedNode::service_client`,
+/// and can not be published or subscribed to. The capacity (if any) bounds
+/// the pending requests
+/// 
+/// ```rust ignore
+/// crosstalk::init!{
+///     TopicEnum::Variant11 => Query -> Answer,
+/// }
+/// ```
+/// 
 /// # Examples
 /// 
 /// ```ignore
@@ -65,8 +170,9 @@ use proc_macro2::TokenStream as TokenStream2;
 /// crosstalk::init!{
 ///     ExampleTopics::BoolChannel => bool,
 ///     ExampleTopics::StringChannel => String,
-///     ExampleTopics::IntChannel => i32,
+///     ExampleTopics::IntChannel => i32 @ 4096,
 /// }
+/// // `ExampleTopics::IntChannel` will have a buffer of 4096 on a `crosstalk::BoundedNode`
 /// // `ExampleTopics::MissingChannel`` will be added automatically with datatype `String``
 /// ```
 pub fn init(input: TokenStream) -> TokenStream {
@@ -121,11 +227,11 @@ fn init_inner(input: TokenStream, source: TokenStream2) -> TokenStream {
     let enum_master = format_ident!( "{}", &unique_enum_names[0]);
 
     // --------------------------------------------------
-    // get topic names/types
+    // get topic names/types/response types/configs
     // --------------------------------------------------
     let nt = fields
         .iter()
-        .map(|nf| (nf.topic.clone(), nf.dtype.clone()))
+        .map(|nf| (nf.topic.clone(), nf.dtype.clone(), nf.response.clone(), get_topic_config(Some(nf), &source)))
         .collect::<Vec<_>>();
 
     // --------------------------------------------------
@@ -135,30 +241,168 @@ fn init_inner(input: TokenStream, source: TokenStream2) -> TokenStream {
 
     // --------------------------------------------------
     // publisher arms
+    // - services can not be published to
     // - add default case
     // --------------------------------------------------
     let mut pub_arms: Vec<TokenStream2> = nt
         .iter()
-        .map(|(n, t)| get_publisher_arm(Some(n), t, &source))
+        .map(|(n, t, r, c)| match r {
+            Some(r) => get_mismatch_arm(Some(n), quote! {
+                #source Error::PublisherMismatch(
+                    ::std::any::type_name::<D>(),
+                    ::std::any::type_name::<(#t, #r)>(),
+                )
+            }),
+            None => get_publisher_arm(Some(n), t, c, &source),
+        })
         .collect();
-    pub_arms.push(get_publisher_arm(None, &dt, &source));
+    pub_arms.push(get_publisher_arm(None, &dt, &get_topic_config(None, &source), &source));
 
     // --------------------------------------------------
     // subscriber arms
+    // - services can not be subscribed to
     // - add default case
     // --------------------------------------------------
     let mut sub_arms: Vec<TokenStream2> = nt
         .iter()
-        .map(|(n, t)| get_subscriber_arm(Some(n), t, &source))
+        .map(|(n, t, r, c)| match r {
+            Some(r) => get_mismatch_arm(Some(n), quote! {
+                #source Error::SubscriberMismatch(
+                    ::std::any::type_name::<D>(),
+                    ::std::any::type_name::<(#t, #r)>(),
+                )
+            }),
+            None => get_subscriber_arm(Some(n), t, c, &source),
+        })
         .collect::<Vec<_>>();
-    sub_arms.push(get_subscriber_arm(None, &dt, &source));
+    sub_arms.push(get_subscriber_arm(None, &dt, &get_topic_config(None, &source), &source));
+
+    // --------------------------------------------------
+    // service arms
+    // - topics can not be used as services
+    // - add default case
+    // --------------------------------------------------
+    let service_mismatch = |t: &Type| quote! {
+        #source Error::ServiceMismatch(
+            ::std::any::type_name::<(Req, Resp)>(),
+            ::std::any::type_name::<#t>(),
+        )
+    };
+    let mut srv_arms: Vec<TokenStream2> = nt
+        .iter()
+        .map(|(n, t, r, c)| match r {
+            Some(r) => get_service_arm(n, t, r, c, &source),
+            None => get_mismatch_arm(Some(n), service_mismatch(t)),
+        })
+        .collect::<Vec<_>>();
+    srv_arms.push(get_mismatch_arm(None, service_mismatch(&dt)));
+
+    // --------------------------------------------------
+    // byte publisher/subscriber arms
+    // - only topics with a serialization format
+    // - add default case
+    // --------------------------------------------------
+    let mut byte_pub_arms: Vec<TokenStream2> = fields
+        .iter()
+        .filter_map(|nf| nf.format.as_ref().map(|f| get_byte_arm(&nf.topic, &nf.dtype, f, quote! { BytePublisher }, quote! { publisher }, &enum_master, &source)))
+        .collect();
+    byte_pub_arms.push(quote! { _ => Err(#source Error::NotSerializable) });
+    let mut byte_sub_arms: Vec<TokenStream2> = fields
+        .iter()
+        .filter_map(|nf| nf.format.as_ref().map(|f| get_byte_arm(&nf.topic, &nf.dtype, f, quote! { ByteSubscriber }, quote! { subscriber }, &enum_master, &source)))
+        .collect();
+    byte_sub_arms.push(quote! { _ => Err(#source Error::NotSerializable) });
 
     // --------------------------------------------------
     // output
+    // - implement for both bounded and unbounded nodes
+    // --------------------------------------------------
+    let bounded_impl = get_pubsub_impl(
+        quote! { #source ImplementedBoundedNode<#enum_master> },
+        &enum_master,
+        &pub_arms,
+        &sub_arms,
+        &source,
+    );
+    let unbounded_impl = get_pubsub_impl(
+        quote! { #source ImplementedUnboundedNode<#enum_master> },
+        &enum_master,
+        &pub_arms,
+        &sub_arms,
+        &source,
+    );
+    let bounded_service_impl = get_service_impl(
+        quote! { #source ImplementedBoundedNode<#enum_master> },
+        &enum_master,
+        &srv_arms,
+        &source,
+    );
+    let unbounded_service_impl = get_service_impl(
+        quote! { #source ImplementedUnboundedNode<#enum_master> },
+        &enum_master,
+        &srv_arms,
+        &source,
+    );
+    let bounded_bytes_impl = get_bytes_impl(
+        quote! { #source ImplementedBoundedNode<#enum_master> },
+        &enum_master,
+        &byte_pub_arms,
+        &byte_sub_arms,
+        &source,
+    );
+    let unbounded_bytes_impl = get_bytes_impl(
+        quote! { #source ImplementedUnboundedNode<#enum_master> },
+        &enum_master,
+        &byte_pub_arms,
+        &byte_sub_arms,
+        &source,
+    );
+    // --------------------------------------------------
+    // services require the `queue` feature of `crosstalk`
     // --------------------------------------------------
+    let queue_required = match nt.iter().any(|(_, _, r, _)| r.is_some()) {
+        true => quote! { #source __macro_exports::queue! { @required } },
+        false => quote! {},
+    };
+    // --------------------------------------------------
+    // serialization formats require the `serde` feature of `crosstalk`
+    // --------------------------------------------------
+    let serde_required = match fields.iter().any(|nf| nf.format.is_some()) {
+        true => quote! { #source __macro_exports::serde! { @required } },
+        false => quote! {},
+    };
     let output: TokenStream2 = quote! {
+        #queue_required
+        #serde_required
+        #bounded_impl
+        #unbounded_impl
+        #bounded_service_impl
+        #unbounded_service_impl
+        #bounded_bytes_impl
+        #unbounded_bytes_impl
+    };
+
+    // --------------------------------------------------
+    // return
+    // --------------------------------------------------
+    TokenStream::from(output)
+}
+
+/// Get the implementation of `crosstalk::CrosstalkPubSub` for a node
+/// 
+/// Both the `crosstalk::ImplementedBoundedNode` and the `crosstalk::ImplementedUnboundedNode`
+/// share the same publisher/subscriber arms, since the creation of the channel
+/// is done by the node itself
+fn get_pubsub_impl(
+    node: TokenStream2,
+    enum_master: &syn::Ident,
+    pub_arms: &[TokenStream2],
+    sub_arms: &[TokenStream2],
+    source: &TokenStream2,
+) -> TokenStream2 {
+    quote! {
         #[automatically_derived]
-        impl #source CrosstalkPubSub<#enum_master> for #source ImplementedBoundedNode<#enum_master> {
+        impl #source CrosstalkPubSub<#enum_master> for #node {
             #[doc = " Get a [`crosstalk::Publisher`] for the given topic"]
             #[doc = ""]
             #[doc = " See [`crosstalk::BoundedNode::publisher`] for more information"]
@@ -190,12 +434,7 @@ fn init_inner(input: TokenStream, source: TokenStream2) -> TokenStream {
                 }
             }
         }
-    };
-
-    // --------------------------------------------------
-    // return
-    // --------------------------------------------------
-    TokenStream::from(output)
+    }
 }
 
 #[proc_macro_derive(AsTopic)]
@@ -213,6 +452,9 @@ fn init_inner(input: TokenStream, source: TokenStream2) -> TokenStream {
 /// * PartialEq
 /// * Eq
 /// * Hash
+/// 
+/// Along with [`crosstalk::CrosstalkTopic::name`], as the
+/// enum and variant name, e.g. `"ExampleTopics::BoolChannel"`
 ///
 /// # Example
 /// 
@@ -250,11 +492,16 @@ pub fn derive_enum_as_topic_test(input: TokenStream) -> TokenStream {
 fn derive_enum_as_topic_inner(input: TokenStream, source: TokenStream2) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     
-    let Data::Enum(_) = &input.data else {
+    let Data::Enum(data) = &input.data else {
         panic!("CrosstalkTopic can only be derived on enums");
     };
     
     let name = &input.ident;
+    let name_arms = data.variants.iter().map(|variant| {
+        let ident = &variant.ident;
+        let topic_name = format!("{name}::{ident}");
+        quote! { #name::#ident { .. } => #topic_name, }
+    });
 
     let expanded = quote! {
         #[automatically_derived]
@@ -293,31 +540,323 @@ fn derive_enum_as_topic_inner(input: TokenStream, source: TokenStream2) -> Token
         }
         
         #[automatically_derived]
-        impl #source CrosstalkTopic for #name {}
+        impl #source CrosstalkTopic for #name {
+            #[inline]
+            fn name(&self) -> &'static str {
+                match self {
+                    #(#name_arms)*
+                }
+            }
+        }
     };
 
     TokenStream::from(expanded)
 }
 
+/// Get the implementation of `crosstalk::CrosstalkService` for a node
+/// 
+/// Similar to [`get_pubsub_impl`], the service arms are shared by both
+/// the `crosstalk::ImplementedBoundedNode` and the `crosstalk::ImplementedUnboundedNode`
+/// 
+/// The implementation is only expanded with the `queue` feature of `crosstalk`,
+/// since the macro can not tell which features `crosstalk` was built with
+fn get_service_impl(
+    node: TokenStream2,
+    enum_master: &syn::Ident,
+    srv_arms: &[TokenStream2],
+    source: &TokenStream2,
+) -> TokenStream2 {
+    quote! {
+        #source __macro_exports::queue! {
+            @items
+            #[automatically_derived]
+            impl #source CrosstalkService<#enum_master> for #node {
+                #[doc = " Get a [`crosstalk::ServiceServer`] for the given topic"]
+                #[doc = ""]
+                #[doc = " See [`crosstalk::BoundedNode::service_server`] for more information"]
+                fn service_server<Req: #source CrosstalkData, Resp: #source CrosstalkData>(&mut self, topic: #enum_master) -> Result<#source ServiceServer<Req, Resp, #enum_master>, #source Error> {
+                    let service = match topic {
+                        #(#srv_arms,)*
+                    }?;
+                    Ok(#source ServiceServer::new(topic, service))
+                }
+
+                #[doc = " Get a [`crosstalk::ServiceClient`] for the given topic"]
+                #[doc = ""]
+                #[doc = " See [`crosstalk::BoundedNode::service_client`] for more information"]
+                fn service_client<Req: #source CrosstalkData, Resp: #source CrosstalkData>(&mut self, topic: #enum_master) -> Result<#source ServiceClient<Req, Resp, #enum_master>, #source Error> {
+                    let service = match topic {
+                        #(#srv_arms,)*
+                    }?;
+                    Ok(#source ServiceClient::new(topic, service))
+                }
+            }
+        }
+    }
+}
+
+/// Get the implementation of `crosstalk::CrosstalkBytes` for a node
+/// 
+/// The implementation is only expanded with the `serde` feature of `crosstalk`,
+/// since the macro can not tell which features `crosstalk` was built with
+fn get_bytes_impl(
+    node: TokenStream2,
+    enum_master: &syn::Ident,
+    byte_pub_arms: &[TokenStream2],
+    byte_sub_arms: &[TokenStream2],
+    source: &TokenStream2,
+) -> TokenStream2 {
+    quote! {
+        #source __macro_exports::serde! {
+            #[automatically_derived]
+            impl #source CrosstalkBytes<#enum_master> for #node {
+                #[doc = " Get a [`crosstalk::BytePublisher`] for the given topic"]
+                #[doc = ""]
+                #[doc = " See [`crosstalk::BoundedNode::byte_publisher`] for more information"]
+                fn byte_publisher(&mut self, topic: #enum_master) -> Result<#source BytePublisher<#enum_master>, #source Error> {
+                    match topic {
+                        #(#byte_pub_arms,)*
+                    }
+                }
+
+                #[doc = " Get a [`crosstalk::ByteSubscriber`] for the given topic"]
+                #[doc = ""]
+                #[doc = " See [`crosstalk::BoundedNode::byte_subscriber`] for more information"]
+                fn byte_subscriber(&mut self, topic: #enum_master) -> Result<#source ByteSubscriber<#enum_master>, #source Error> {
+                    match topic {
+                        #(#byte_sub_arms,)*
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Kind of a topic for the [`crosstalk_macros::init!`] macro
+/// 
+/// Mirrors `crosstalk::TopicKind`
+enum TopicKind {
+    Broadcast,
+    Watch,
+    Queue,
+}
+/// [`TopicKind`] implementation
+impl TopicKind {
+    /// Parses the kind of a topic from its attributes
+    /// 
+    /// # Format
+    /// 
+    /// ```text
+    /// #[watch]
+    /// #[queue]
+    /// ```
+    fn from_attrs(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut kind = TopicKind::Broadcast;
+        for attr in attrs.iter().filter(|attr| !["history", "lifespan", "deadline", "stamp", "serde"].iter().any(|ident| attr.path().is_ident(ident))) {
+            let next = match attr.path().get_ident().map(|i| i.to_string()).as_deref() {
+                Some("watch") => TopicKind::Watch,
+                Some("queue") => TopicKind::Queue,
+                _ => return Err(syn::Error::new_spanned(attr, "Unknown crosstalk topic attribute, expected `#[watch]`, `#[queue]`, `#[history(<Depth>)]`, `#[lifespan(<Duration>)]`, `#[deadline(<Duration>)]`, `#[stamp]` or `#[serde(<Format>)]`")),
+            };
+            attr.meta.require_path_only()?;
+            if !matches!(kind, TopicKind::Broadcast) {
+                return Err(syn::Error::new_spanned(attr, "Only one topic kind can be specified per topic"));
+            }
+            kind = next;
+        }
+        Ok(kind)
+    }
+}
+
+/// Parses the history depth of a topic from its attributes
+/// 
+/// # Format
+/// 
+/// ```text
+/// #[history(<Depth>)]
+/// ```
+fn history_from_attrs(attrs: &[Attribute]) -> syn::Result<Option<Expr>> {
+    let mut history = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("history")) {
+        let depth: Expr = attr.parse_args()?;
+        // --------------------------------------------------
+        // catch the obvious zero-depth case at compile time
+        // --------------------------------------------------
+        if let Expr::Lit(ExprLit { lit: Lit::Int(lit), .. }) = &depth
+        && lit.base10_parse::<usize>()? == 0 {
+            return Err(syn::Error::new_spanned(lit, "Topic history depth must be greater than 0"));
+        }
+        if history.replace(depth).is_some() {
+            return Err(syn::Error::new_spanned(attr, "Only one history depth can be specified per topic"));
+        }
+    }
+    Ok(history)
+}
+
+/// Parses a duration of a topic from its attributes, such
+/// as the lifespan of its samples or its deadline
+/// 
+/// # Format
+/// 
+/// ```text
+/// #[<Name>(<Duration>)]
+/// ```
+fn duration_from_attrs(attrs: &[Attribute], name: &str) -> syn::Result<Option<Expr>> {
+    let mut duration = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident(name)) {
+        if duration.replace(attr.parse_args::<Expr>()?).is_some() {
+            return Err(syn::Error::new_spanned(attr, format!("Only one {} can be specified per topic", name)));
+        }
+    }
+    Ok(duration)
+}
+
+/// Parses whether the samples of a topic are stamped from its attributes
+/// 
+/// # Format
+/// 
+/// ```text
+/// #[stamp]
+/// ```
+fn stamp_from_attrs(attrs: &[Attribute]) -> syn::Result<bool> {
+    let mut stamp = false;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("stamp")) {
+        attr.meta.require_path_only()?;
+        if std::mem::replace(&mut stamp, true) {
+            return Err(syn::Error::new_spanned(attr, "Only one `#[stamp]` can be specified per topic"));
+        }
+    }
+    Ok(stamp)
+}
+
+/// Parses the serialization format of a topic from its attributes,
+/// as the variant of the matching `crosstalk::Format`
+/// 
+/// # Format
+/// 
+/// ```text
+/// #[serde(bincode)]
+/// #[serde(json)]
+/// #[serde(cbor)]
+/// ```
+fn format_from_attrs(attrs: &[Attribute]) -> syn::Result<Option<syn::Ident>> {
+    let mut format = None;
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+        let ident: syn::Ident = attr.parse_args()?;
+        let variant = match ident.to_string().as_str() {
+            "bincode" => format_ident!("Bincode", span = ident.span()),
+            "json" => format_ident!("Json", span = ident.span()),
+            "cbor" => format_ident!("Cbor", span = ident.span()),
+            _ => return Err(syn::Error::new_spanned(ident, "Unknown serialization format, expected `bincode`, `json` or `cbor`")),
+        };
+        if format.replace(variant).is_some() {
+            return Err(syn::Error::new_spanned(attr, "Only one serialization format can be specified per topic"));
+        }
+    }
+    Ok(format)
+}
+
 /// Individual field for the [`crosstalk_macros::init!`] macro
 /// 
 /// # Format
 /// 
 /// ```text
 /// `<Enum>::<Variant> => <Type>`
+/// `<Enum>::<Variant> => <Type> @ <Capacity>`
+/// `#[<Kind>] <Enum>::<Variant> => <Type>`
+/// `#[history(<Depth>)] <Enum>::<Variant> => <Type>`
+/// `#[lifespan(<Duration>)] <Enum>::<Variant> => <Type>`
+/// `#[deadline(<Duration>)] <Enum>::<Variant> => <Type>`
+/// `#[stamp] <Enum>::<Variant> => <Type>`
+/// `#[serde(<Format>)] <Enum>::<Variant> => <Type>`
+/// `<Enum>::<Variant> => <Request Type> -> <Response Type>`
 /// ```
 struct NodeField {
+    kind: TopicKind,
+    history: Option<Expr>,
+    lifespan: Option<Expr>,
+    deadline: Option<Expr>,
+    stamp: bool,
+    format: Option<syn::Ident>,
     topic: Path,
     _arrow: Token![=>],
     dtype: Type,
+    response: Option<Type>,
+    capacity: Option<Expr>,
 }
 /// [`NodeField`] implementation of [`syn::parse::Parse`]
 impl Parse for NodeField {
     fn parse(input: ParseStream) -> syn::Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let kind = TopicKind::from_attrs(&attrs)?;
+        let history = history_from_attrs(&attrs)?;
+        if let Some(history) = &history
+        && !matches!(kind, TopicKind::Broadcast) {
+            return Err(syn::Error::new_spanned(history, "Only broadcast topics can have a history"));
+        }
+        let lifespan = duration_from_attrs(&attrs, "lifespan")?;
+        let deadline = duration_from_attrs(&attrs, "deadline")?;
+        let stamp = stamp_from_attrs(&attrs)?;
+        let format = format_from_attrs(&attrs)?;
+        let topic = input.parse()?;
+        let _arrow = input.parse()?;
+        let dtype = input.parse()?;
+        let response = match input.peek(Token![->]) {
+            true => {
+                let arrow = input.parse::<Token![->]>()?;
+                if !matches!(kind, TopicKind::Broadcast) {
+                    return Err(syn::Error::new_spanned(arrow, "Services can not have a topic kind"));
+                }
+                if history.is_some() {
+                    return Err(syn::Error::new_spanned(arrow, "Services can not have a history"));
+                }
+                if lifespan.is_some() {
+                    return Err(syn::Error::new_spanned(arrow, "Services can not have a lifespan"));
+                }
+                if deadline.is_some() {
+                    return Err(syn::Error::new_spanned(arrow, "Services can not have a deadline"));
+                }
+                if stamp {
+                    return Err(syn::Error::new_spanned(arrow, "Services can not be stamped"));
+                }
+                if format.is_some() {
+                    return Err(syn::Error::new_spanned(arrow, "Services can not have a serialization format"));
+                }
+                Some(input.parse()?)
+            },
+            false => None,
+        };
+        let capacity = match input.peek(Token![@]) {
+            true => {
+                input.parse::<Token![@]>()?;
+                let capacity: Expr = input.parse()?;
+                // --------------------------------------------------
+                // catch the obvious zero-capacity case at compile time,
+                // since tokio panics on a zero-capacity channel
+                // --------------------------------------------------
+                if let Expr::Lit(ExprLit { lit: Lit::Int(lit), .. }) = &capacity
+                && lit.base10_parse::<usize>()? == 0 {
+                    return Err(syn::Error::new_spanned(lit, "Topic capacity must be greater than 0"));
+                }
+                if matches!(kind, TopicKind::Watch) {
+                    return Err(syn::Error::new_spanned(capacity, "Watch topics only hold the latest sample, and can not have a capacity"));
+                }
+                Some(capacity)
+            },
+            false => None,
+        };
         Ok(NodeField {
-            topic: input.parse()?,
-            _arrow: input.parse()?,
-            dtype: input.parse()?,
+            kind,
+            history,
+            lifespan,
+            deadline,
+            stamp,
+            format,
+            topic,
+            _arrow,
+            dtype,
+            response,
+            capacity,
         })
     }
 }
@@ -329,7 +868,8 @@ impl Parse for NodeField {
 /// ```text
 /// crosstalk_macros::init!{
 ///     `<Enum>::<Variant> => <Type>`,
-///     `<Enum>::<Variant> => <Type>`,
+///     `<Enum>::<Variant> => <Type> @ <Capacity>`,
+///     `<Enum>::<Variant> => <Request Type> -> <Response Type>`,
 /// }
 /// ```
 struct NodeFields(Punctuated<NodeField, Token![,]>);
@@ -342,11 +882,103 @@ impl Parse for NodeFields {
     }
 }
 
+/// Get the `crosstalk::TopicConfig` of a topic (used in the arms of the [`crosstalk_macros::init!`] macro)
+/// 
+/// Topics not specified in the macro (the default case) use the default configuration
+fn get_topic_config(field: Option<&NodeField>, source: &TokenStream2) -> TokenStream2 {
+    let Some(field) = field else {
+        return quote! { #source TopicConfig::default() };
+    };
+    let kind = match field.kind {
+        TopicKind::Broadcast => quote! { #source TopicKind::Broadcast },
+        TopicKind::Watch => quote! { #source TopicKind::Watch },
+        TopicKind::Queue => quote! { #source __macro_exports::queue!(#source TopicKind::Queue) },
+    };
+    let capacity = match &field.capacity {
+        Some(capacity) => quote! { Some(#capacity) },
+        None => quote! { None },
+    };
+    let history = match &field.history {
+        Some(history) => quote! { #history },
+        None => quote! { 0 },
+    };
+    let lifespan = match &field.lifespan {
+        Some(lifespan) => quote! { Some(#lifespan) },
+        None => quote! { None },
+    };
+    let deadline = match &field.deadline {
+        Some(deadline) => quote! { Some(#deadline) },
+        None => quote! { None },
+    };
+    let stamp = field.stamp;
+    quote! {
+        #source TopicConfig {
+            kind: #kind,
+            capacity: #capacity,
+            history: #history,
+            lifespan: #lifespan,
+            deadline: #deadline,
+            stamp: #stamp,
+        }
+    }
+}
+
+/// Get mismatch arm (used in type-matching within the [`crosstalk_macros::init!`] macro)
+/// 
+/// This is used for topics which can never match the requested
+/// usage, e.g. publishing to a service or serving a topic
+fn get_mismatch_arm(case: Option<&Path>, err: TokenStream2) -> TokenStream2 {
+    let contents = quote! {
+        => Err(#err)
+    };
+    // --------------------------------------------------
+    // if arm not specified, use default (_)
+    // --------------------------------------------------
+    match case {
+        Some(case) => quote! { #case #contents },
+        None => quote! { _ #contents }
+    }
+}
+
+/// Get service arm (used in type-matching within the [`crosstalk_macros::init!`] macro)
+/// 
+/// This helps fill in the `match` statement in the [`crosstalk_macros::init!`] macro
+/// with all the arms that are valid for a given service and request/response datatypes
+fn get_service_arm(case: &Path, req: &Type, resp: &Type, config: &TokenStream2, source: &TokenStream2) -> TokenStream2 {
+    quote! {
+        #case => {
+            let err = #source Error::ServiceMismatch(
+                ::std::any::type_name::<(Req, Resp)>(),
+                ::std::any::type_name::<(#req, #resp)>(),
+            );
+            if ::std::any::TypeId::of::<Req>() == ::std::any::TypeId::of::<#req>()
+            && ::std::any::TypeId::of::<Resp>() == ::std::any::TypeId::of::<#resp>() {
+                // --------------------------------------------------
+                // if the datatypes match, get the service of the topic
+                // from the node, which creates the channel if needed
+                // using the configuration of the topic
+                // --------------------------------------------------
+                let tsrv = self.service::<#req, #resp>(topic, #config, err)?;
+                // --------------------------------------------------
+                // this downcasts from (#req, #resp) -> (Req, Resp).
+                // These are the same types, due to check made above
+                // --------------------------------------------------
+                #source __macro_exports::downcast::<#source Service<Req, Resp>>(Box::new(tsrv), err)
+            } else {
+                // --------------------------------------------------
+                // if the datatypes do not match, return an error
+                // --------------------------------------------------
+                Err(err)
+            }
+        }
+    }
+}
+
 /// Get publisher arm (used in type-matching within the [`crosstalk_macros::init!`] macro)
 /// 
 /// This helps fill in the `match` statement in the [`crosstalk_macros::init!`] macro
 /// with all the arms that are valid for a given topic and datatype
-fn get_publisher_arm(case: Option<&Path>, dtype: &Type, source: &TokenStream2) -> TokenStream2 {
+fn get_publisher_arm(case: Option<&Path>, dtype: &Type, config: &TokenStream2, source: &TokenStream2) -> TokenStream2 {
     let contents = quote! {
         => {
             let err = #source Error::PublisherMismatch(
@@ -356,37 +988,19 @@ fn get_publisher_arm(case: Option<&Path>, dtype: &Type, source: &TokenStream2) -
             if ::std::any::TypeId::of::<D>()
             == ::std::any::TypeId::of::<#dtype>() {
                 // --------------------------------------------------
-                // if the datatype matches, get the tokio brdcst sender
-                // to create the subscriber
+                // if the datatype matches, get the sender of the topic
+                // from the node, which creates the channel if needed
+                // using the configuration of the topic
                 // --------------------------------------------------
-                let tsen = match self.senders.contains_key(&topic) {
-                    true => {
-                        #[allow(clippy::unwrap_used)]
-                        // can't use .get here because it returns a reference,
-                        // and need to consume the value in order to downcast
-                        // it. as a result, .contains_key() is used in junction
-                        // with .remove.unwrap()
-                        let tsen_ = self.senders.remove(&topic).unwrap();
-                        let tsen_ = #source __macro_exports::downcast::<#source __macro_exports::broadcast::Sender<#dtype>>(tsen_, err)?;
-                        let tsen = tsen_.clone();
-                        self.senders.insert(topic, Box::new(tsen_));
-                        tsen
-                    },
-                    false => {
-                        // size is defined during crosstalk::BoundedNode::new(size)
-                        let (sender, _) =  #source __macro_exports::broadcast::channel::<#dtype>(self.size);
-                        self.senders.insert(topic, Box::new(sender.clone()));
-                        sender
-                    },
-                };
+                let tsen = self.sender::<#dtype>(topic, #config, err)?;
                 // --------------------------------------------------
                 // create and return publisher
                 // --------------------------------------------------
                 // this downcasts from #dtype -> D. These are the same type,
                 // due to check made above
                 // --------------------------------------------------
-                let sender = #source __macro_exports::downcast::<#source __macro_exports::broadcast::Sender<D>>(Box::new(tsen), err)?;
-                Ok(#source Publisher::new(topic, sender))
+                let sender = #source __macro_exports::downcast::<#source Sender<D>>(Box::new(tsen), err)?;
+                Ok(#source Publisher::from_sender(topic, sender))
             } else {
                 // --------------------------------------------------
                 // if the datatype does not match, return an error
@@ -404,11 +1018,33 @@ fn get_publisher_arm(case: Option<&Path>, dtype: &Type, source: &TokenStream2) -
     }
 }
 
+/// Get byte publisher or subscriber arm (used within the `crosstalk::CrosstalkBytes`
+/// implementation of the [`crosstalk_macros::init!`] macro)
+/// 
+/// This creates the typed publisher or subscriber of a topic, and erases
+/// its type with the serialization format of the topic
+fn get_byte_arm(
+    case: &Path,
+    dtype: &Type,
+    format: &syn::Ident,
+    erased: TokenStream2,
+    method: TokenStream2,
+    enum_master: &syn::Ident,
+    source: &TokenStream2,
+) -> TokenStream2 {
+    quote! {
+        #case => {
+            let typed = <Self as #source CrosstalkPubSub<#enum_master>>::#method::<#dtype>(self, topic)?;
+            Ok(#source #erased::new(typed, #source Format::#format))
+        }
+    }
+}
+
 /// Get subscriber arm (used in type-matching within the [`crosstalk_macros::init!`] macro)
 /// 
 /// This helps fill in the `match` statement in the [`crosstalk_macros::init!`] macro
 /// with all the arms that are valid for a given topic and datatype
-fn get_subscriber_arm(case: Option<&Path>, dtype: &Type, source: &TokenStream2) -> TokenStream2 {
+fn get_subscriber_arm(case: Option<&Path>, dtype: &Type, config: &TokenStream2, source: &TokenStream2) -> TokenStream2 {
     let contents = quote! {
         => {
             let err = #source Error::SubscriberMismatch(
@@ -418,37 +1054,19 @@ fn get_subscriber_arm(case: Option<&Path>, dtype: &Type, source: &TokenStream2)
             if ::std::any::TypeId::of::<D>()
             == ::std::any::TypeId::of::<#dtype>() {
                 // --------------------------------------------------
-                // if the datatype matches, get the tokio brdcst sender
-                // to create the subscriber
+                // if the datatype matches, get the sender of the topic
+                // from the node, which creates the channel if needed
+                // using the configuration of the topic
                 // --------------------------------------------------
-                let tsen = match self.senders.contains_key(&topic) {
-                    true => {
-                        #[allow(clippy::unwrap_used)]
-                        // can't use .get here because it returns a reference,
-                        // and need to consume the value in order to downcast
-                        // it. as a result, .contains_key() is used in junction
-                        // with .remove.unwrap()
-                        let tsen_ = self.senders.remove(&topic).unwrap();
-                        let tsen_ = #source __macro_exports::downcast::<#source __macro_exports::broadcast::Sender<#dtype>>(tsen_, err)?;
-                        let tsen = tsen_.clone();
-                        self.senders.insert(topic, Box::new(tsen_));
-                        tsen
-                    },
-                    false => {
-                        // size is defined during crosstalk::BoundedNode::new(size)
-                        let (sender, _) =  #source __macro_exports::broadcast::channel::<#dtype>(self.size);
-                        self.senders.insert(topic, Box::new(sender.clone()));
-                        sender
-                    },
-                };
+                let tsen = self.sender::<#dtype>(topic, #config, err)?;
                 // --------------------------------------------------
                 // create and return subscriber
                 // --------------------------------------------------
                 // this downcasts from #dtype -> D. These are the same type,
                 // due to check made above
                 // --------------------------------------------------
-                let sender = #source __macro_exports::downcast::<#source __macro_exports::broadcast::Sender<D>>(Box::new(tsen), err)?;
-                Ok(#source Subscriber::new(topic, None, ::std::sync::Arc::new(sender)))
+                let sender = #source __macro_exports::downcast::<#source Sender<D>>(Box::new(tsen), err)?;
+                Ok(#source Subscriber::from_sender(topic, sender))
             } else {
                 // --------------------------------------------------
                 // if the datatype does not match, return an error
@@ -0,0 +1,930 @@
+// --------------------------------------------------
+// external
+// --------------------------------------------------
+use std::io;
+use std::sync::Arc;
+use std::future::Future;
+use std::time::Duration;
+use std::net::SocketAddr;
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+use std::sync::atomic::{
+    AtomicU64,
+    AtomicUsize,
+    Ordering,
+};
+use tokio::sync::{
+    mpsc,
+    Mutex,
+    Notify,
+};
+use tokio::task::{
+    JoinSet,
+    JoinHandle,
+};
+use tokio::net::{
+    TcpStream,
+    TcpListener,
+    ToSocketAddrs,
+};
+#[cfg(unix)]
+use std::path::{
+    Path,
+    PathBuf,
+};
+#[cfg(unix)]
+use std::os::unix::fs::{
+    FileTypeExt,
+    PermissionsExt,
+    DirBuilderExt,
+};
+#[cfg(unix)]
+use tokio::net::{
+    UnixStream,
+    UnixListener,
+};
+use tokio::io::{
+    AsyncRead,
+    AsyncWrite,
+    AsyncReadExt,
+    AsyncWriteExt,
+};
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use crate::{
+    Format,
+    BoundedNode,
+    BytePublisher,
+    CrosstalkBytes,
+    CrosstalkTopic,
+    ImplementedBoundedNode,
+};
+
+/// Sent by both ends of a connection before any frame,
+/// followed by the version of the wire format
+const MAGIC: &[u8; 5] = b"XTLK\x02";
+
+/// The default maximum size of a frame, see [`Bridge::max_frame`]
+const MAX_FRAME: usize = 1 << 20;
+
+/// The maximum size of the frame declaring the forwarded topics, which is sent
+/// when connecting regardless of [`Bridge::max_frame`], see [`Links::declaration`]
+const MAX_DECLARATION: usize = 1 << 20;
+
+/// The maximum number of topics whose dropped samples are logged per
+/// connection, since the names of inbound topics are chosen by the peer
+const WARNED_TOPICS: usize = 256;
+
+/// The number of frames buffered per connection before
+/// the forwarded subscribers wait for the socket
+const BUFFERED_FRAMES: usize = 64;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// The direction a topic is forwarded in by a [`Bridge`]
+pub enum Direction {
+    /// Samples received from the peer are published to the local node
+    Inbound,
+    /// Samples published to the local node are sent to the peer
+    Outbound,
+    /// Both [`Direction::Inbound`] and [`Direction::Outbound`]
+    Both,
+}
+/// [`Direction`] implementation
+impl Direction {
+    #[inline(always)]
+    /// Whether samples received from the peer are published locally
+    fn inbound(self) -> bool {
+        matches!(self, Direction::Inbound | Direction::Both)
+    }
+
+    #[inline(always)]
+    /// Whether samples published locally are sent to the peer
+    fn outbound(self) -> bool {
+        matches!(self, Direction::Outbound | Direction::Both)
+    }
+}
+
+/// Links a [`BoundedNode`] to the node of another process over TCP (or a Unix domain
+/// socket on Unix), forwarding the chosen topics so that subscribers on one side
+/// receive what publishers on the other side write, with the `bridge` feature
+/// 
+/// Only topics declared with `#[serde(<Format>)]` in [`crosstalk_macros::init!`]
+/// can be forwarded. Samples are matched to topics by [`CrosstalkTopic::name`], and
+/// samples of topics which are not forwarded inbound are dropped by the receiving side.
+/// Both ends exchange the format and data type of their forwarded topics when they
+/// connect, and a topic which the peer declares with another format or data type
+/// is not forwarded over that connection (which is logged under the `log` or
+/// `tracing` features), since its samples would not decode into the same values.
+/// 
+/// Samples received over a bridge are never sent back over it, so that two
+/// bridged nodes do not echo samples back and forth. This also means that a
+/// listening bridge does not relay samples between the peers connected to it.
+/// 
+/// # Attributes
+/// 
+/// * `node` - the local node
+/// * `topics` - the forwarded topics, and their directions
+/// * `reconnect` - the time between attempts to (re)connect to the peer
+/// * `max_frame` - the maximum size of a frame sent or received, see [`Bridge::max_frame`]
+/// * `mode` - the permissions of the socket of [`Bridge::listen_unix`]
+/// 
+/// # Examples
+/// 
+/// ```
+/// use crosstalk::AsTopic;
+/// use crosstalk::{Bridge, Direction};
+/// 
+/// #[derive(AsTopic)]
+/// enum House {
+///     Thermostat,
+/// }
+/// 
+/// crosstalk::init! {
+///     #[serde(bincode)]
+///     House::Thermostat => f32,
+/// }
+/// 
+/// #[tokio::main]
+/// async fn main() {
+///     // process A
+///     let mut node_a = crosstalk::BoundedNode::<House>::new(10);
+///     let publisher = node_a.publisher::<f32>(House::Thermostat).await.unwrap();
+///     let bridge_a = Bridge::new(&node_a)
+///         .topic(House::Thermostat, Direction::Outbound)
+///         .listen("127.0.0.1:0")
+///         .await
+///         .unwrap();
+/// 
+///     // process B
+///     let mut node_b = crosstalk::BoundedNode::<House>::new(10);
+///     let mut subscriber = node_b.subscriber::<f32>(House::Thermostat).await.unwrap();
+///     let bridge_b = Bridge::new(&node_b)
+///         .topic(House::Thermostat, Direction::Inbound)
+///         .connect(bridge_a.local_addr().unwrap())
+///         .await
+///         .unwrap();
+/// 
+///     bridge_b.wait_connected().await;
+///     publisher.write(21.5);
+///     assert_eq!(subscriber.read().await, Some(21.5));
+/// }
+/// ```
+pub struct Bridge<T> {
+    node: BoundedNode<T>,
+    topics: Vec<(T, Direction)>,
+    reconnect: Duration,
+    max_frame: usize,
+    #[cfg(unix)]
+    mode: u32,
+}
+/// [`Bridge`] implementation
+impl<T> Bridge<T>
+where
+    T: CrosstalkTopic + Send + Sync + 'static,
+    ImplementedBoundedNode<T>: CrosstalkBytes<T>,
+{
+    #[inline(always)]
+    /// Constructs a new [`Bridge`] of a node, without any topics
+    /// 
+    /// # Arguments
+    /// 
+    /// * `node` - the local node
+    pub fn new(node: &BoundedNode<T>) -> Self {
+        Self {
+            node: BoundedNode {
+                node: node.node.clone(),
+                size: node.size,
+            },
+            topics: Vec::new(),
+            reconnect: Duration::from_millis(500),
+            max_frame: MAX_FRAME,
+            #[cfg(unix)]
+            mode: 0o600,
+        }
+    }
+
+    #[inline(always)]
+    /// Forwards a topic in the given direction
+    /// 
+    /// Adding the same topic again replaces its direction
+    /// 
+    /// # Arguments
+    /// 
+    /// * `topic` - the topic to forward
+    /// * `direction` - the direction to forward the topic in
+    pub fn topic(mut self, topic: T, direction: Direction) -> Self {
+        self.topics.retain(|(t, _)| *t != topic);
+        self.topics.push((topic, direction));
+        self
+    }
+
+    #[inline(always)]
+    /// Sets the time between attempts to (re)connect to the peer,
+    /// which defaults to 500ms
+    /// 
+    /// # Arguments
+    /// 
+    /// * `interval` - the time between attempts
+    pub fn reconnect(mut self, interval: Duration) -> Self {
+        self.reconnect = interval;
+        self
+    }
+
+    #[inline(always)]
+    /// Sets the maximum size of a frame (a serialized sample and the
+    /// name of its topic), which defaults to 1MiB
+    /// 
+    /// Samples too large to be sent are dropped, and a peer sending a larger
+    /// frame is disconnected. This bounds the memory a peer can make the
+    /// bridge allocate, since peers are not authenticated
+    /// 
+    /// # Arguments
+    /// 
+    /// * `bytes` - the maximum size of a frame
+    pub fn max_frame(mut self, bytes: usize) -> Self {
+        self.max_frame = bytes;
+        self
+    }
+
+    /// Listens for peers on a TCP address, forwarding the topics to every
+    /// peer which connects, until the returned [`BridgeHandle`] is closed or dropped
+    /// 
+    /// # Arguments
+    /// 
+    /// * `addr` - the address to listen on, where port `0` picks a free port
+    ///   (see [`BridgeHandle::local_addr`])
+    /// 
+    /// # Errors
+    /// 
+    /// * if the address can not be bound
+    /// * if a topic can not be forwarded, wrapping a [`crate::Error`] (e.g. [`crate::Error::NotSerializable`])
+    pub async fn listen<A: ToSocketAddrs>(self, addr: A) -> io::Result<BridgeHandle> {
+        let listener = TcpListener::bind(addr).await?;
+        let local_addr = listener.local_addr()?;
+        let mut handle = self.accepting(listener).await?;
+        handle.local_addr = Some(local_addr);
+        Ok(handle)
+    }
+
+    /// Connects to a peer listening on a TCP address (see [`Bridge::listen`]),
+    /// forwarding the topics until the returned [`BridgeHandle`] is closed or dropped
+    /// 
+    /// The connection is made in the background, and is re-established
+    /// whenever it is lost, see [`Bridge::reconnect`]
+    /// 
+    /// # Arguments
+    /// 
+    /// * `addr` - the address of the peer
+    /// 
+    /// # Errors
+    /// 
+    /// * if the address can not be resolved
+    /// * if a topic can not be forwarded, wrapping a [`crate::Error`] (e.g. [`crate::Error::NotSerializable`])
+    pub async fn connect<A: ToSocketAddrs>(self, addr: A) -> io::Result<BridgeHandle> {
+        let addr = tokio::net::lookup_host(addr).await?
+            .next()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no address to connect to"))?;
+        self.connecting(move || async move {
+            let stream = TcpStream::connect(addr).await?;
+            let _ = stream.set_nodelay(true);
+            Ok(stream)
+        }).await
+    }
+
+    #[cfg(unix)]
+    #[inline(always)]
+    /// Sets the permissions of the socket created by [`Bridge::listen_unix`], which
+    /// defaults to `0o600` so that only processes of the same user can connect
+    /// 
+    /// Connecting to a Unix domain socket requires write permission on it, so
+    /// e.g. `0o660` grants access to the group of the socket as well
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mode` - the permissions of the socket
+    pub fn mode(mut self, mode: u32) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    #[cfg(unix)]
+    /// Listens for peers on a Unix domain socket, forwarding the topics to every
+    /// peer which connects, until the returned [`BridgeHandle`] is closed or dropped,
+    /// which also removes the socket
+    /// 
+    /// The wire format is the same as [`Bridge::listen`], while access is controlled by
+    /// the permissions of the socket (see [`Bridge::mode`]), which are set before the
+    /// socket appears at `path`. A socket left behind by a process which exited
+    /// without closing its bridge is replaced, while any other file is not
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - the path of the socket
+    /// 
+    /// # Errors
+    /// 
+    /// * if the socket can not be created, or another bridge is listening on it
+    /// * if a topic can not be forwarded, wrapping a [`crate::Error`] (e.g. [`crate::Error::NotSerializable`])
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// use crosstalk::{Bridge, Direction};
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Thermostat,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     #[serde(bincode)]
+    ///     House::Thermostat => f32,
+    /// }
+    /// 
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let path = std::env::temp_dir().join(format!("crosstalk-{}.sock", std::process::id()));
+    /// 
+    ///     // process A
+    ///     let mut node_a = crosstalk::BoundedNode::<House>::new(10);
+    ///     let publisher = node_a.publisher::<f32>(House::Thermostat).await.unwrap();
+    ///     let bridge_a = Bridge::new(&node_a)
+    ///         .topic(House::Thermostat, Direction::Outbound)
+    ///         .listen_unix(&path)
+    ///         .await
+    ///         .unwrap();
+    /// 
+    ///     // process B
+    ///     let mut node_b = crosstalk::BoundedNode::<House>::new(10);
+    ///     let mut subscriber = node_b.subscriber::<f32>(House::Thermostat).await.unwrap();
+    ///     let bridge_b = Bridge::new(&node_b)
+    ///         .topic(House::Thermostat, Direction::Inbound)
+    ///         .connect_unix(&path)
+    ///         .await
+    ///         .unwrap();
+    /// 
+    ///     bridge_b.wait_connected().await;
+    ///     publisher.write(21.5);
+    ///     assert_eq!(subscriber.read().await, Some(21.5));
+    /// }
+    /// ```
+    pub async fn listen_unix<P: AsRef<Path>>(self, path: P) -> io::Result<BridgeHandle> {
+        let path = path.as_ref().to_path_buf();
+        let listener = bind_unix(&path, self.mode)?;
+        match self.accepting(listener).await {
+            Ok(mut handle) => {
+                handle.path = Some(path);
+                Ok(handle)
+            },
+            Err(e) => {
+                let _ = std::fs::remove_file(&path);
+                Err(e)
+            },
+        }
+    }
+
+    #[cfg(unix)]
+    /// Connects to a peer listening on a Unix domain socket (see [`Bridge::listen_unix`]),
+    /// forwarding the topics until the returned [`BridgeHandle`] is closed or dropped
+    /// 
+    /// The connection is made in the background, and is re-established
+    /// whenever it is lost, see [`Bridge::reconnect`]
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - the path of the socket of the peer
+    /// 
+    /// # Errors
+    /// 
+    /// * if a topic can not be forwarded, wrapping a [`crate::Error`] (e.g. [`crate::Error::NotSerializable`])
+    pub async fn connect_unix<P: AsRef<Path>>(self, path: P) -> io::Result<BridgeHandle> {
+        let path = path.as_ref().to_path_buf();
+        self.connecting(move || UnixStream::connect(path.clone())).await
+    }
+
+    /// Forwards the topics to every peer accepted by a listener
+    async fn accepting<L: Accept>(self, listener: L) -> io::Result<BridgeHandle> {
+        let reconnect = self.reconnect;
+        let links = self.links().await?;
+        let connections = links.connections.clone();
+        let task = tokio::spawn(async move {
+            let mut sessions = JoinSet::new();
+            loop {
+                match listener.accept().await {
+                    Ok(stream) => {
+                        let links = links.clone();
+                        sessions.spawn(async move {
+                            if let Err(e) = links.serve(stream).await {
+                                disconnected(e);
+                            }
+                        });
+                    },
+                    Err(e) => {
+                        disconnected(e);
+                        tokio::time::sleep(reconnect).await;
+                    },
+                }
+                while sessions.try_join_next().is_some() {}
+            }
+        });
+        Ok(BridgeHandle::new(connections, task))
+    }
+
+    /// Forwards the topics to a peer, connecting to it
+    /// again whenever the connection is lost
+    async fn connecting<F, C, S>(self, connect: F) -> io::Result<BridgeHandle>
+    where
+        F: Fn() -> C + Send + 'static,
+        C: Future<Output = io::Result<S>> + Send,
+        S: AsyncRead + AsyncWrite + Send,
+    {
+        let reconnect = self.reconnect;
+        let links = self.links().await?;
+        let connections = links.connections.clone();
+        let task = tokio::spawn(async move {
+            loop {
+                let res = match connect().await {
+                    Ok(stream) => links.serve(stream).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = res {
+                    disconnected(e);
+                }
+                tokio::time::sleep(reconnect).await;
+            }
+        });
+        Ok(BridgeHandle::new(connections, task))
+    }
+
+    /// Creates the publishers of the inbound topics, and checks
+    /// that every outbound topic can be forwarded
+    /// 
+    /// The publishers are shared by every connection, so
+    /// that losing a connection does not close the topics
+    async fn links(self) -> io::Result<Arc<Links<T>>> {
+        let mut inbound = HashMap::new();
+        let mut outbound = Vec::new();
+        let mut declared = HashMap::new();
+        let mut node = self.node.node.lock().await;
+        for (topic, direction) in self.topics {
+            if direction.inbound() {
+                let publisher = node.byte_publisher(topic).map_err(io::Error::other)?;
+                declared.insert(topic.name(), (publisher.format(), publisher.data_type()));
+                inbound.insert(topic.name(), publisher);
+            }
+            if direction.outbound() {
+                let subscriber = node.byte_subscriber(topic).map_err(io::Error::other)?;
+                declared.insert(topic.name(), (subscriber.format(), subscriber.data_type()));
+                outbound.push(topic);
+            }
+        }
+        drop(node);
+        Ok(Arc::new(Links {
+            node: self.node.node,
+            inbound,
+            outbound,
+            declared,
+            max_frame: self.max_frame,
+            connections: Arc::new(Connections::default()),
+        }))
+    }
+}
+
+/// A listener accepting the connections of the peers of a [`Bridge`]
+trait Accept: Send + 'static {
+    type Stream: AsyncRead + AsyncWrite + Send + 'static;
+
+    /// Accepts the connection of the next peer
+    fn accept(&self) -> impl Future<Output = io::Result<Self::Stream>> + Send;
+}
+/// [`TcpListener`] implementation of [`Accept`]
+impl Accept for TcpListener {
+    type Stream = TcpStream;
+
+    async fn accept(&self) -> io::Result<TcpStream> {
+        let (stream, _) = TcpListener::accept(self).await?;
+        let _ = stream.set_nodelay(true);
+        Ok(stream)
+    }
+}
+
+#[cfg(unix)]
+/// [`UnixListener`] implementation of [`Accept`]
+impl Accept for UnixListener {
+    type Stream = UnixStream;
+
+    async fn accept(&self) -> io::Result<UnixStream> {
+        let (stream, _) = UnixListener::accept(self).await?;
+        Ok(stream)
+    }
+}
+
+/// The forwarded topics of a [`Bridge`], shared by all of its connections
+/// 
+/// # Attributes
+/// 
+/// * `node` - the local node
+/// * `inbound` - the publishers of the inbound topics, by name
+/// * `outbound` - the outbound topics
+/// * `declared` - the format and data type of every forwarded topic, by name,
+///   which are sent to the peer when connecting
+/// * `max_frame` - the maximum size of a frame, see [`Bridge::max_frame`]
+/// * `connections` - the live connections of the bridge
+struct Links<T> {
+    node: Arc<Mutex<ImplementedBoundedNode<T>>>,
+    inbound: HashMap<&'static str, BytePublisher<T>>,
+    outbound: Vec<T>,
+    declared: HashMap<&'static str, (Format, &'static str)>,
+    max_frame: usize,
+    connections: Arc<Connections>,
+}
+/// [`Links`] implementation
+impl<T> Links<T>
+where
+    T: CrosstalkTopic + Send + Sync + 'static,
+    ImplementedBoundedNode<T>: CrosstalkBytes<T>,
+{
+    /// Forwards the topics over a connection, until it is closed by the peer
+    /// 
+    /// # Arguments
+    /// 
+    /// * `stream` - the connection to the peer
+    /// 
+    /// # Errors
+    /// 
+    /// * if the peer is not a [`Bridge`], or sends a malformed frame
+    /// * if the forwarded topics do not fit in a frame
+    /// * if the connection fails
+    async fn serve<S>(&self, stream: S) -> io::Result<()>
+    where
+        S: AsyncRead + AsyncWrite + Send,
+    {
+        let (mut reader, mut writer) = tokio::io::split(stream);
+
+        // every outbound subscriber forwards its samples to the writer,
+        // skipping the samples received over this bridge
+        let (tx, mut rx) = mpsc::channel::<(&'static str, Vec<u8>)>(BUFFERED_FRAMES);
+        let mut forwarding = JoinSet::new();
+        for topic in &self.outbound {
+            let mut subscriber = self.node.lock().await.byte_subscriber(*topic).map_err(io::Error::other)?;
+            let echo = self.inbound.get(topic.name()).map(BytePublisher::id);
+            let tx = tx.clone();
+            forwarding.spawn(async move {
+                while let Some(sample) = subscriber.read_sample().await {
+                    if Some(sample.publisher) != echo && tx.send((subscriber.topic.name(), sample.data)).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
+
+        // the handshake is only sent once the subscribers exist, so that once both ends
+        // received it, every sample published on either end is forwarded. It declares
+        // the forwarded topics in a frame without a topic name
+        writer.write_all(MAGIC).await?;
+        if !write_frame(&mut writer, "", self.declaration().as_bytes(), MAX_DECLARATION).await? {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "the forwarded topics do not fit in a frame"));
+        }
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic).await?;
+        if &magic != MAGIC {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "the peer is not a crosstalk bridge"));
+        }
+        let frame = read_frame(&mut reader, MAX_DECLARATION).await?
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "the peer closed the connection during the handshake"))?;
+        let mismatched = self.mismatched(split_frame(&frame)?.1)?;
+        let _connection = self.connections.connect();
+
+        let writing = async {
+            let mut warned = HashSet::new();
+            while let Some((name, data)) = rx.recv().await {
+                if mismatched.contains(name) {
+                    continue;
+                }
+                if !write_frame(&mut writer, name, &data, self.max_frame).await? {
+                    dropped(name, &mut warned);
+                }
+            }
+            // the node was shut down, but samples can still be received
+            std::future::pending::<io::Result<()>>().await
+        };
+        let reading = async {
+            let mut warned = HashSet::new();
+            while let Some(frame) = read_frame(&mut reader, self.max_frame).await? {
+                let (name, data) = split_frame(&frame)?;
+                match self.inbound.get(name) {
+                    // mismatched topics were logged during the handshake
+                    Some(_) if mismatched.contains(name) => {},
+                    // serialization errors are logged by the publisher
+                    Some(publisher) => { let _ = publisher.publish_bytes(data); },
+                    None => dropped(name, &mut warned),
+                }
+            }
+            Ok(())
+        };
+        tokio::select! {
+            res = writing => res,
+            res = reading => res,
+        }
+    }
+
+    /// Declares the forwarded topics to the peer, with a line per topic made
+    /// of its name, format and data type, separated by tabs
+    fn declaration(&self) -> String {
+        self.declared.iter()
+            .map(|(name, (format, data_type))| format!("{}\t{:?}\t{}\n", name, format, data_type))
+            .collect()
+    }
+
+    /// The forwarded topics which the peer declares with another format
+    /// or data type, logging them under the `log` or `tracing` features
+    /// 
+    /// See [`Links::declaration`]
+    fn mismatched(&self, declaration: &[u8]) -> io::Result<HashSet<&'static str>> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid topic declaration");
+        let mut mismatched = HashSet::new();
+        for line in std::str::from_utf8(declaration).map_err(|_| invalid())?.lines() {
+            let mut fields = line.split('\t');
+            let (Some(name), Some(format), Some(data_type)) = (fields.next(), fields.next(), fields.next()) else {
+                return Err(invalid());
+            };
+            if let Some((name, (ours, our_type))) = self.declared.get_key_value(name)
+            && (format != format!("{:?}", ours) || data_type != *our_type) {
+                mismatch(name, format, data_type);
+                mismatched.insert(*name);
+            }
+        }
+        Ok(mismatched)
+    }
+}
+
+/// Closes a [`Bridge`] when dropped, see [`Bridge::listen`] and [`Bridge::connect`]
+/// (or [`Bridge::listen_unix`] and [`Bridge::connect_unix`])
+/// 
+/// # Attributes
+/// 
+/// * `local_addr` - the address a listening bridge is bound to
+/// * `path` - the socket a bridge listening on a Unix domain socket is bound to
+/// * `connections` - the live connections of the bridge
+/// * `task` - the task accepting or (re)establishing connections
+pub struct BridgeHandle {
+    local_addr: Option<SocketAddr>,
+    #[cfg(unix)]
+    path: Option<PathBuf>,
+    connections: Arc<Connections>,
+    task: JoinHandle<()>,
+}
+/// [`BridgeHandle`] implementation
+impl BridgeHandle {
+    #[inline(always)]
+    /// Constructs a new [`BridgeHandle`] of a running bridge
+    fn new(connections: Arc<Connections>, task: JoinHandle<()>) -> Self {
+        Self {
+            local_addr: None,
+            #[cfg(unix)]
+            path: None,
+            connections,
+            task,
+        }
+    }
+
+    #[inline(always)]
+    /// The address a listening bridge is bound to, or
+    /// [`None`] if the bridge connects to a peer
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.local_addr
+    }
+
+    #[cfg(unix)]
+    #[inline(always)]
+    /// The socket a bridge listening on a Unix domain socket is bound
+    /// to (see [`Bridge::listen_unix`]), or [`None`] otherwise
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    #[inline(always)]
+    /// The number of peers currently connected to the bridge
+    pub fn connections(&self) -> usize {
+        self.connections.count()
+    }
+
+    /// Waits until at least one peer is connected to the bridge,
+    /// returning immediately if one already is
+    /// 
+    /// Samples published on either end after the connection is established
+    /// are forwarded, while samples published before are not
+    pub async fn wait_connected(&self) {
+        self.connections.wait().await
+    }
+
+    #[inline(always)]
+    /// Closes the bridge, disconnecting every peer
+    /// 
+    /// Equivalent to dropping the handle
+    pub fn close(self) {}
+}
+/// [`BridgeHandle`] implementation of [`Drop`]
+impl Drop for BridgeHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+        #[cfg(unix)]
+        if let Some(path) = &self.path {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// The live connections of a [`Bridge`]
+/// 
+/// # Attributes
+/// 
+/// * `count` - the number of live connections
+/// * `notify` - notified whenever a connection is established
+#[derive(Default)]
+struct Connections {
+    count: AtomicUsize,
+    notify: Notify,
+}
+/// [`Connections`] implementation
+impl Connections {
+    #[inline(always)]
+    /// Registers a new connection, which is live until the returned [`Connection`] is dropped
+    fn connect(self: &Arc<Self>) -> Connection {
+        self.count.fetch_add(1, Ordering::AcqRel);
+        self.notify.notify_waiters();
+        Connection(self.clone())
+    }
+
+    #[inline(always)]
+    /// The number of live connections
+    fn count(&self) -> usize {
+        self.count.load(Ordering::Acquire)
+    }
+
+    /// Waits until there is at least one live connection
+    async fn wait(&self) {
+        loop {
+            // the notification is enabled before checking, so
+            // that it is not missed if it happens in between
+            let notified = self.notify.notified();
+            let mut notified = std::pin::pin!(notified);
+            notified.as_mut().enable();
+            if self.count() > 0 {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// A live connection of a [`Bridge`], unregistered when dropped
+struct Connection(Arc<Connections>);
+/// [`Connection`] implementation of [`Drop`]
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.0.count.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// Writes a frame, made of its length, the length of the topic
+/// name, the topic name and the serialized sample
+/// 
+/// Returns `false` if the sample is too large for a frame of at most
+/// `max_frame` bytes, in which case it is dropped
+async fn write_frame<W>(writer: &mut W, name: &str, data: &[u8], max_frame: usize) -> io::Result<bool>
+where
+    W: AsyncWrite + Unpin,
+{
+    let len = 2 + name.len() + data.len();
+    if len > max_frame || len > u32::MAX as usize || name.len() > u16::MAX as usize {
+        return Ok(false);
+    }
+    let mut frame = Vec::with_capacity(4 + len);
+    frame.extend_from_slice(&(len as u32).to_be_bytes());
+    frame.extend_from_slice(&(name.len() as u16).to_be_bytes());
+    frame.extend_from_slice(name.as_bytes());
+    frame.extend_from_slice(data);
+    writer.write_all(&frame).await?;
+    Ok(true)
+}
+
+/// Reads a frame of at most `max_frame` bytes, or [`None`]
+/// if the peer closed the connection
+/// 
+/// See [`write_frame`]
+async fn read_frame<R>(reader: &mut R, max_frame: usize) -> io::Result<Option<Vec<u8>>>
+where
+    R: AsyncRead + Unpin,
+{
+    let len = match reader.read_u32().await {
+        Ok(len) => len as usize,
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    if !(2..=max_frame).contains(&len) {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "invalid frame length"));
+    }
+    let mut frame = vec![0; len];
+    reader.read_exact(&mut frame).await?;
+    Ok(Some(frame))
+}
+
+/// Splits a frame read with [`read_frame`] into its topic name and serialized sample
+fn split_frame(frame: &[u8]) -> io::Result<(&str, &[u8])> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid frame");
+    let (len, rest) = frame.split_at(2);
+    let len = u16::from_be_bytes([len[0], len[1]]) as usize;
+    if len > rest.len() {
+        return Err(invalid());
+    }
+    let (name, data) = rest.split_at(len);
+    let name = std::str::from_utf8(name).map_err(|_| invalid())?;
+    Ok((name, data))
+}
+
+#[cfg(unix)]
+/// Binds a Unix domain socket at `path`, with the permissions `mode`
+/// 
+/// The socket is bound in a new directory which only the current user can access,
+/// and is only linked to `path` once its permissions are set, so that no other
+/// process can connect to it in between. Linking fails if `path` already exists,
+/// unless it is a socket left behind by a bridge which is no longer listening
+fn bind_unix(path: &Path, mode: u32) -> io::Result<UnixListener> {
+    static STAGING: AtomicU64 = AtomicU64::new(0);
+    let name = path.file_name().ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "the socket path has no file name"))?;
+    let parent = path.parent().filter(|parent| !parent.as_os_str().is_empty()).unwrap_or(Path::new("."));
+    let staging = parent.join(format!(
+        ".{}.{}.{}",
+        name.to_string_lossy(),
+        std::process::id(),
+        STAGING.fetch_add(1, Ordering::Relaxed),
+    ));
+    std::fs::DirBuilder::new().mode(0o700).create(&staging)?;
+    let staged = staging.join("socket");
+    let res = UnixListener::bind(&staged).and_then(|listener| {
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(mode))?;
+        match std::fs::hard_link(&staged, path) {
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists && is_stale(path) => {
+                std::fs::remove_file(path)?;
+                std::fs::hard_link(&staged, path)?;
+            },
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => return Err(io::Error::new(io::ErrorKind::AddrInUse, e)),
+            res => res?,
+        }
+        Ok(listener)
+    });
+    let _ = std::fs::remove_file(&staged);
+    let _ = std::fs::remove_dir(&staging);
+    res
+}
+
+#[cfg(unix)]
+/// Whether a Unix domain socket was left behind by a bridge which is no longer listening
+fn is_stale(path: &Path) -> bool {
+    std::fs::symlink_metadata(path).is_ok_and(|metadata| metadata.file_type().is_socket())
+        && std::os::unix::net::UnixStream::connect(path).is_err_and(|e| e.kind() == io::ErrorKind::ConnectionRefused)
+}
+
+#[inline(always)]
+/// Logs a lost connection under the `log` or `tracing` features
+fn disconnected(err: io::Error) {
+    #[cfg(feature = "log")]
+    log::warn!("crosstalk bridge disconnected: {}", err);
+    #[cfg(feature = "tracing")]
+    tracing::warn!("crosstalk bridge disconnected: {}", err);
+    #[cfg(not(any(feature = "log", feature = "tracing")))]
+    let _ = err;
+}
+
+/// Logs a topic which is not forwarded, since the peer declares it with another
+/// format or data type, under the `log` or `tracing` features
+fn mismatch(name: &str, format: &str, data_type: &str) {
+    #[cfg(feature = "log")]
+    log::warn!("crosstalk bridge does not forward {}, which the peer declares as {} with {}", name, data_type, format);
+    #[cfg(feature = "tracing")]
+    tracing::warn!("crosstalk bridge does not forward {}, which the peer declares as {} with {}", name, data_type, format);
+    #[cfg(not(any(feature = "log", feature = "tracing")))]
+    let _ = (name, format, data_type);
+}
+
+/// Logs a sample which could not be forwarded under the `log` or `tracing` features
+/// 
+/// Every topic is only logged once per connection (in `warned`), so
+/// that a topic which is not forwarded does not flood the log
+fn dropped(name: &str, warned: &mut HashSet<String>) {
+    if warned.len() >= WARNED_TOPICS || warned.contains(name) {
+        return;
+    }
+    warned.insert(name.to_string());
+    #[cfg(feature = "log")]
+    log::warn!("crosstalk bridge dropped a sample of {} (further samples of it are not logged)", name);
+    #[cfg(feature = "tracing")]
+    tracing::warn!("crosstalk bridge dropped a sample of {} (further samples of it are not logged)", name);
+}
@@ -0,0 +1,603 @@
+// --------------------------------------------------
+// external
+// --------------------------------------------------
+use std::pin::Pin;
+use std::future::Future;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use crate::{
+    Error,
+    Sample,
+    Publisher,
+    Subscriber,
+    BoundedNode,
+    UnboundedNode,
+    CrosstalkData,
+    CrosstalkTopic,
+    ImplementedBoundedNode,
+    ImplementedUnboundedNode,
+};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// The serialization format of a topic, as declared with
+/// `#[serde(<Format>)]` in [`crosstalk_macros::init!`]
+pub enum Format {
+    /// [`bincode`], declared with `#[serde(bincode)]`
+    Bincode,
+    /// JSON through [`serde_json`], declared with `#[serde(json)]`
+    Json,
+    /// CBOR through [`ciborium`], declared with `#[serde(cbor)]`
+    Cbor,
+}
+/// [`Format`] implementation
+impl Format {
+    /// Serializes a sample
+    /// 
+    /// # Errors
+    /// 
+    /// [`Error::Serialization`] if the sample can not be represented in the format,
+    /// where the cause is logged under the `log` or `tracing` features
+    pub fn encode<D: Serialize>(&self, data: &D) -> Result<Vec<u8>, Error> {
+        match self {
+            Format::Bincode => bincode::serialize(data).map_err(serialization),
+            Format::Json => serde_json::to_vec(data).map_err(serialization),
+            Format::Cbor => {
+                let mut bytes = Vec::new();
+                ciborium::into_writer(data, &mut bytes).map_err(serialization)?;
+                Ok(bytes)
+            },
+        }
+    }
+
+    /// Deserializes a sample
+    /// 
+    /// # Errors
+    /// 
+    /// [`Error::Serialization`] if the bytes are not a valid sample in the format,
+    /// where the cause is logged under the `log` or `tracing` features
+    pub fn decode<D: DeserializeOwned>(&self, bytes: &[u8]) -> Result<D, Error> {
+        match self {
+            Format::Bincode => bincode::deserialize(bytes).map_err(serialization),
+            Format::Json => serde_json::from_slice(bytes).map_err(serialization),
+            Format::Cbor => ciborium::from_reader(bytes).map_err(serialization),
+        }
+    }
+}
+
+/// A trait to get type-erased publishers and subscribers of the
+/// topics declared with `#[serde(<Format>)]`, with the `serde` feature
+/// 
+/// This is implemented by the [`crosstalk_macros::init!`] macro
+pub trait CrosstalkBytes<T> {
+    /// Gets a [`BytePublisher`] of the given topic, creating the topic if needed
+    /// 
+    /// Fails with [`Error::NotSerializable`] if the topic was not
+    /// declared with a serialization format
+    fn byte_publisher(&mut self, topic: T) -> Result<BytePublisher<T>, Error>;
+
+    /// Gets a [`ByteSubscriber`] of the given topic, creating the topic if needed
+    /// 
+    /// Fails with [`Error::NotSerializable`] if the topic was not
+    /// declared with a serialization format
+    fn byte_subscriber(&mut self, topic: T) -> Result<ByteSubscriber<T>, Error>;
+}
+
+/// Deserializes and publishes a sample, erasing the data type of a [`Publisher`]
+type Publish = Box<dyn Fn(&[u8]) -> Result<(), Error> + Send + Sync>;
+
+/// A type-erased [`Publisher`], which publishes serialized samples
+/// 
+/// Produced with [`BoundedNode::byte_publisher`] or [`UnboundedNode::byte_publisher`]
+/// 
+/// # Attributes
+/// 
+/// * `topic` - the topic of the publisher
+/// * `format` - the serialization format of the topic
+/// * `data_type` - the name of the data type of the topic
+/// * `id` - the id of the publisher, see [`Publisher::id`]
+/// * `publish` - deserializes and publishes a sample
+pub struct BytePublisher<T> {
+    pub topic: T,
+    format: Format,
+    data_type: &'static str,
+    id: u64,
+    publish: Publish,
+}
+/// [`BytePublisher`] implementation
+impl<T: CrosstalkTopic> BytePublisher<T> {
+    #[inline(always)]
+    /// Erases the data type of a [`Publisher`]
+    /// 
+    /// This is not meant to be used directly, and is called
+    /// from the arms generated by [`crosstalk_macros::init!`]
+    pub fn new<D>(publisher: Publisher<D, T>, format: Format) -> Self
+    where
+        D: CrosstalkData + Sync + DeserializeOwned,
+        T: Send + Sync + 'static,
+    {
+        Self {
+            topic: publisher.topic,
+            format,
+            data_type: std::any::type_name::<D>(),
+            id: publisher.id(),
+            publish: Box::new(move |bytes| format.decode(bytes).map(|data| publisher.write(data))),
+        }
+    }
+
+    #[inline(always)]
+    /// The serialization format of the topic
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    #[inline(always)]
+    /// The name of the data type of the topic
+    pub fn data_type(&self) -> &'static str {
+        self.data_type
+    }
+
+    #[inline(always)]
+    /// The process-unique id of the publisher, see [`Publisher::id`]
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    #[inline(always)]
+    /// Deserializes a sample and publishes it, see [`Publisher::write`]
+    /// 
+    /// # Arguments
+    /// 
+    /// * `bytes` - the serialized sample
+    /// 
+    /// # Errors
+    /// 
+    /// [`Error::Serialization`] if the bytes are not a valid sample of the topic
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Thermostat,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     #[serde(json)]
+    ///     House::Thermostat => Vec<f32>,
+    /// }
+    /// 
+    /// let mut node = crosstalk::BoundedNode::<House>::new(10);
+    /// let mut subscriber = node.subscriber_blocking::<Vec<f32>>(House::Thermostat).unwrap();
+    /// let publisher = node.byte_publisher_blocking(House::Thermostat).unwrap();
+    /// publisher.publish_bytes(b"[21.5, 22.0]").unwrap();
+    /// assert_eq!(subscriber.try_read(), Some(vec![21.5, 22.0]));
+    /// assert!(publisher.publish_bytes(b"hot").is_err());
+    /// ```
+    pub fn publish_bytes(&self, bytes: &[u8]) -> Result<(), Error> {
+        (self.publish)(bytes)
+    }
+}
+
+/// A type-erased [`Subscriber`], which reads serialized samples
+/// 
+/// Produced with [`BoundedNode::byte_subscriber`] or [`UnboundedNode::byte_subscriber`]
+/// 
+/// # Attributes
+/// 
+/// * `topic` - the topic of the subscriber
+/// * `format` - the serialization format of the topic
+/// * `data_type` - the name of the data type of the topic
+/// * `subscriber` - the subscriber, along with how to serialize its samples
+pub struct ByteSubscriber<T> {
+    pub topic: T,
+    format: Format,
+    data_type: &'static str,
+    subscriber: Box<dyn ReadBytes + Send>,
+}
+/// [`ByteSubscriber`] implementation
+impl<T: CrosstalkTopic> ByteSubscriber<T> {
+    #[inline(always)]
+    /// Erases the data type of a [`Subscriber`]
+    /// 
+    /// This is not meant to be used directly, and is called
+    /// from the arms generated by [`crosstalk_macros::init!`]
+    pub fn new<D>(subscriber: Subscriber<D, T>, format: Format) -> Self
+    where
+        D: CrosstalkData + Sync + Serialize,
+        T: Send + 'static,
+    {
+        Self {
+            topic: subscriber.topic,
+            format,
+            data_type: std::any::type_name::<D>(),
+            subscriber: Box::new(Encoder { subscriber, format }),
+        }
+    }
+
+    #[inline(always)]
+    /// The serialization format of the topic
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    #[inline(always)]
+    /// The name of the data type of the topic
+    pub fn data_type(&self) -> &'static str {
+        self.data_type
+    }
+
+    #[inline(always)]
+    /// Asynchronous blocking read of a serialized sample, see [`Subscriber::read`]
+    /// 
+    /// Samples which can not be serialized are skipped, and
+    /// logged under the `log` or `tracing` features
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Thermostat,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     #[serde(cbor)]
+    ///     House::Thermostat => f32,
+    /// }
+    /// 
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut node = crosstalk::BoundedNode::<House>::new(10);
+    ///     let mut subscriber = node.byte_subscriber(House::Thermostat).await.unwrap();
+    ///     let publisher = node.publisher::<f32>(House::Thermostat).await.unwrap();
+    ///     publisher.write(21.5);
+    /// 
+    ///     let bytes = subscriber.read().await.unwrap();
+    ///     assert_eq!(subscriber.format().decode::<f32>(&bytes).unwrap(), 21.5);
+    /// }
+    /// ```
+    pub async fn read(&mut self) -> Option<Vec<u8>> {
+        self.subscriber.read().await.map(Sample::into_inner)
+    }
+
+    #[inline(always)]
+    /// Non-blocking read of a serialized sample, see [`Subscriber::try_read`]
+    /// 
+    /// Samples which can not be serialized are skipped, and
+    /// logged under the `log` or `tracing` features
+    pub fn try_read(&mut self) -> Option<Vec<u8>> {
+        self.subscriber.try_read().map(Sample::into_inner)
+    }
+
+    #[inline(always)]
+    /// Sequential blocking read of a serialized sample, see [`Subscriber::read_blocking`]
+    /// 
+    /// Samples which can not be serialized are skipped, and
+    /// logged under the `log` or `tracing` features
+    pub fn read_blocking(&mut self) -> Option<Vec<u8>> {
+        self.subscriber.read_blocking().map(Sample::into_inner)
+    }
+
+    #[inline(always)]
+    /// Asynchronous blocking read of a serialized sample, along with
+    /// where and when it was published, see [`Subscriber::read_sample`]
+    pub async fn read_sample(&mut self) -> Option<Sample<Vec<u8>>> {
+        self.subscriber.read().await
+    }
+
+    #[inline(always)]
+    /// Non-blocking read of a serialized sample, along with where
+    /// and when it was published, see [`Subscriber::try_read_sample`]
+    pub fn try_read_sample(&mut self) -> Option<Sample<Vec<u8>>> {
+        self.subscriber.try_read()
+    }
+
+    #[inline(always)]
+    /// Sequential blocking read of a serialized sample, along with where
+    /// and when it was published, see [`Subscriber::read_blocking_sample`]
+    pub fn read_blocking_sample(&mut self) -> Option<Sample<Vec<u8>>> {
+        self.subscriber.read_blocking()
+    }
+}
+
+/// A pending read of a serialized sample
+type Read<'a> = Pin<Box<dyn Future<Output = Option<Sample<Vec<u8>>>> + Send + 'a>>;
+
+/// The reads of a [`ByteSubscriber`], erasing the data type of its [`Subscriber`]
+trait ReadBytes {
+    fn read(&mut self) -> Read<'_>;
+
+    fn try_read(&mut self) -> Option<Sample<Vec<u8>>>;
+
+    fn read_blocking(&mut self) -> Option<Sample<Vec<u8>>>;
+}
+
+/// A [`Subscriber`] along with how to serialize its samples
+/// 
+/// # Attributes
+/// 
+/// * `subscriber` - the subscriber
+/// * `format` - the serialization format of the topic
+struct Encoder<D, T> {
+    subscriber: Subscriber<D, T>,
+    format: Format,
+}
+/// [`Encoder`] implementation
+impl<D: Serialize, T> Encoder<D, T> {
+    #[inline(always)]
+    /// Serializes a sample, keeping where and when it was published
+    fn encode(&self, sample: Sample<D>) -> Result<Sample<Vec<u8>>, Error> {
+        Ok(Sample {
+            data: self.format.encode(&sample.data)?,
+            publisher: sample.publisher,
+            stamp: sample.stamp,
+        })
+    }
+}
+/// [`Encoder`] implementation of [`ReadBytes`]
+impl<D, T> ReadBytes for Encoder<D, T>
+where
+    D: CrosstalkData + Sync + Serialize,
+    T: CrosstalkTopic + Send,
+{
+    fn read(&mut self) -> Read<'_> {
+        Box::pin(async move {
+            loop {
+                let sample = self.subscriber.read_sample().await?;
+                if let Ok(sample) = self.encode(sample) {
+                    return Some(sample);
+                }
+            }
+        })
+    }
+
+    fn try_read(&mut self) -> Option<Sample<Vec<u8>>> {
+        loop {
+            let sample = self.subscriber.try_read_sample()?;
+            if let Ok(sample) = self.encode(sample) {
+                return Some(sample);
+            }
+        }
+    }
+
+    fn read_blocking(&mut self) -> Option<Sample<Vec<u8>>> {
+        loop {
+            let sample = self.subscriber.read_blocking_sample()?;
+            if let Ok(sample) = self.encode(sample) {
+                return Some(sample);
+            }
+        }
+    }
+}
+
+/// [`BoundedNode`] implementation of serialization
+impl<T> BoundedNode<T>
+where
+    T: CrosstalkTopic,
+    ImplementedBoundedNode<T>: CrosstalkBytes<T>,
+{
+    #[inline(always)]
+    /// Creates a new [`BytePublisher`] for the given topic `T`, which
+    /// publishes serialized samples without knowing the data type of the topic
+    /// 
+    /// # Arguments
+    /// 
+    /// * `topic` - the topic to create a publisher for
+    /// 
+    /// # Errors
+    /// 
+    /// * [`Error::NotSerializable`] - if the topic was not declared with `#[serde(<Format>)]`
+    /// * [`Error::NodeShutdown`] - if the node has been shut down
+    pub async fn byte_publisher(&mut self, topic: T) -> Result<BytePublisher<T>, Error> {
+        self.node.lock().await.byte_publisher(topic)
+    }
+
+    #[inline(always)]
+    /// Creates a new [`BytePublisher`] for the given topic `T`
+    /// 
+    /// See [`BoundedNode::byte_publisher`]
+    pub fn byte_publisher_blocking(&mut self, topic: T) -> Result<BytePublisher<T>, Error> {
+        self.node.blocking_lock().byte_publisher(topic)
+    }
+
+    #[inline(always)]
+    /// Creates a new [`ByteSubscriber`] for the given topic `T`, which
+    /// reads serialized samples without knowing the data type of the topic
+    /// 
+    /// # Arguments
+    /// 
+    /// * `topic` - the topic to create a subscriber for
+    /// 
+    /// # Errors
+    /// 
+    /// * [`Error::NotSerializable`] - if the topic was not declared with `#[serde(<Format>)]`
+    /// * [`Error::NodeShutdown`] - if the node has been shut down
+    pub async fn byte_subscriber(&mut self, topic: T) -> Result<ByteSubscriber<T>, Error> {
+        self.node.lock().await.byte_subscriber(topic)
+    }
+
+    #[inline(always)]
+    /// Creates a new [`ByteSubscriber`] for the given topic `T`
+    /// 
+    /// See [`BoundedNode::byte_subscriber`]
+    pub fn byte_subscriber_blocking(&mut self, topic: T) -> Result<ByteSubscriber<T>, Error> {
+        self.node.blocking_lock().byte_subscriber(topic)
+    }
+
+    #[inline(always)]
+    /// Deserializes a sample and publishes it to the given topic `T`,
+    /// without knowing the data type of the topic
+    /// 
+    /// The node creates a [`BytePublisher`] for the topic on the first call and
+    /// keeps it, so that tools publishing to many topics need not keep one each.
+    /// It counts as a publisher of the topic (see [`crate::TopicInfo::publishers`]),
+    /// which stays open until it is closed with [`BoundedNode::close_topic`] or
+    /// the node is shut down, as the kept publisher is never dropped before
+    /// 
+    /// # Arguments
+    /// 
+    /// * `topic` - the topic to publish to
+    /// * `bytes` - the serialized sample
+    /// 
+    /// # Errors
+    /// 
+    /// * [`Error::NotSerializable`] - if the topic was not declared with `#[serde(<Format>)]`
+    /// * [`Error::Serialization`] - if the bytes are not a valid sample of the topic
+    /// * [`Error::NodeShutdown`] - if the node has been shut down
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Thermostat,
+    ///     Bedroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     #[serde(json)]
+    ///     House::Thermostat => Vec<f32>,
+    ///     House::Bedroom => String,
+    /// }
+    /// 
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut node = crosstalk::BoundedNode::<House>::new(10);
+    ///     let mut subscriber = node.subscriber::<Vec<f32>>(House::Thermostat).await.unwrap();
+    ///     node.publish_bytes(House::Thermostat, b"[21.5, 22.0]").await.unwrap();
+    ///     node.publish_bytes(House::Thermostat, b"[23.0]").await.unwrap();
+    ///     assert_eq!(subscriber.read().await, Some(vec![21.5, 22.0]));
+    ///     assert_eq!(subscriber.read().await, Some(vec![23.0]));
+    /// 
+    ///     let res = node.publish_bytes(House::Bedroom, b"\"hello\"").await;
+    ///     assert!(matches!(res, Err(crosstalk::Error::NotSerializable)));
+    /// }
+    /// ```
+    pub async fn publish_bytes(&mut self, topic: T, bytes: &[u8]) -> Result<(), Error> {
+        self.node.lock().await.publish_bytes(topic, bytes)
+    }
+
+    #[inline(always)]
+    /// Deserializes a sample and publishes it to the given topic `T`
+    /// 
+    /// See [`BoundedNode::publish_bytes`]
+    pub fn publish_bytes_blocking(&mut self, topic: T, bytes: &[u8]) -> Result<(), Error> {
+        self.node.blocking_lock().publish_bytes(topic, bytes)
+    }
+}
+
+/// [`UnboundedNode`] implementation of serialization
+impl<T> UnboundedNode<T>
+where
+    T: CrosstalkTopic,
+    ImplementedUnboundedNode<T>: CrosstalkBytes<T>,
+{
+    #[inline(always)]
+    /// Creates a new [`BytePublisher`] for the given topic `T`
+    /// 
+    /// See [`BoundedNode::byte_publisher`]
+    pub async fn byte_publisher(&mut self, topic: T) -> Result<BytePublisher<T>, Error> {
+        self.node.lock().await.byte_publisher(topic)
+    }
+
+    #[inline(always)]
+    /// Creates a new [`BytePublisher`] for the given topic `T`
+    /// 
+    /// See [`BoundedNode::byte_publisher`]
+    pub fn byte_publisher_blocking(&mut self, topic: T) -> Result<BytePublisher<T>, Error> {
+        self.node.blocking_lock().byte_publisher(topic)
+    }
+
+    #[inline(always)]
+    /// Creates a new [`ByteSubscriber`] for the given topic `T`
+    /// 
+    /// See [`BoundedNode::byte_subscriber`]
+    pub async fn byte_subscriber(&mut self, topic: T) -> Result<ByteSubscriber<T>, Error> {
+        self.node.lock().await.byte_subscriber(topic)
+    }
+
+    #[inline(always)]
+    /// Creates a new [`ByteSubscriber`] for the given topic `T`
+    /// 
+    /// See [`BoundedNode::byte_subscriber`]
+    pub fn byte_subscriber_blocking(&mut self, topic: T) -> Result<ByteSubscriber<T>, Error> {
+        self.node.blocking_lock().byte_subscriber(topic)
+    }
+
+    #[inline(always)]
+    /// Deserializes a sample and publishes it to the given topic `T`
+    /// 
+    /// See [`BoundedNode::publish_bytes`]
+    pub async fn publish_bytes(&mut self, topic: T, bytes: &[u8]) -> Result<(), Error> {
+        self.node.lock().await.publish_bytes(topic, bytes)
+    }
+
+    #[inline(always)]
+    /// Deserializes a sample and publishes it to the given topic `T`
+    /// 
+    /// See [`BoundedNode::publish_bytes`]
+    pub fn publish_bytes_blocking(&mut self, topic: T, bytes: &[u8]) -> Result<(), Error> {
+        self.node.blocking_lock().publish_bytes(topic, bytes)
+    }
+}
+
+/// [`ImplementedBoundedNode`] implementation of serialization
+impl<T> ImplementedBoundedNode<T>
+where
+    T: CrosstalkTopic,
+    Self: CrosstalkBytes<T>,
+{
+    /// Publishes a serialized sample through the [`BytePublisher`] kept for
+    /// the topic, creating it first if needed
+    /// 
+    /// See [`BoundedNode::publish_bytes`]
+    pub fn publish_bytes(&mut self, topic: T, bytes: &[u8]) -> Result<(), Error> {
+        if !self.byte_publishers.contains_key(&topic) {
+            let publisher = self.byte_publisher(topic)?;
+            self.byte_publishers.insert(topic, publisher);
+        }
+        self.byte_publishers[&topic].publish_bytes(bytes)
+    }
+}
+
+/// [`ImplementedUnboundedNode`] implementation of serialization
+impl<T> ImplementedUnboundedNode<T>
+where
+    T: CrosstalkTopic,
+    Self: CrosstalkBytes<T>,
+{
+    /// Publishes a serialized sample through the [`BytePublisher`] kept for
+    /// the topic, creating it first if needed
+    /// 
+    /// See [`BoundedNode::publish_bytes`]
+    pub fn publish_bytes(&mut self, topic: T, bytes: &[u8]) -> Result<(), Error> {
+        if !self.byte_publishers.contains_key(&topic) {
+            let publisher = self.byte_publisher(topic)?;
+            self.byte_publishers.insert(topic, publisher);
+        }
+        self.byte_publishers[&topic].publish_bytes(bytes)
+    }
+}
+
+#[inline(always)]
+/// Converts the error of a serialization format into an
+/// [`Error::Serialization`], logging it under the `log` or `tracing` features
+fn serialization(err: impl std::fmt::Display) -> Error {
+    #[cfg(feature = "log")]
+    log::error!("{}", err);
+    #[cfg(feature = "tracing")]
+    tracing::error!("{}", err);
+    #[cfg(not(any(feature = "log", feature = "tracing")))]
+    let _ = err;
+    Error::Serialization
+}
@@ -0,0 +1,202 @@
+// --------------------------------------------------
+// external
+// --------------------------------------------------
+use std::time::Duration;
+use tokio::time::Instant;
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use crate::{
+    Publisher,
+    Subscriber,
+};
+
+/// The deadline of a periodic topic, as seen by one of its
+/// publishers or subscribers
+/// 
+/// # Attributes
+/// 
+/// * `period` - the maximum time between two samples, see [`crate::TopicConfig::deadline`]
+/// * `last` - when the last sample was published or received, or when the deadline was last missed
+/// * `missed` - the number of deadlines missed
+pub(crate) struct Deadline {
+    period: Duration,
+    last: Instant,
+    missed: u64,
+}
+/// [`Deadline`] implementation
+impl Deadline {
+    #[inline(always)]
+    /// Constructs a new [`Deadline`], starting now
+    pub(crate) fn new(period: Duration) -> Self {
+        Self {
+            period,
+            last: Instant::now(),
+            missed: 0,
+        }
+    }
+
+    #[inline(always)]
+    /// The time left until the deadline is missed
+    pub(crate) fn remaining(&self) -> Duration {
+        (self.last + self.period).saturating_duration_since(Instant::now())
+    }
+
+    #[inline(always)]
+    /// Whether the deadline has passed
+    pub(crate) fn overdue(&self) -> bool {
+        self.last.elapsed() > self.period
+    }
+
+    #[inline(always)]
+    /// Records a sample, returning whether it missed the deadline
+    pub(crate) fn met(&mut self) -> bool {
+        let missed = self.overdue();
+        if missed {
+            self.missed += 1;
+        }
+        self.last = Instant::now();
+        missed
+    }
+
+    #[inline(always)]
+    /// Restarts the deadline from a sample published `at`, without
+    /// counting whether it missed the deadline
+    pub(crate) fn heard(&mut self, at: Instant) {
+        self.last = self.last.max(at);
+    }
+
+    #[inline(always)]
+    /// Records a missed deadline without any sample, restarting the period
+    /// so that the next deadline is only missed after another `period`
+    pub(crate) fn miss(&mut self) {
+        self.missed += 1;
+        self.last = Instant::now();
+    }
+}
+
+/// [`Publisher`] implementation of deadlines
+impl<D, T> Publisher<D, T> {
+    #[inline(always)]
+    /// The maximum time between two samples of the publisher, or
+    /// [`None`] if the topic has no deadline
+    /// 
+    /// Declared with `#[deadline(<Duration>)]` in [`crosstalk_macros::init!`]
+    pub fn deadline(&self) -> Option<Duration> {
+        self.deadline.as_ref().map(|deadline| lock(deadline).period)
+    }
+
+    #[inline(always)]
+    /// Whether the publisher is late, i.e. its deadline passed since its last
+    /// sample (or since it was created), meaning its next sample will miss it
+    /// 
+    /// Always `false` if the topic has no deadline
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Thermostat,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     #[deadline(std::time::Duration::from_millis(10))]
+    ///     House::Thermostat => f32,
+    /// }
+    /// 
+    /// #[tokio::main(flavor = "current_thread", start_paused = true)]
+    /// async fn main() {
+    ///     let mut node = crosstalk::BoundedNode::<House>::new(10);
+    ///     let publisher = node.publisher::<f32>(House::Thermostat).await.unwrap();
+    ///     assert!(!publisher.is_late());
+    ///     tokio::time::advance(std::time::Duration::from_millis(20)).await;
+    ///     assert!(publisher.is_late());
+    /// 
+    ///     publisher.write(21.5);
+    ///     assert!(!publisher.is_late());
+    ///     assert_eq!(publisher.deadlines_missed(), 1);
+    /// }
+    /// ```
+    pub fn is_late(&self) -> bool {
+        self.deadline.as_ref().is_some_and(|deadline| lock(deadline).overdue())
+    }
+
+    #[inline(always)]
+    /// The number of samples the publisher wrote after its deadline
+    /// had passed, since it was created
+    pub fn deadlines_missed(&self) -> u64 {
+        self.deadline.as_ref().map_or(0, |deadline| lock(deadline).missed)
+    }
+
+    #[inline(always)]
+    /// Records a sample against the deadline of the publisher, if any
+    pub(crate) fn wrote(&self) {
+        if let Some(deadline) = &self.deadline
+        && lock(deadline).met() {
+            #[cfg(feature = "metrics")]
+            self.buf.metrics.deadline_missed();
+        }
+    }
+}
+
+/// [`Subscriber`] implementation of deadlines
+impl<D: Clone, T: Clone> Subscriber<D, T> {
+    #[inline(always)]
+    /// The maximum time between two samples received by the subscriber,
+    /// or [`None`] if the topic has no deadline
+    /// 
+    /// Declared with `#[deadline(<Duration>)]` in [`crosstalk_macros::init!`]
+    pub fn deadline(&self) -> Option<Duration> {
+        self.lag.deadline.as_ref().map(|deadline| deadline.period)
+    }
+
+    #[inline(always)]
+    /// The number of deadlines missed by the topic, as observed by this subscriber
+    /// since it was created
+    /// 
+    /// A deadline is missed every time a read or receive finds no sample published
+    /// within the deadline of the previous one. Receives (e.g. [`Subscriber::recv`])
+    /// return [`crate::RecvError::DeadlineMissed`], while reads (e.g. [`Subscriber::read`])
+    /// only count it, and keep waiting for the next sample. Blocking reads (e.g. [`Subscriber::read_blocking`]) wait for the next sample
+    /// regardless, as they can not time out without a [`tokio`] runtime
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Thermostat,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     #[deadline(std::time::Duration::from_millis(10))]
+    ///     House::Thermostat => f32,
+    /// }
+    /// 
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut node = crosstalk::BoundedNode::<House>::new(10);
+    ///     let (publisher, mut subscriber) = node.pubsub(House::Thermostat).await.unwrap();
+    ///     publisher.write(21.5f32);
+    ///     assert_eq!(subscriber.recv().await, Ok(21.5));
+    ///     assert_eq!(subscriber.recv().await, Err(crosstalk::RecvError::DeadlineMissed));
+    ///     assert_eq!(subscriber.deadlines_missed(), 1);
+    /// }
+    /// ```
+    pub fn deadlines_missed(&self) -> u64 {
+        self.lag.deadline.as_ref().map_or(0, |deadline| deadline.missed)
+    }
+}
+
+#[inline(always)]
+/// Locks the deadline of a publisher, ignoring poisoning since
+/// the deadline is never left inconsistent
+fn lock(deadline: &std::sync::Mutex<Deadline>) -> std::sync::MutexGuard<'_, Deadline> {
+    deadline.lock().unwrap_or_else(|e| e.into_inner())
+}
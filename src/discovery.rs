@@ -0,0 +1,655 @@
+// --------------------------------------------------
+// external
+// --------------------------------------------------
+use std::io;
+use std::sync::Arc;
+use std::time::{
+    Instant,
+    Duration,
+};
+use std::collections::{
+    HashMap,
+    HashSet,
+};
+use std::net::{
+    Ipv4Addr,
+    SocketAddr,
+    SocketAddrV4,
+};
+use std::hash::BuildHasher;
+use std::sync::atomic::{
+    AtomicU64,
+    Ordering,
+};
+use tokio::sync::{
+    Mutex,
+    Notify,
+};
+use tokio::net::UdpSocket;
+use tokio::task::JoinHandle;
+use socket2::{
+    Type,
+    Domain,
+    Socket,
+    Protocol,
+};
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use crate::{
+    TopicInfo,
+    BoundedNode,
+    CrosstalkTopic,
+    ImplementedBoundedNode,
+};
+#[cfg(feature = "bridge")]
+use crate::{
+    Bridge,
+    Direction,
+    BridgeHandle,
+    CrosstalkBytes,
+};
+
+/// The first line of every announcement, followed by the version of the format
+const MAGIC: &str = "XTLK 1";
+
+/// The largest announcement which fits in a UDP datagram
+const MAX_ANNOUNCEMENT: usize = 65507;
+
+/// The number of discoveries created by this process, to tell them apart
+static NEXT_DISCOVERY: AtomicU64 = AtomicU64::new(0);
+
+#[cfg(feature = "bridge")]
+/// A pending [`Bridge::listen`] or [`Bridge::connect`] of a [`Discovery`]
+type Linking = std::pin::Pin<Box<dyn std::future::Future<Output = io::Result<BridgeHandle>> + Send>>;
+
+#[cfg(feature = "bridge")]
+/// Bridges a node to its peers, erasing the bounds of [`Bridge`]
+/// 
+/// # Attributes
+/// 
+/// * `topics` - the linked topics, and their directions
+/// * `listen` - listens for peers on an address
+/// * `connect` - connects to a peer at an address
+struct Link<T> {
+    topics: Vec<(T, Direction)>,
+    listen: Box<dyn Fn(SocketAddr) -> Linking + Send + Sync>,
+    connect: Box<dyn Fn(SocketAddr) -> Linking + Send + Sync>,
+}
+
+/// Discovers the nodes of other processes which share topics with a [`BoundedNode`],
+/// by announcing its topics over UDP multicast, with the `discovery` feature
+/// 
+/// Every announcement holds the name of the topic enum, and the name and data type of
+/// every topic the node has created so far (see [`BoundedNode::topics`]). A peer is
+/// reported (see [`DiscoveryHandle::peers`]) once it announced at least one topic of
+/// the node, with the same data type for every topic both have. Peers with a different
+/// topic enum, or which disagree on a data type, are ignored.
+/// 
+/// With the `bridge` feature, matching peers can also be linked with a
+/// [`Bridge`], see [`Discovery::link`]
+/// 
+/// # Attributes
+/// 
+/// * `node` - the local node
+/// * `group` - the multicast group and port announcements are sent to
+/// * `interface` - the address of the interface announcements are sent and received on
+/// * `interval` - the time between two announcements
+/// * `link` - how to bridge the node to its peers, with the `bridge` feature
+/// 
+/// # Examples
+/// 
+/// ```no_run
+/// use crosstalk::AsTopic;
+/// use crosstalk::Discovery;
+/// 
+/// #[derive(AsTopic)]
+/// enum House {
+///     Thermostat,
+/// }
+/// 
+/// crosstalk::init! {
+///     House::Thermostat => f32,
+/// }
+/// 
+/// #[tokio::main]
+/// async fn main() {
+///     let mut node = crosstalk::BoundedNode::<House>::new(10);
+///     let _subscriber = node.subscriber::<f32>(House::Thermostat).await.unwrap();
+///     let discovery = Discovery::new(&node).start().await.unwrap();
+///     let peer = discovery.wait_for_peer().await;
+///     assert!(peer.topics.contains(&House::Thermostat));
+/// }
+/// ```
+pub struct Discovery<T> {
+    node: BoundedNode<T>,
+    group: SocketAddrV4,
+    interface: Ipv4Addr,
+    interval: Duration,
+    #[cfg(feature = "bridge")]
+    link: Option<Link<T>>,
+}
+/// [`Discovery`] implementation
+impl<T> Discovery<T>
+where
+    T: CrosstalkTopic + Send + Sync + 'static,
+{
+    #[inline(always)]
+    /// Constructs a new [`Discovery`] of a node, announcing to the multicast group
+    /// `239.255.0.76:7476` on every interface, every second
+    /// 
+    /// # Arguments
+    /// 
+    /// * `node` - the local node
+    pub fn new(node: &BoundedNode<T>) -> Self {
+        Self {
+            node: BoundedNode {
+                node: node.node.clone(),
+                size: node.size,
+            },
+            group: SocketAddrV4::new(Ipv4Addr::new(239, 255, 0, 76), 7476),
+            interface: Ipv4Addr::UNSPECIFIED,
+            interval: Duration::from_secs(1),
+            #[cfg(feature = "bridge")]
+            link: None,
+        }
+    }
+
+    #[inline(always)]
+    /// Sets the multicast group and port announcements are sent to,
+    /// which must be the same for every peer
+    /// 
+    /// # Arguments
+    /// 
+    /// * `group` - the multicast group and port
+    pub fn group(mut self, group: SocketAddrV4) -> Self {
+        self.group = group;
+        self
+    }
+
+    #[inline(always)]
+    /// Sets the address of the interface announcements are sent and received on,
+    /// e.g. `127.0.0.1` to only discover peers on the same host
+    /// 
+    /// # Arguments
+    /// 
+    /// * `interface` - the address of the interface
+    pub fn interface(mut self, interface: Ipv4Addr) -> Self {
+        self.interface = interface;
+        self
+    }
+
+    #[inline(always)]
+    /// Sets the time between two announcements, which defaults to 1s
+    /// 
+    /// Peers which have not announced themselves for three intervals are
+    /// forgotten. The interval must not be zero, see [`Discovery::start`]
+    /// 
+    /// # Arguments
+    /// 
+    /// * `interval` - the time between two announcements
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Starts announcing the node and listening for peers, until
+    /// the returned [`DiscoveryHandle`] is closed or dropped
+    /// 
+    /// # Errors
+    /// 
+    /// * if the interval is zero, see [`Discovery::interval`]
+    /// * if the multicast group can not be joined on the interface
+    /// * if the bridge of [`Discovery::link`] can not listen on the interface
+    pub async fn start(self) -> io::Result<DiscoveryHandle<T>> {
+        if self.interval.is_zero() {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "the interval of a discovery must not be zero"));
+        }
+        let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP))?;
+        // every node of the host binds the same port
+        socket.set_reuse_address(true)?;
+        #[cfg(all(unix, not(any(target_os = "solaris", target_os = "illumos", target_os = "cygwin"))))]
+        socket.set_reuse_port(true)?;
+        socket.bind(&SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, self.group.port()).into())?;
+        socket.join_multicast_v4(self.group.ip(), &self.interface)?;
+        socket.set_multicast_if_v4(&self.interface)?;
+        socket.set_multicast_loop_v4(true)?;
+        socket.set_nonblocking(true)?;
+        let socket = UdpSocket::from_std(socket.into())?;
+
+        let id = std::hash::RandomState::new().hash_one((std::process::id(), NEXT_DISCOVERY.fetch_add(1, Ordering::Relaxed)));
+        // links are only made over loopback, unless an interface was chosen
+        #[cfg(feature = "bridge")]
+        let interface = match self.interface.is_unspecified() {
+            true => Ipv4Addr::LOCALHOST,
+            false => self.interface,
+        };
+        #[cfg(feature = "bridge")]
+        let (bridge, link) = match self.link {
+            Some(link) => {
+                let listening = (link.listen)(SocketAddr::new(interface.into(), 0)).await?;
+                (listening.local_addr().map(|addr| addr.port()), Some((link, listening, HashMap::new())))
+            },
+            None => (None, None),
+        };
+        #[cfg(not(feature = "bridge"))]
+        let bridge = None;
+        let peers = Arc::new(Peers::default());
+        let announcer = Announcer {
+            id,
+            node: self.node.node,
+            socket,
+            group: self.group,
+            bridge,
+            #[cfg(feature = "bridge")]
+            link,
+            #[cfg(feature = "bridge")]
+            interface,
+        };
+        let task = tokio::spawn(announcer.run(peers.clone(), self.interval));
+        Ok(DiscoveryHandle { id, peers, task })
+    }
+}
+#[cfg(feature = "bridge")]
+/// [`Discovery`] implementation of links, with the `bridge` feature
+impl<T> Discovery<T>
+where
+    T: CrosstalkTopic + Send + Sync + 'static,
+    ImplementedBoundedNode<T>: CrosstalkBytes<T>,
+{
+    /// Links a topic with every matching peer which links it as well, in the given direction
+    /// 
+    /// Once a topic is linked, the node listens with a [`Bridge`] on the interface
+    /// (see [`Discovery::interface`]) and announces its port, and every pair of matching
+    /// peers is linked by a single connection, made by the peer with the lower id.
+    /// Links are closed once their peer no longer matches or is forgotten, and
+    /// links which could not be made are retried on the next announcement of the peer
+    /// 
+    /// Neither announcements nor bridges are authenticated, so every process which can
+    /// reach the interface can publish to the linked topics, and can make the node connect
+    /// to an address of its choosing by announcing it. Without an explicit interface, the
+    /// bridge therefore only listens on, and only links peers announcing from, `127.0.0.1`.
+    /// Choose an interface only on networks where every host is trusted
+    /// 
+    /// Adding the same topic again replaces its direction
+    /// 
+    /// # Arguments
+    /// 
+    /// * `topic` - the topic to link
+    /// * `direction` - the direction to forward the topic in, see [`Bridge::topic`]
+    pub fn link(mut self, topic: T, direction: Direction) -> Self {
+        let mut topics = self.link.take().map(|link| link.topics).unwrap_or_default();
+        topics.retain(|(t, _)| *t != topic);
+        topics.push((topic, direction));
+        let (node, size) = (self.node.node.clone(), self.node.size);
+        let bridge = {
+            let topics = topics.clone();
+            move || {
+                let node = BoundedNode { node: node.clone(), size };
+                topics.iter().fold(Bridge::new(&node), |bridge, (topic, direction)| bridge.topic(*topic, *direction))
+            }
+        };
+        self.link = Some(Link {
+            topics,
+            listen: Box::new({
+                let bridge = bridge.clone();
+                move |addr| Box::pin(bridge().listen(addr))
+            }),
+            connect: Box::new(move |addr| Box::pin(bridge().connect(addr))),
+        });
+        self
+    }
+}
+
+/// A node of another process discovered by a [`Discovery`]
+/// 
+/// # Attributes
+/// 
+/// * `id` - the id of the peer, unique to its [`Discovery`]
+/// * `addr` - the address the peer announces itself from
+/// * `bridge` - the address the peer listens on for links, see [`Discovery::link`]
+/// * `topics` - the topics of the node which the peer has as well
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Peer<T> {
+    pub id: u64,
+    pub addr: SocketAddr,
+    pub bridge: Option<SocketAddr>,
+    pub topics: Vec<T>,
+}
+
+/// Stops a [`Discovery`] when dropped, see [`Discovery::start`]
+/// 
+/// # Attributes
+/// 
+/// * `id` - the id of the discovery, as seen by its peers
+/// * `peers` - the matching peers
+/// * `task` - the task announcing the node and listening for peers
+pub struct DiscoveryHandle<T> {
+    id: u64,
+    peers: Arc<Peers<T>>,
+    task: JoinHandle<()>,
+}
+/// [`DiscoveryHandle`] implementation
+impl<T: Clone> DiscoveryHandle<T> {
+    #[inline(always)]
+    /// The id of the discovery, as seen by its peers (see [`Peer::id`])
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    #[inline(always)]
+    /// The peers which currently match the node
+    pub fn peers(&self) -> Vec<Peer<T>> {
+        self.peers.lock().values().map(|(peer, _)| peer.clone()).collect()
+    }
+
+    /// Waits until a peer matches the node, returning it
+    /// 
+    /// Returns immediately if a peer already matches
+    pub async fn wait_for_peer(&self) -> Peer<T> {
+        loop {
+            // the notification is enabled before checking, so
+            // that it is not missed if it happens in between
+            let notified = self.peers.notify.notified();
+            let mut notified = std::pin::pin!(notified);
+            notified.as_mut().enable();
+            if let Some(peer) = self.peers().into_iter().next() {
+                return peer;
+            }
+            notified.await;
+        }
+    }
+
+    #[inline(always)]
+    /// Stops the discovery, closing its links
+    /// 
+    /// Equivalent to dropping the handle
+    pub fn close(self) {}
+}
+/// [`DiscoveryHandle`] implementation of [`Drop`]
+impl<T> Drop for DiscoveryHandle<T> {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// The peers matching a node, by id
+/// 
+/// # Attributes
+/// 
+/// * `peers` - the matching peers, and when they last announced themselves
+/// * `notify` - notified whenever a peer starts matching
+struct Peers<T> {
+    peers: std::sync::Mutex<HashMap<u64, (Peer<T>, Instant)>>,
+    notify: Notify,
+}
+/// [`Peers`] implementation of [`Default`]
+impl<T> Default for Peers<T> {
+    fn default() -> Self {
+        Self {
+            peers: std::sync::Mutex::new(HashMap::new()),
+            notify: Notify::new(),
+        }
+    }
+}
+/// [`Peers`] implementation
+impl<T> Peers<T> {
+    #[inline(always)]
+    /// Locks the peers, ignoring poisoning since they are never left inconsistent
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<u64, (Peer<T>, Instant)>> {
+        self.peers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Records the announcement of a peer, returning whether it started matching
+    /// 
+    /// A peer which no longer matches is forgotten
+    fn heard(&self, peer: Option<Peer<T>>, id: u64) -> bool {
+        let mut peers = self.lock();
+        match peer {
+            Some(peer) => {
+                let new = peers.insert(id, (peer, Instant::now())).is_none();
+                drop(peers);
+                if new {
+                    self.notify.notify_waiters();
+                }
+                new
+            },
+            None => {
+                peers.remove(&id);
+                false
+            },
+        }
+    }
+
+    /// Forgets the peers which have not announced themselves within `expiry`,
+    /// returning their ids
+    fn expire(&self, expiry: Duration) -> Vec<u64> {
+        let mut peers = self.lock();
+        let expired = peers.iter()
+            .filter(|(_, (_, heard))| heard.elapsed() > expiry)
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        for id in &expired {
+            peers.remove(id);
+        }
+        expired
+    }
+}
+
+/// The state of the task of a [`Discovery`]
+/// 
+/// # Attributes
+/// 
+/// * `id` - the id of the discovery
+/// * `node` - the local node
+/// * `socket` - the socket joined to the multicast group
+/// * `group` - the multicast group and port
+/// * `bridge` - the port the node listens on for links
+/// * `link` - how to link peers, the listening bridge and the links by peer id, with the `bridge` feature
+/// * `interface` - the interface links are made on, with the `bridge` feature
+struct Announcer<T> {
+    id: u64,
+    node: Arc<Mutex<ImplementedBoundedNode<T>>>,
+    socket: UdpSocket,
+    group: SocketAddrV4,
+    bridge: Option<u16>,
+    #[cfg(feature = "bridge")]
+    link: Option<(Link<T>, BridgeHandle, HashMap<u64, BridgeHandle>)>,
+    #[cfg(feature = "bridge")]
+    interface: Ipv4Addr,
+}
+/// [`Announcer`] implementation
+impl<T> Announcer<T>
+where
+    T: CrosstalkTopic + Send + Sync + 'static,
+{
+    /// Announces the node every `interval`, and records the announcements of its peers
+    async fn run(mut self, peers: Arc<Peers<T>>, interval: Duration) {
+        let mut ticker = tokio::time::interval(interval);
+        let mut buf = vec![0; MAX_ANNOUNCEMENT];
+        let mut conflicts = HashSet::new();
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    self.expire(&peers, interval * 3);
+                    self.announce().await;
+                },
+                res = self.socket.recv_from(&mut buf) => {
+                    let (len, from) = match res {
+                        Ok(received) => received,
+                        Err(e) => {
+                            log_error(e);
+                            continue;
+                        },
+                    };
+                    let Some(announcement) = Announcement::parse(&buf[..len]) else {
+                        continue;
+                    };
+                    if announcement.id == self.id {
+                        continue;
+                    }
+                    let topics = self.node.lock().await.topics();
+                    let peer = announcement.matching(from, &topics);
+                    if peer.is_none() && announcement.conflicts(&topics) && conflicts.insert(announcement.id) {
+                        log_error(format!("crosstalk discovery ignored peer {} ({}), which disagrees on a data type", announcement.id, from));
+                    }
+                    #[cfg(feature = "bridge")]
+                    let bridge = peer.as_ref().and_then(|peer| peer.bridge);
+                    if peers.heard(peer, announcement.id) {
+                        // announcing right away lets the new peer discover this node sooner
+                        self.announce().await;
+                    }
+                    #[cfg(feature = "bridge")]
+                    self.link(announcement.id, bridge).await;
+                },
+            }
+        }
+    }
+
+    /// Forgets the peers which have not announced themselves within `expiry`,
+    /// closing their links
+    fn expire(&mut self, peers: &Peers<T>, expiry: Duration) {
+        for id in peers.expire(expiry) {
+            #[cfg(feature = "bridge")]
+            if let Some((_, _, links)) = &mut self.link {
+                links.remove(&id);
+            }
+            #[cfg(not(feature = "bridge"))]
+            let _ = id;
+        }
+    }
+
+    /// Announces the node to the multicast group
+    async fn announce(&self) {
+        let topics = self.node.lock().await.topics();
+        let announcement = Announcement::encode::<T>(self.id, self.bridge, &topics);
+        if let Err(e) = self.socket.send_to(announcement.as_bytes(), self.group).await {
+            log_error(e);
+        }
+    }
+
+    #[cfg(feature = "bridge")]
+    /// Links a peer which announced itself, if it listens for links (`bridge`) and
+    /// has a higher id, so that every pair is linked once. The link of a peer which
+    /// no longer matches or listens for links (`bridge` is [`None`]) is closed
+    async fn link(&mut self, id: u64, bridge: Option<SocketAddr>) {
+        let Some((link, _, links)) = &mut self.link else {
+            return;
+        };
+        let Some(addr) = bridge else {
+            links.remove(&id);
+            return;
+        };
+        // over loopback, peers announcing another address are not linked
+        if self.id > id || links.contains_key(&id) || (self.interface.is_loopback() && !addr.ip().is_loopback()) {
+            return;
+        }
+        match (link.connect)(addr).await {
+            Ok(bridge) => { links.insert(id, bridge); },
+            Err(e) => log_error(e),
+        }
+    }
+}
+
+/// An announcement of a [`Discovery`], as received from a peer
+/// 
+/// Announcements are UTF-8 text, starting with [`MAGIC`] and followed by one
+/// tab-separated field per line: `id`, `enum` (the name of the topic enum),
+/// optionally `bridge` (the port of [`Discovery::link`]) and one `topic`
+/// per topic, with its name and data type
+/// 
+/// # Attributes
+/// 
+/// * `id` - the id of the peer
+/// * `topic_enum` - the name of the topic enum of the peer
+/// * `bridge` - the port the peer listens on for links
+/// * `topics` - the name and data type of every topic of the peer
+struct Announcement {
+    id: u64,
+    topic_enum: String,
+    bridge: Option<u16>,
+    topics: Vec<(String, String)>,
+}
+/// [`Announcement`] implementation
+impl Announcement {
+    /// Encodes the announcement of a node
+    fn encode<T: CrosstalkTopic>(id: u64, bridge: Option<u16>, topics: &[(T, TopicInfo)]) -> String {
+        let mut announcement = format!("{}\nid\t{}\nenum\t{}\n", MAGIC, id, std::any::type_name::<T>());
+        if let Some(port) = bridge {
+            announcement.push_str(&format!("bridge\t{}\n", port));
+        }
+        for (topic, info) in topics {
+            announcement.push_str(&format!("topic\t{}\t{}\n", topic.name(), info.data_type));
+        }
+        announcement
+    }
+
+    /// Parses the announcement of a peer, or [`None`] if it is malformed
+    fn parse(bytes: &[u8]) -> Option<Self> {
+        let mut lines = std::str::from_utf8(bytes).ok()?.lines();
+        if lines.next()? != MAGIC {
+            return None;
+        }
+        let mut announcement = Self {
+            id: 0,
+            topic_enum: String::new(),
+            bridge: None,
+            topics: Vec::new(),
+        };
+        for line in lines {
+            let mut fields = line.split('\t');
+            match (fields.next()?, fields.next()?, fields.next()) {
+                ("id", id, None) => announcement.id = id.parse().ok()?,
+                ("enum", name, None) => announcement.topic_enum = name.to_string(),
+                ("bridge", port, None) => announcement.bridge = Some(port.parse().ok()?),
+                ("topic", name, Some(data_type)) => announcement.topics.push((name.to_string(), data_type.to_string())),
+                // fields of later versions are ignored
+                _ => {},
+            }
+        }
+        Some(announcement)
+    }
+
+    /// The data type the peer announced for a topic
+    fn data_type<T: CrosstalkTopic>(&self, topic: T) -> Option<&str> {
+        self.topics.iter()
+            .find(|(name, _)| name == topic.name())
+            .map(|(_, data_type)| data_type.as_str())
+    }
+
+    /// Whether the peer has a topic of the node with a different data type
+    fn conflicts<T: CrosstalkTopic>(&self, topics: &[(T, TopicInfo)]) -> bool {
+        self.topic_enum == std::any::type_name::<T>()
+            && topics.iter().any(|(topic, info)| self.data_type(*topic).is_some_and(|data_type| data_type != info.data_type))
+    }
+
+    /// The peer, if it has the same topic enum and at least one topic of
+    /// the node, with the same data type for every topic both have
+    fn matching<T: CrosstalkTopic>(&self, from: SocketAddr, topics: &[(T, TopicInfo)]) -> Option<Peer<T>> {
+        if self.topic_enum != std::any::type_name::<T>() || self.conflicts(topics) {
+            return None;
+        }
+        let shared = topics.iter()
+            .filter(|(topic, _)| self.data_type(*topic).is_some())
+            .map(|(topic, _)| *topic)
+            .collect::<Vec<_>>();
+        (!shared.is_empty()).then(|| Peer {
+            id: self.id,
+            addr: from,
+            bridge: self.bridge.map(|port| SocketAddr::new(from.ip(), port)),
+            topics: shared,
+        })
+    }
+}
+
+#[inline(always)]
+/// Logs an error of a [`Discovery`] under the `log` or `tracing` features
+fn log_error(err: impl std::fmt::Display) {
+    #[cfg(feature = "log")]
+    log::warn!("{}", err);
+    #[cfg(feature = "tracing")]
+    tracing::warn!("{}", err);
+    #[cfg(not(any(feature = "log", feature = "tracing")))]
+    let _ = err;
+}
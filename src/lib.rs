@@ -3,19 +3,119 @@
 // --------------------------------------------------
 // external
 // --------------------------------------------------
-use std::sync::Arc;
+use std::sync::{
+    Arc,
+    Weak,
+};
+use std::sync::atomic::{
+    AtomicU64,
+    Ordering,
+};
 use tokio::sync::Mutex;
-use std::collections::HashMap;
+use std::collections::{
+    HashMap,
+    VecDeque,
+};
 use tokio::sync::broadcast::{
     Sender as TokioSender,
     Receiver as TokioReceiver,
 };
+use tokio::sync::mpsc::{
+    UnboundedSender as TokioUnboundedSender,
+    UnboundedReceiver as TokioUnboundedReceiver,
+};
+use tokio::sync::watch::{
+    Sender as TokioWatchSender,
+    Receiver as TokioWatchReceiver,
+};
+#[cfg(feature = "queue")]
+use async_channel::{
+    Sender as AsyncSender,
+    Receiver as AsyncReceiver,
+};
 
 // --------------------------------------------------
 // local
 // --------------------------------------------------
 pub use crosstalk_macros::init;
 pub use crosstalk_macros::AsTopic;
+#[cfg(feature = "queue")]
+mod service;
+#[cfg(feature = "queue")]
+pub use service::{
+    Service,
+    ServiceClient,
+    ServiceServer,
+    ServiceRequest,
+    CrosstalkService,
+};
+#[cfg(feature = "stream")]
+mod stream;
+#[cfg(feature = "stream")]
+pub use stream::SubscriberStream;
+#[cfg(feature = "stream")]
+use stream::Wakers;
+mod shutdown;
+pub use shutdown::Shutdown;
+use shutdown::{
+    Observer,
+    Observers,
+};
+#[cfg(feature = "sink")]
+mod sink;
+#[cfg(feature = "sink")]
+pub use sink::SinkPolicy;
+mod sample;
+pub use sample::{Sample, Stamp};
+mod deadline;
+use deadline::Deadline;
+mod liveliness;
+use liveliness::Publishers;
+#[cfg(feature = "serde")]
+mod bytes;
+#[cfg(feature = "serde")]
+pub use bytes::{
+    Format,
+    BytePublisher,
+    ByteSubscriber,
+    CrosstalkBytes,
+};
+#[cfg(feature = "bridge")]
+mod bridge;
+#[cfg(feature = "bridge")]
+pub use bridge::{
+    Bridge,
+    Direction,
+    BridgeHandle,
+};
+#[cfg(feature = "shm")]
+mod shm;
+#[cfg(feature = "shm")]
+pub use shm::{
+    ShmHandle,
+    ShmReader,
+    ShmWriter,
+    SharedMemory,
+};
+#[cfg(feature = "discovery")]
+mod discovery;
+#[cfg(feature = "discovery")]
+pub use discovery::{
+    Peer,
+    Discovery,
+    DiscoveryHandle,
+};
+#[cfg(feature = "tracing")]
+mod trace;
+#[cfg(feature = "tracing")]
+pub use trace::Traced;
+#[cfg(feature = "metrics")]
+mod metrics;
+#[cfg(feature = "metrics")]
+use metrics::{
+    TopicMetrics,
+    SubscriberMetrics,
+};
 
 // --------------------------------------------------
 // re-exports
@@ -23,6 +123,8 @@ pub use crosstalk_macros::AsTopic;
 pub mod __macro_exports {
     pub use tokio::runtime;
     pub use tokio::sync::broadcast;
+    pub use crate::__serde as serde;
+    pub use crate::__queue as queue;
 
     #[inline(always)]
     /// Downcasts a [`Box`] into a type `T`
@@ -50,19 +152,167 @@ pub mod __macro_exports {
     }
 }
 
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "serde")]
+/// Expands the serialization items generated by [`crosstalk_macros::init!`],
+/// with the `serde` feature
+macro_rules! __serde {
+    (@required) => {};
+    ($($item:item)*) => { $($item)* };
+}
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "serde"))]
+/// Drops the serialization items generated by [`crosstalk_macros::init!`], and
+/// rejects topics declared with a serialization format, without the `serde` feature
+macro_rules! __serde {
+    (@required) => { compile_error!("`#[serde(<Format>)]` topics require the `serde` feature of `crosstalk`"); };
+    ($($item:item)*) => {};
+}
+
+#[doc(hidden)]
+#[macro_export]
+#[cfg(feature = "queue")]
+/// Expands the queue topics and services generated by [`crosstalk_macros::init!`],
+/// with the `queue` feature
+macro_rules! __queue {
+    (@items $($item:item)*) => { $($item)* };
+    (@required) => {};
+    ($($tt:tt)*) => { $($tt)* };
+}
+#[doc(hidden)]
+#[macro_export]
+#[cfg(not(feature = "queue"))]
+/// Drops the service items generated by [`crosstalk_macros::init!`], and
+/// rejects queue topics and services, without the `queue` feature
+macro_rules! __queue {
+    (@items $($item:item)*) => {};
+    (@required) => { compile_error!("services require the `queue` feature of `crosstalk`"); };
+    ($($tt:tt)*) => { compile_error!("`#[queue]` topics require the `queue` feature of `crosstalk`") };
+}
+
 /// A trait bound an enum as a [`CrosstalkTopic`]
-pub trait CrosstalkTopic: Eq + Copy + Clone + PartialEq + std::hash::Hash {}
+pub trait CrosstalkTopic: Eq + Copy + Clone + PartialEq + std::hash::Hash {
+    #[inline(always)]
+    /// The name of the topic, used to label it outside of the
+    /// type system (e.g. in metrics)
+    /// 
+    /// Implemented by [`AsTopic`] as the enum and variant
+    /// name, e.g. `"House::Kitchen"`. Defaults to the name of the enum
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+}
 
 /// A trait to bound a datatype as a [`CrosstalkData`]
 pub trait CrosstalkData: Clone + Send + 'static {}
 /// [`CrosstalkData`] implementation for all types
 impl<T: Clone + Send + 'static> CrosstalkData for T {}
 
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+/// The kind of a topic, which determines how samples
+/// are delivered from publishers to subscribers
+/// 
+/// The kind is declared per topic in [`crosstalk_macros::init!`]
+/// with an attribute (e.g. `#[watch]`)
+pub enum TopicKind {
+    #[default]
+    /// Every subscriber receives every sample
+    Broadcast,
+    /// Subscribers only observe the latest sample, and new
+    /// subscribers immediately receive the current value.
+    /// Declared with `#[watch]`
+    Watch,
+    /// Each sample is received by exactly one subscriber, where
+    /// subscribers compete for samples (load-balancing).
+    /// Declared with `#[queue]`, with the `queue` feature
+    /// 
+    /// When a bounded queue is full, publishing evicts the oldest
+    /// pending sample (like a lagging broadcast topic) instead of
+    /// waiting for a subscriber to take one
+    #[cfg(feature = "queue")]
+    Queue,
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+/// The configuration of a topic, as declared in [`crosstalk_macros::init!`]
+/// 
+/// This is not meant to be used directly, and is passed by the
+/// arms generated by [`crosstalk_macros::init!`] to the node
+/// 
+/// # Attributes
+/// 
+/// * `kind` - the kind of the topic
+/// * `capacity` - the capacity of the topic, or [`None`] to use the size of the node
+/// * `history` - the number of latest samples replayed to new subscribers, declared
+///   with `#[history(<Depth>)]`
+/// * `lifespan` - how long after being published samples can be read, or [`None`]
+///   if they never expire. Declared with `#[lifespan(<Duration>)]`
+/// * `deadline` - the maximum time expected between two samples, or [`None`]
+///   if the topic is not periodic. Declared with `#[deadline(<Duration>)]`
+/// * `stamp` - whether samples are stamped with their sequence number and the time
+///   they were published, declared with `#[stamp]`. Topics with a lifespan or a
+///   deadline are always stamped
+pub struct TopicConfig {
+    pub kind: TopicKind,
+    pub capacity: Option<usize>,
+    pub history: usize,
+    pub lifespan: Option<std::time::Duration>,
+    pub deadline: Option<std::time::Duration>,
+    pub stamp: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+/// A snapshot of a topic of a node, as returned by [`BoundedNode::topics`]
+/// 
+/// # Attributes
+/// 
+/// * `kind` - the kind of the topic. Services are `TopicKind::Queue`s of requests
+/// * `service` - whether the topic is a service
+/// * `data_type` - the name of the data type of the topic, or of `(Request, Response)` for services
+/// * `capacity` - the capacity of the topic, or [`None`] if it is unbounded
+/// * `publishers` - the number of publishers, or clients for services
+/// * `subscribers` - the number of subscribers, or servers for services
+/// * `queued` - the number of samples currently buffered, or [`None`] for broadcast
+///   topics of an [`UnboundedNode`], which buffer samples per subscriber
+/// * `closed` - whether the topic is closed
+pub struct TopicInfo {
+    pub kind: TopicKind,
+    pub service: bool,
+    pub data_type: &'static str,
+    pub capacity: Option<usize>,
+    pub publishers: usize,
+    pub subscribers: usize,
+    pub queued: Option<usize>,
+    pub closed: bool,
+}
+
 #[derive(Copy, Clone, Debug)]
+#[non_exhaustive]
 /// [`crosstalk`](crate) errors
 pub enum Error {
     PublisherMismatch(&'static str, &'static str),
     SubscriberMismatch(&'static str, &'static str),
+    /// The service was declared with different request/response types
+    ServiceMismatch(&'static str, &'static str),
+    /// There is no `ServiceServer` for the service
+    NoServer,
+    /// The `ServiceServer` dropped the request without responding
+    NoResponse,
+    /// No response was received within the timeout
+    Timeout,
+    /// There are no subscribers to receive the sample
+    NoSubscribers,
+    /// The topic has been closed
+    TopicClosed,
+    /// The node has been shut down
+    NodeShutdown,
+    /// The topic was not declared with `#[serde(<Format>)]`, with the `serde` feature
+    NotSerializable,
+    /// The sample could not be serialized or deserialized, with the `serde` feature
+    Serialization,
 }
 /// [`crosstalk::Error`](crate::Error) implementation of [`std::error::Error`]
 impl std::error::Error for Error {}
@@ -72,6 +322,134 @@ impl std::fmt::Display for Error {
         match self {
             Error::PublisherMismatch(input, output) => write!(f, "Publisher type mismatch: {} (cast) != {} (expected)", input, output),
             Error::SubscriberMismatch(input, output) => write!(f, "Subscriber type mismatch: {} (cast) != {} (expected)", input, output),
+            Error::ServiceMismatch(input, output) => write!(f, "Service type mismatch: {} (cast) != {} (expected)", input, output),
+            Error::NoServer => write!(f, "No server is available for the service"),
+            Error::NoResponse => write!(f, "The server dropped the request without responding"),
+            Error::Timeout => write!(f, "Timed out waiting for a response"),
+            Error::NoSubscribers => write!(f, "No subscribers to receive the sample"),
+            Error::TopicClosed => write!(f, "The topic has been closed"),
+            Error::NodeShutdown => write!(f, "The node has been shut down"),
+            Error::NotSerializable => write!(f, "The topic has no serialization format"),
+            Error::Serialization => write!(f, "The sample could not be serialized or deserialized"),
+        }
+    }
+}
+
+/// The error returned by [`Publisher::try_write`]
+/// 
+/// # Attributes
+/// 
+/// * `sample` - the sample which was not published
+/// * `error` - the reason the sample was not published
+/// 
+/// Can be converted into a [`crosstalk::Error`](crate::Error) with `?`
+pub struct SendError<D> {
+    pub sample: D,
+    pub error: Error,
+}
+/// [`SendError`] implementation
+impl<D> SendError<D> {
+    #[inline(always)]
+    /// Takes back the sample which was not published
+    pub fn into_inner(self) -> D {
+        self.sample
+    }
+}
+/// [`SendError`] implementation of [`std::fmt::Debug`]
+impl<D> std::fmt::Debug for SendError<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SendError").field("error", &self.error).finish_non_exhaustive()
+    }
+}
+/// [`SendError`] implementation of [`std::fmt::Display`]
+impl<D> std::fmt::Display for SendError<D> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.error.fmt(f)
+    }
+}
+/// [`SendError`] implementation of [`std::error::Error`]
+impl<D> std::error::Error for SendError<D> {}
+/// [`crosstalk::Error`](crate::Error) implementation of [`From<SendError>`]
+impl<D> From<SendError<D>> for Error {
+    fn from(err: SendError<D>) -> Self {
+        err.error
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// How a [`Subscriber`] handles lost samples when it falls behind a topic
+/// 
+/// Only broadcast topics of a [`BoundedNode`] can lag. The policy applies
+/// to the `read` methods of a [`Subscriber`], while the `recv` methods
+/// always report [`RecvError::Lagged`]
+/// 
+/// Set with [`Subscriber::set_lag_policy`]
+pub enum LagPolicy {
+    #[default]
+    /// Skip the lost samples silently, and continue from the oldest sample still in the buffer
+    Skip,
+    /// Skip the lost samples, adding them to [`Subscriber::lagged_total`]
+    Count,
+    /// Fail the read, adding the lost samples to [`Subscriber::lagged_total`]. The
+    /// next read continues from the oldest sample still in the buffer
+    Error,
+    /// Jump straight to the newest sample, adding every sample
+    /// skipped on the way to [`Subscriber::lagged_total`]
+    Latest,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+/// The error returned by the `recv` methods of a [`Subscriber`]
+pub enum RecvError {
+    /// The topic is closed, and no more samples will be received
+    Closed,
+    /// There are no samples available yet
+    Empty,
+    /// No sample was received within the timeout
+    Timeout,
+    /// The subscriber fell behind, and the given number of samples were skipped
+    Lagged(u64),
+    /// The call requires a [`tokio`] runtime, but none is running
+    NoRuntime,
+    /// No sample was received within the deadline of the topic, see [`TopicConfig::deadline`].
+    /// Only returned by the `recv` methods, while the `read` methods keep waiting
+    DeadlineMissed,
+    /// The topic is not a [`TopicKind::Watch`] topic, see [`Subscriber::changed`]
+    NotWatch,
+}
+/// [`RecvError`] implementation of [`std::error::Error`]
+impl std::error::Error for RecvError {}
+/// [`RecvError`] implementation of [`std::fmt::Display`]
+impl std::fmt::Display for RecvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecvError::Closed => write!(f, "channel closed"),
+            RecvError::Empty => write!(f, "channel empty"),
+            RecvError::Timeout => write!(f, "timed out waiting on channel"),
+            RecvError::Lagged(n) => write!(f, "channel lagged by {}", n),
+            RecvError::NoRuntime => write!(f, "no tokio runtime is running"),
+            RecvError::DeadlineMissed => write!(f, "no sample received within the deadline"),
+            RecvError::NotWatch => write!(f, "the topic is not a watch topic"),
+        }
+    }
+}
+/// [`RecvError`] implementation of [`From<tokio::sync::broadcast::error::RecvError>`]
+impl From<tokio::sync::broadcast::error::RecvError> for RecvError {
+    fn from(err: tokio::sync::broadcast::error::RecvError) -> Self {
+        match err {
+            tokio::sync::broadcast::error::RecvError::Closed => RecvError::Closed,
+            tokio::sync::broadcast::error::RecvError::Lagged(n) => RecvError::Lagged(n),
+        }
+    }
+}
+/// [`RecvError`] implementation of [`From<tokio::sync::broadcast::error::TryRecvError>`]
+impl From<tokio::sync::broadcast::error::TryRecvError> for RecvError {
+    fn from(err: tokio::sync::broadcast::error::TryRecvError) -> Self {
+        match err {
+            tokio::sync::broadcast::error::TryRecvError::Empty => RecvError::Empty,
+            tokio::sync::broadcast::error::TryRecvError::Closed => RecvError::Closed,
+            tokio::sync::broadcast::error::TryRecvError::Lagged(n) => RecvError::Lagged(n),
         }
     }
 }
@@ -98,10 +476,14 @@ pub trait CrosstalkPubSub<T> {
 /// subscribers on, where the size of each buffer is
 /// fixed.
 /// 
+/// The size of each buffer defaults to `size`, unless a
+/// capacity was given for the topic in [`crosstalk_macros::init!`]
+/// (e.g. `House::Kitchen => Vec<f32> @ 1024`)
+/// 
 /// # Attributes
 /// 
 /// * `node` - the node to spawn publishers and subscribers on
-/// * `size` - the size of each buffer
+/// * `size` - the default size of each buffer
 /// 
 /// # Type Parameters
 /// 
@@ -164,7 +546,7 @@ where
     /// 
     /// # Arguments
     /// 
-    /// * `size` - the size of each buffer
+    /// * `size` - the size of each buffer, for topics without a capacity in [`crosstalk_macros::init!`]
     /// 
     /// # Panics
     /// 
@@ -460,80 +842,68 @@ where
     pub fn pubsub_blocking<D: CrosstalkData>(&mut self, topic: T) -> Result<(Publisher<D, T>, Subscriber<D, T>), crate::Error> {
         self.node.blocking_lock().pubsub(topic)
     }
-}
-
-/// The inner implementation of the node,
-/// which implements the [`AsTopic`] trait
-/// 
-/// This is auto-generated by the [`crosstalk_macros::init!`] macro
-/// 
-/// # Attributes
-/// 
-/// * `senders` - the senders of the node
-/// * `size` - the size of each buffer
-pub struct ImplementedBoundedNode<T> {
-    pub senders: HashMap<T, Box<dyn std::any::Any + 'static>>,
-    pub size: usize,
-}
-
-/// [`ImplementedBoundedNode`] implementation of [`Send`]
-unsafe impl<T> Send for ImplementedBoundedNode<T> {}
-/// [`ImplementedBoundedNode`] implementation of [`Sync`]
-unsafe impl<T> Sync for ImplementedBoundedNode<T> {}
 
-/// [`ImplementedBoundedNode`] implementation 
-impl<T> ImplementedBoundedNode<T>
-where
-    T: CrosstalkTopic,
-{
-    /// See [`BoundedNode::new`]
+    #[inline(always)]
+    /// Closes the given topic `T`
+    /// 
+    /// Subscribers of the topic read every sample still buffered, after
+    /// which they observe the end of the topic ([`RecvError::Closed`]).
+    /// Existing publishers of the topic drop every sample written to it,
+    /// while new publishers and subscribers open the topic again
+    /// 
+    /// A topic is also closed automatically once its last publisher is dropped, and
+    /// is then reopened by the next publisher or subscriber, keeping its history and
+    /// publishers (see [`Subscriber::wait_for_publisher`]). Subscribers of the closed
+    /// topic have to subscribe again, e.g. with [`Subscriber::clone`]
     /// 
     /// # Arguments
     /// 
-    /// * `size` - the size of each buffer
-    pub fn new(size: usize) -> Self {
-        Self {
-            senders: HashMap::new(),
-            size,
-        }
-    }
-}
-
-#[derive(Clone)]
-/// A `crosstalk` [`Publisher`]
-/// 
-/// # Attributes
-/// 
-/// * `topic` - the topic of the publisher
-/// * `buf` - the buffer which broadcasts the data
-/// 
-/// # Type Parameters
-/// 
-/// * `T` - the topic of the publisher
-/// * `D` - the data type of the publisher
-/// 
-/// This is not meant to be used directly, please
-/// use the [`crosstalk_macros::init!`] macro instead
-/// and produce a [`Publisher`] with [`BoundedNode::publisher`]
-/// or [`BoundedNode::pubsub`]
-pub struct Publisher<D, T> {
-    pub topic: T,
-    buf: TokioSender<D>,
-}
-/// [`Publisher`] implementation
-impl<D, T> Publisher<D, T> {
-    #[inline(always)]
-    /// See [`BoundedNode::publisher`]
-    pub fn new(topic: T, buf: TokioSender<D>) -> Self {
-        Self { topic, buf }
+    /// * `topic` - the topic to close
+    /// 
+    /// # Returns
+    /// 
+    /// `true` if the topic was open
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bedroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bedroom => String,
+    /// }
+    /// 
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut node = crosstalk::BoundedNode::<House>::new(10);
+    ///     let (publisher, mut subscriber) = node.pubsub(House::Bedroom).await.unwrap();
+    ///     publisher.write("hello".to_string());
+    ///     assert!(node.close_topic(House::Bedroom).await);
+    ///     assert_eq!(subscriber.read().await, Some("hello".to_string()));
+    ///     assert_eq!(subscriber.recv().await, Err(crosstalk::RecvError::Closed));
+    /// }
+    /// ```
+    pub async fn close_topic(&mut self, topic: T) -> bool {
+        self.node.lock().await.close_topic(topic)
     }
 
     #[inline(always)]
-    /// Publishes data to a topic, broadcasting it to all subscribers
+    /// Closes the given topic `T`
+    /// 
+    /// See [`BoundedNode::close_topic`]
     /// 
     /// # Arguments
     /// 
-    /// * `sample` - the sample to publish
+    /// * `topic` - the topic to close
+    /// 
+    /// # Returns
+    /// 
+    /// `true` if the topic was open
     /// 
     /// # Examples
     /// 
@@ -543,73 +913,35 @@ impl<D, T> Publisher<D, T> {
     /// #[derive(AsTopic)]
     /// enum House {
     ///     Bedroom,
-    ///     LivingRoom,
-    ///     Kitchen,
-    ///     Bathroom,
     /// }
     /// 
     /// crosstalk::init! {
     ///     House::Bedroom => String,
-    ///     House::LivingRoom => String,
-    ///     House::Kitchen => Vec<f32>,
-    ///     House::Bathroom => u8,
     /// }
     /// 
     /// let mut node = crosstalk::BoundedNode::<House>::new(10);
     /// let (publisher, mut subscriber) = node.pubsub_blocking(House::Bedroom).unwrap();
-    /// publisher.write("hello".to_string());
-    /// std::thread::spawn(move || {
-    ///     assert_eq!(subscriber.try_read().unwrap(), "hello");
-    /// });
+    /// assert!(node.close_topic_blocking(House::Bedroom));
+    /// assert!(!node.close_topic_blocking(House::Bedroom));
+    /// assert!(publisher.try_write("hello".to_string()).is_err());
+    /// assert_eq!(subscriber.read_blocking(), None);
     /// ```
-    pub fn write(&self, sample: D) {
-        let _ = self.buf.send(sample);
-    }
-}
-
-/// A `crosstalk` [`Subscriber`]
-/// 
-/// # Attributes
-/// 
-/// * `topic` - the topic of the subscriber
-/// * `rcvr` - the receiver of the subscriber
-/// * `sndr` - the sender for the topic. This is used to spawn multiple receivers upon [`Subscriber::clone`]
-/// 
-/// # Type Parameters
-/// 
-/// * `T` - the topic of the subscriber
-/// * `D` - the data type of the subscriber
-/// 
-/// This is not meant to be used directly, please
-/// use the [`crosstalk_macros::init!`] macro instead
-/// and produce a [`Subscriber`] with [`BoundedNode::subscriber`]
-/// or [`BoundedNode::pubsub`]
-pub struct Subscriber<D, T> {
-    pub topic: T,
-    rcvr: Receiver<D>,
-    sndr: Arc<TokioSender<D>>,
-}
-/// [`Subscriber`] implementation 
-impl<D: Clone, T: Clone> Subscriber<D, T> {
-    #[inline(always)]
-    /// See [`BoundedNode::subscriber`]
-    pub fn new(
-        topic: T,
-        rcvr: Option<TokioReceiver<D>>,
-        sndr: Arc<TokioSender<D>>,
-    ) -> Self {
-        Self {
-            topic,
-            rcvr: Receiver::new(rcvr.unwrap_or(sndr.subscribe())),
-            sndr: sndr.clone(),
-        }
+    pub fn close_topic_blocking(&mut self, topic: T) -> bool {
+        self.node.blocking_lock().close_topic(topic)
     }
 
     #[inline(always)]
-    /// Asynchronous blocking read from the [`TokioReceiver`]
+    /// Shuts down the node
     /// 
-    /// The sequential equivalent to this function is [`Subscriber::read_blocking`]
-    /// which can be used outside of an asynchronous context
+    /// Every topic of the node is closed (see [`BoundedNode::close_topic`]),
+    /// and no new publishers, subscribers or services can be created, failing
+    /// with [`Error::NodeShutdown`]. Subscribers can still read every sample
+    /// which was buffered before the shutdown
+    /// 
+    /// # Returns
+    /// 
+    /// A [`Shutdown`], which resolves once every subscriber (and service server)
+    /// of the node has observed the close of its topic, or has been dropped
     /// 
     /// # Examples
     /// 
@@ -619,43 +951,43 @@ impl<D: Clone, T: Clone> Subscriber<D, T> {
     /// #[derive(AsTopic)]
     /// enum House {
     ///     Bedroom,
-    ///     LivingRoom,
-    ///     Kitchen,
-    ///     Bathroom,
     /// }
     /// 
     /// crosstalk::init! {
     ///     House::Bedroom => String,
-    ///     House::LivingRoom => String,
-    ///     House::Kitchen => Vec<f32>,
-    ///     House::Bathroom => u8,
     /// }
     /// 
     /// #[tokio::main]
     /// async fn main() {
     ///     let mut node = crosstalk::BoundedNode::<House>::new(10);
     ///     let (publisher, mut subscriber) = node.pubsub(House::Bedroom).await.unwrap();
+    ///     let reader = tokio::spawn(async move {
+    ///         let mut received = Vec::new();
+    ///         while let Some(sample) = subscriber.read().await {
+    ///             received.push(sample);
+    ///         }
+    ///         received
+    ///     });
     ///     publisher.write("hello".to_string());
-    ///     assert_eq!(subscriber.read().await, Some("hello".to_string()));
+    /// 
+    ///     node.shutdown().await.await;
+    ///     assert_eq!(reader.await.unwrap(), vec!["hello".to_string()]);
+    ///     assert!(node.publisher::<String>(House::Bedroom).await.is_err());
     /// }
     /// ```
-    pub async fn read(&mut self) -> Option<D> {
-        self.rcvr.read().await
+    pub async fn shutdown(&mut self) -> Shutdown {
+        self.node.lock().await.shutdown()
     }
-    
+
     #[inline(always)]
-    /// Non-blocking read from [`TokioReceiver`]
-    /// Upon immediate failure, [`None`] will be returned
+    /// Shuts down the node
     /// 
-    /// Difference between this function and [`Subscriber::try_read_raw`]
-    /// is that this function continuously loops upon [`tokio`] error of
-    /// [`tokio::sync::broadcast::error::TryRecvError::Lagged`], looping
-    /// until a valid message is received OR the buffer is determined to be
-    /// empty
+    /// See [`BoundedNode::shutdown`]
     /// 
-    /// [`Subscriber::try_read_raw`] will return [`None`] upon
-    /// [`tokio::sync::broadcast::error::TryRecvError::Lagged`], which
-    /// can cause some unexpected behavior
+    /// # Returns
+    /// 
+    /// A [`Shutdown`], which resolves once every subscriber (and service server)
+    /// of the node has observed the close of its topic, or has been dropped
     /// 
     /// # Examples
     /// 
@@ -665,35 +997,142 @@ impl<D: Clone, T: Clone> Subscriber<D, T> {
     /// #[derive(AsTopic)]
     /// enum House {
     ///     Bedroom,
-    ///     LivingRoom,
-    ///     Kitchen,
-    ///     Bathroom,
     /// }
     /// 
     /// crosstalk::init! {
     ///     House::Bedroom => String,
-    ///     House::LivingRoom => String,
-    ///     House::Kitchen => Vec<f32>,
-    ///     House::Bathroom => u8,
     /// }
     /// 
     /// let mut node = crosstalk::BoundedNode::<House>::new(10);
     /// let (publisher, mut subscriber) = node.pubsub_blocking(House::Bedroom).unwrap();
+    /// let reader = std::thread::spawn(move || {
+    ///     while subscriber.read_blocking().is_some() {}
+    /// });
     /// publisher.write("hello".to_string());
-    /// assert_eq!(subscriber.try_read(), Some(String::from("hello")));
-    /// assert_eq!(subscriber.try_read(), None);
+    /// node.shutdown_blocking().wait_blocking();
+    /// reader.join().unwrap();
     /// ```
-    pub fn try_read(&mut self) -> Option<D> {
-        self.rcvr.try_read()
+    pub fn shutdown_blocking(&mut self) -> Shutdown {
+        self.node.blocking_lock().shutdown()
     }
 
     #[inline(always)]
-    /// Non-blocking read from [`TokioReceiver`], returning
-    /// [`None`] if there are no messages available or if 
-    /// [`tokio::sync::broadcast::error::TryRecvError::Lagged`] occurs.
+    /// Gets a snapshot of every topic of the node
     /// 
-    /// This function can cause some unexpected behavior. It is recommended
-    /// to use [`Subscriber::try_read`] instead.
+    /// Only topics which have been used (i.e. which had a publisher,
+    /// subscriber or service created) are listed, in no particular order
+    /// 
+    /// # Returns
+    /// 
+    /// Every topic of the node, with its [`TopicInfo`]
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bedroom,
+    ///     Kitchen,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bedroom => String,
+    ///     House::Kitchen => Vec<f32> @ 64,
+    /// }
+    /// 
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut node = crosstalk::BoundedNode::<House>::new(10);
+    ///     let (publisher, subscriber) = node.pubsub::<Vec<f32>>(House::Kitchen).await.unwrap();
+    ///     publisher.write(vec![1.0]);
+    /// 
+    ///     let topics = node.topics().await;
+    ///     assert_eq!(topics.len(), 1);
+    ///     let (topic, info) = &topics[0];
+    ///     assert!(*topic == House::Kitchen);
+    ///     assert_eq!(info.data_type, std::any::type_name::<Vec<f32>>());
+    ///     assert_eq!(info.capacity, Some(64));
+    ///     assert_eq!((info.publishers, info.subscribers), (1, 1));
+    ///     assert_eq!(info.queued, Some(1));
+    /// }
+    /// ```
+    pub async fn topics(&self) -> Vec<(T, TopicInfo)> {
+        self.node.lock().await.topics()
+    }
+
+    #[inline(always)]
+    /// Gets a snapshot of every topic of the node
+    /// 
+    /// See [`BoundedNode::topics`]
+    pub fn topics_blocking(&self) -> Vec<(T, TopicInfo)> {
+        self.node.blocking_lock().topics()
+    }
+}
+
+#[derive(Clone)]
+/// An [`UnboundedNode`] is a node to spawn publishers and
+/// subscribers on, where the buffers have no fixed size.
+/// 
+/// Unlike a [`BoundedNode`], publishing on an [`UnboundedNode`]
+/// never overwrites samples that a lagging subscriber has not read
+/// yet. Every subscriber receives every sample, at the cost of
+/// unbounded memory growth if a subscriber stops reading.
+/// 
+/// # Attributes
+/// 
+/// * `node` - the node to spawn publishers and subscribers on
+/// 
+/// # Type Parameters
+/// 
+/// * `T` - the topic enum name
+/// 
+/// # Examples
+/// 
+/// ```
+/// use crosstalk::AsTopic;
+/// 
+/// #[derive(AsTopic)]
+/// enum House {
+///     Bedroom,
+///     LivingRoom,
+///     Kitchen,
+///     Bathroom,
+/// }
+/// 
+/// crosstalk::init! {
+///     House::Bedroom => String,
+///     House::LivingRoom => String,
+///     House::Kitchen => Vec<f32>,
+///     House::Bathroom => u8,
+/// }
+/// 
+/// let mut node = crosstalk::UnboundedNode::<House>::new();
+/// let (publisher, mut subscriber) = node.pubsub_blocking(House::Bathroom).unwrap();
+/// 
+/// for i in 0..100u8 {
+///     publisher.write(i);
+/// }
+/// for i in 0..100u8 {
+///     assert_eq!(subscriber.try_read().unwrap(), i);
+/// }
+/// ```
+pub struct UnboundedNode<T> {
+    pub node: Arc<Mutex<ImplementedUnboundedNode<T>>>,
+}
+/// [`UnboundedNode`] implementation
+/// 
+/// This holds an [`Arc<Mutex<ImplementedUnboundedNode<T>>>`], which
+/// references the true (private) node that implements the [`AsTopic`] trait.
+impl<T> UnboundedNode<T>
+where
+    T: CrosstalkTopic,
+    ImplementedUnboundedNode<T>: CrosstalkPubSub<T>,
+{
+    #[inline(always)]
+    #[allow(clippy::new_without_default)]
+    /// Creates a new [`UnboundedNode`]
     /// 
     /// # Examples
     /// 
@@ -715,21 +1154,32 @@ impl<D: Clone, T: Clone> Subscriber<D, T> {
     ///     House::Bathroom => u8,
     /// }
     /// 
-    /// let mut node = crosstalk::BoundedNode::<House>::new(10);
-    /// let (publisher, mut subscriber) = node.pubsub_blocking(House::Bedroom).unwrap();
-    /// publisher.write("hello".to_string());
-    /// assert_eq!(subscriber.try_read_raw(), Some(String::from("hello")));
-    /// assert_eq!(subscriber.try_read_raw(), None);
+    /// fn main() {
+    ///     let node = crosstalk::UnboundedNode::<House>::new();
+    ///     let moved_node = node.clone();
+    ///     std::thread::spawn(move || another_thread(moved_node));
+    /// }
+    /// 
+    /// fn another_thread(mut node: crosstalk::UnboundedNode<House>) {
+    ///     assert!(node.publisher_blocking::<u8>(House::Bathroom).is_ok());
+    /// }
     /// ```
-    pub fn try_read_raw(&mut self) -> Option<D> {
-        self.rcvr.try_read_raw()
+    pub fn new() -> Self {
+        Self {
+            node: Arc::new(Mutex::new(ImplementedUnboundedNode::<T>::new())),
+        }
     }
-    
+
     #[inline(always)]
-    /// Sequential blocking read from [`TokioReceiver`]
+    /// Creates a new publisher for the given topic `T`
     /// 
-    /// The asynchronous equivalent to this function is [`Subscriber::read`], 
-    /// which must be used in an asynchronous context with `.await`
+    /// # Arguments
+    /// 
+    /// * `topic` - the topic to create a publisher for
+    /// 
+    /// # Returns
+    /// 
+    /// A publisher for the topic `T`
     /// 
     /// # Examples
     /// 
@@ -751,19 +1201,64 @@ impl<D: Clone, T: Clone> Subscriber<D, T> {
     ///     House::Bathroom => u8,
     /// }
     /// 
-    /// let mut node = crosstalk::BoundedNode::<House>::new(10);
-    /// let (publisher, mut subscriber) = node.pubsub_blocking(House::Bedroom).unwrap();
-    /// publisher.write("hello".to_string());
-    /// assert_eq!(subscriber.read_blocking(), Some("hello".to_string()));
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut node = crosstalk::UnboundedNode::<House>::new();
+    ///     assert!(node.publisher::<String>(House::Bedroom).await.is_ok());
+    /// }
     /// ```
-    pub fn read_blocking(&mut self) -> Option<D> {
-        self.rcvr.read_blocking()
+    pub async fn publisher<D: CrosstalkData>(&mut self, topic: T) -> Result<Publisher<D, T>, crate::Error> {
+        self.node.lock().await.publisher(topic)
     }
-    
+
     #[inline(always)]
-    /// Asynchronous non-blocking read from [`TokioReceiver`]
-    /// with a given timeout. After the timeout if there are no messages,
-    /// returns [`None`].
+    /// Creates a new publisher for the given topic `T`
+    /// 
+    /// # Arguments
+    /// 
+    /// * `topic` - the topic to create a publisher for
+    /// 
+    /// # Returns
+    /// 
+    /// A publisher for the topic `T`
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bedroom,
+    ///     LivingRoom,
+    ///     Kitchen,
+    ///     Bathroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bedroom => String,
+    ///     House::LivingRoom => String,
+    ///     House::Kitchen => Vec<f32>,
+    ///     House::Bathroom => u8,
+    /// }
+    /// 
+    /// let mut node = crosstalk::UnboundedNode::<House>::new();
+    /// assert!(node.publisher_blocking::<String>(House::Bedroom).is_ok());
+    /// ```
+    pub fn publisher_blocking<D: CrosstalkData>(&mut self, topic: T) -> Result<Publisher<D, T>, crate::Error> {
+        self.node.blocking_lock().publisher(topic)
+    }
+
+    #[inline(always)]
+    /// Creates a new subscriber for the given topic `T`
+    /// 
+    /// # Arguments
+    /// 
+    /// * `topic` - the topic to create a subscriber for
+    /// 
+    /// # Returns
+    /// 
+    /// A subscriber for the topic `T`
     /// 
     /// # Examples
     /// 
@@ -787,20 +1282,24 @@ impl<D: Clone, T: Clone> Subscriber<D, T> {
     /// 
     /// #[tokio::main]
     /// async fn main() {
-    ///     let mut node = crosstalk::BoundedNode::<House>::new(10);
-    ///     let (publisher, mut subscriber) = node.pubsub(House::Bedroom).await.unwrap();
-    ///     publisher.write("hello".to_string());
-    ///     assert_eq!(subscriber.read_timeout(std::time::Duration::from_millis(100)).await, Some("hello".to_string()));
+    ///     let mut node = crosstalk::UnboundedNode::<House>::new();
+    ///     assert!(node.subscriber::<String>(House::Bedroom).await.is_ok());
     /// }
     /// ```
-    pub async fn read_timeout(&mut self, timeout: std::time::Duration) -> Option<D> {
-        self.rcvr.read_timeout(timeout).await
+    pub async fn subscriber<D: CrosstalkData>(&mut self, topic: T) -> Result<Subscriber<D, T>, crate::Error> {
+        self.node.lock().await.subscriber(topic)
     }
-}
-/// [`Subscriber`] implementation of [`Clone`]
-impl<D: Clone, T: Clone> Clone for Subscriber<D, T> {
+
     #[inline(always)]
-    /// Clones a [`Subscriber`]
+    /// Creates a new subscriber for the given topic `T`
+    /// 
+    /// # Arguments
+    /// 
+    /// * `topic` - the topic to create a subscriber for
+    /// 
+    /// # Returns
+    /// 
+    /// A subscriber for the topic `T`
     /// 
     /// # Examples
     /// 
@@ -822,543 +1321,4592 @@ impl<D: Clone, T: Clone> Clone for Subscriber<D, T> {
     ///     House::Bathroom => u8,
     /// }
     /// 
-    /// let mut node = crosstalk::BoundedNode::<House>::new(10);
-    /// let (publisher, mut subscriber) = node.pubsub_blocking(House::Bedroom).unwrap();
-    /// let mut subscriber_2 = subscriber.clone();
-    /// publisher.write("hello".to_string());
-    /// assert_eq!(subscriber.try_read().unwrap(), "hello");
-    /// assert_eq!(subscriber_2.try_read().unwrap(), "hello");
+    /// let mut node = crosstalk::UnboundedNode::<House>::new();
+    /// assert!(node.subscriber_blocking::<String>(House::Bedroom).is_ok());
     /// ```
-    fn clone(&self) -> Self {
-        Self {
-            topic: self.topic.clone(),
-            rcvr: Receiver::new(self.sndr.subscribe()),
-            sndr: self.sndr.clone(),
-        }
+    pub fn subscriber_blocking<D: CrosstalkData>(&mut self, topic: T) -> Result<Subscriber<D, T>, crate::Error> {
+        self.node.blocking_lock().subscriber(topic)
     }
-}
 
-/// Receiver
-/// 
-/// Define a receiver for subscribing messages
-/// 
-/// Reads from [`TokioReceiver`]
-struct Receiver<D> {
-    buf: TokioReceiver<D>,
-}
-/// [`Receiver`] implementation
-impl<D: Clone> Receiver<D>{
     #[inline(always)]
-    /// Constructs a new [`Receiver`]
-    pub fn new(
-        buf: TokioReceiver<D>,
-    ) -> Self {
-        Self { buf }
-    }
-
-    /// Reads from [`TokioReceiver`]
+    /// Creates a new publisher and subscriber for the given topic `T`
     /// 
-    /// This struct/function is not meant to be used directly,
-    /// rather through the [`Subscriber`] struct with [`Subscriber::read`]
-    async fn read(&mut self) -> Option<D> {
-        loop {
-            match self.buf.recv().await {
-                Ok(res) => return Some(res),
-                Err(e) => match e {
-                    tokio::sync::broadcast::error::RecvError::Lagged(_) => { continue; }
-
-                    #[cfg(not(any(feature = "log", feature = "tracing")))]
-                    _ => return None,
-
-                    #[cfg(any(feature = "log", feature = "tracing"))]
-                    _ => {
-                        #[cfg(feature = "log")]
-                        log::error!("{}", e);
-                        #[cfg(feature = "tracing")]
-                        tracing::error!("{}", e);
-                        return None
-                    }
-                }
-            }
-        }
-    }    
-
-    /// Reads from [`TokioReceiver`]
+    /// # Arguments
     /// 
-    /// This struct/function is not meant to be used directly,
-    /// rather through the [`Subscriber`] struct with [`Subscriber::try_read`]
-    fn try_read(&mut self) -> Option<D> {
-        loop {
-            match self.buf.try_recv() {
-                Ok(d) => return Some(d),
-                Err(e) => {
-                    match e {
-                        tokio::sync::broadcast::error::TryRecvError::Lagged(_) => { continue; },
-
-                        #[cfg(not(any(feature = "log", feature = "tracing")))]
-                        _ => return None,
-
-                        #[cfg(any(feature = "log", feature = "tracing"))]
-                        _ => {
-                            #[cfg(feature = "log")]
-                            log::error!("{}", e);
-                            #[cfg(feature = "tracing")]
-                            tracing::error!("{}", e);
-                            return None
-                        },
-                    }
-                },
-            }
-        } 
-    }
-
-    /// Reads from [`TokioReceiver`]
+    /// * `topic` - the topic to create a publisher and subscriber for
     /// 
-    /// This struct/function is not meant to be used directly,
-    /// rather through the [`Subscriber`] struct with [`Subscriber::try_read_raw`]
-    fn try_read_raw(&mut self) -> Option<D> {
-        match self.buf.try_recv() {
-            Ok(d) => Some(d),
-
-            #[cfg(not(any(feature = "log", feature = "tracing")))]
-            Err(_) => None,
-            
-            #[cfg(any(feature = "log", feature = "tracing"))]
-            Err(e) => {
-                #[cfg(feature = "log")]
-                log::error!("{}", e);
-                #[cfg(feature = "tracing")]
-                tracing::error!("{}", e);
-                None
-            },
-        }
-    }
-    
-    /// Reads from [`TokioReceiver`]
+    /// # Returns
     /// 
-    /// This struct/function is not meant to be used directly,
-    /// rather through the [`Subscriber`] struct with [`Subscriber::read_blocking`]
-    fn read_blocking(&mut self) -> Option<D> {
-        loop {
-            match self.buf.blocking_recv() {
-                Ok(res) => return Some(res),
-                Err(e) => match e {
-                    tokio::sync::broadcast::error::RecvError::Lagged(_) => { continue; }
-                    
-                    #[cfg(not(any(feature = "log", feature = "tracing")))]
-                    _ => return None,
-
-                    #[cfg(any(feature = "log", feature = "tracing"))]
-                    _ => {
-                        #[cfg(feature = "log")]
-                        log::error!("{}", e);
-                        #[cfg(feature = "tracing")]
-                        tracing::error!("{}", e);
-                        return None
-                    },
-                }
-            }
-        }
-    }
-    
-    /// Reads from [`TokioReceiver`]
+    /// A publisher and subscriber for the topic `T`
     /// 
-    /// This struct/function is not meant to be used directly,
-    /// rather through the [`Subscriber`] struct with [`Subscriber::read_timeout`]
-    async fn read_timeout(&mut self, timeout: std::time::Duration) -> Option<D> {
-        match tokio::runtime::Handle::try_current() {
-            Ok(_) => {
-                match tokio::time::timeout(timeout, self.buf.recv()).await {
-                    Ok(res) => {
-                        match res {
-                            Ok(res) => Some(res),
-
-                            #[cfg(not(any(feature = "log", feature = "tracing")))]
-                            Err(_) => None,
-                            
-                            #[cfg(any(feature = "log", feature = "tracing"))]
-                            Err(e) => {
-                                #[cfg(feature = "log")]
-                                log::error!("{}", e);
-                                #[cfg(feature = "tracing")]
-                                tracing::error!("{}", e);
-                                None
-                            },
-                        }
-                    },
-
-                    #[cfg(not(any(feature = "log", feature = "tracing")))]
-                    Err(_) => None,
-                    
-                    #[cfg(any(feature = "log", feature = "tracing"))]
-                    Err(e) => {
-                        #[cfg(feature = "log")]
-                        log::error!("{}", e);
-                        #[cfg(feature = "tracing")]
-                        tracing::error!("{}", e);
-                        None
-                    },
-                }
-            },
-
-            #[cfg(not(any(feature = "log", feature = "tracing")))]
-            Err(_) => None,
-            
-            #[cfg(any(feature = "log", feature = "tracing"))]
-            Err(e) => {
-                #[cfg(feature = "log")]
-                log::error!("{}", e);
-                #[cfg(feature = "tracing")]
-                tracing::error!("{}", e);
-                None
-            },
-        }
-    }
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bedroom,
+    ///     LivingRoom,
+    ///     Kitchen,
+    ///     Bathroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bedroom => String,
+    ///     House::LivingRoom => String,
+    ///     House::Kitchen => Vec<f32>,
+    ///     House::Bathroom => u8,
+    /// }
+    /// 
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut node = crosstalk::UnboundedNode::<House>::new();
+    ///     let (publisher, mut subscriber) = node.pubsub(House::Bedroom).await.unwrap();
+    ///     publisher.write("hello".to_string());
+    ///     assert_eq!(subscriber.try_read().unwrap(), "hello");
+    /// }
+    /// ```
+    pub async fn pubsub<D: CrosstalkData>(&mut self, topic: T) -> Result<(Publisher<D, T>, Subscriber<D, T>), crate::Error> {
+        self.node.lock().await.pubsub(topic)
+    }
+
+    #[inline(always)]
+    #[allow(clippy::type_complexity)]
+    /// Creates a new publisher and subscriber for the given topic `T`
+    /// 
+    /// # Arguments
+    /// 
+    /// * `topic` - the topic to create a publisher and subscriber for
+    /// 
+    /// # Returns
+    /// 
+    /// A publisher and subscriber for the topic `T`
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bedroom,
+    ///     LivingRoom,
+    ///     Kitchen,
+    ///     Bathroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bedroom => String,
+    ///     House::LivingRoom => String,
+    ///     House::Kitchen => Vec<f32>,
+    ///     House::Bathroom => u8,
+    /// }
+    /// 
+    /// let mut node = crosstalk::UnboundedNode::<House>::new();
+    /// let (publisher, mut subscriber) = node.pubsub_blocking(House::Bedroom).unwrap();
+    /// publisher.write("hello".to_string());
+    /// assert_eq!(subscriber.try_read().unwrap(), "hello");
+    /// ```
+    pub fn pubsub_blocking<D: CrosstalkData>(&mut self, topic: T) -> Result<(Publisher<D, T>, Subscriber<D, T>), crate::Error> {
+        self.node.blocking_lock().pubsub(topic)
+    }
+
+    #[inline(always)]
+    /// Closes the given topic `T`
+    /// 
+    /// See [`BoundedNode::close_topic`]
+    pub async fn close_topic(&mut self, topic: T) -> bool {
+        self.node.lock().await.close_topic(topic)
+    }
+
+    #[inline(always)]
+    /// Closes the given topic `T`
+    /// 
+    /// See [`BoundedNode::close_topic`]
+    pub fn close_topic_blocking(&mut self, topic: T) -> bool {
+        self.node.blocking_lock().close_topic(topic)
+    }
+
+    #[inline(always)]
+    /// Shuts down the node
+    /// 
+    /// See [`BoundedNode::shutdown`]
+    pub async fn shutdown(&mut self) -> Shutdown {
+        self.node.lock().await.shutdown()
+    }
+
+    #[inline(always)]
+    /// Shuts down the node
+    /// 
+    /// See [`BoundedNode::shutdown`]
+    pub fn shutdown_blocking(&mut self) -> Shutdown {
+        self.node.blocking_lock().shutdown()
+    }
+
+    #[inline(always)]
+    /// Gets a snapshot of every topic of the node
+    /// 
+    /// See [`BoundedNode::topics`]
+    pub async fn topics(&self) -> Vec<(T, TopicInfo)> {
+        self.node.lock().await.topics()
+    }
+
+    #[inline(always)]
+    /// Gets a snapshot of every topic of the node
+    /// 
+    /// See [`BoundedNode::topics`]
+    pub fn topics_blocking(&self) -> Vec<(T, TopicInfo)> {
+        self.node.blocking_lock().topics()
+    }
+}
+
+/// The inner implementation of the node,
+/// which implements the [`AsTopic`] trait
+/// 
+/// This is auto-generated by the [`crosstalk_macros::init!`] macro
+/// 
+/// # Attributes
+/// 
+/// * `senders` - the senders of the topics of the node
+/// * `size` - the default size of each buffer
+/// * `closed` - whether the node has been shut down
+/// * `channels` - the senders and services of the node, to close them and take snapshots of them
+/// * `byte_publishers` - the publishers of [`BoundedNode::publish_bytes`], with the `serde` feature
+pub struct ImplementedBoundedNode<T> {
+    pub senders: HashMap<T, Box<dyn std::any::Any + 'static>>,
+    pub size: usize,
+    pub closed: bool,
+    channels: HashMap<T, Box<dyn AnyChannel>>,
+    #[cfg(feature = "serde")]
+    byte_publishers: HashMap<T, BytePublisher<T>>,
+}
+
+/// [`ImplementedBoundedNode`] implementation of [`Send`]
+unsafe impl<T> Send for ImplementedBoundedNode<T> {}
+/// [`ImplementedBoundedNode`] implementation of [`Sync`]
+unsafe impl<T> Sync for ImplementedBoundedNode<T> {}
+
+/// [`ImplementedBoundedNode`] implementation 
+impl<T> ImplementedBoundedNode<T>
+where
+    T: CrosstalkTopic,
+{
+    /// See [`BoundedNode::new`]
+    /// 
+    /// # Arguments
+    /// 
+    /// * `size` - the size of each buffer
+    pub fn new(size: usize) -> Self {
+        Self {
+            senders: HashMap::new(),
+            size,
+            closed: false,
+            channels: HashMap::new(),
+            #[cfg(feature = "serde")]
+            byte_publishers: HashMap::new(),
+        }
+    }
+
+    /// Gets the [`Sender`] of the given topic, creating
+    /// the underlying channel if it does not exist yet
+    /// 
+    /// This is not meant to be used directly, and is called
+    /// from the arms generated by [`crosstalk_macros::init!`]
+    /// 
+    /// # Arguments
+    /// 
+    /// * `topic` - the topic to get the [`Sender`] of
+    /// * `config` - the configuration of the topic, used if the channel is created
+    /// * `err` - the error to return if the existing [`Sender`] is not of type `D`
+    /// 
+    /// # Errors
+    /// 
+    /// * `err` - if the existing [`Sender`] is not of type `D`
+    /// * [`Error::NodeShutdown`] - if the node has been shut down
+    pub fn sender<D: CrosstalkData>(&mut self, topic: T, config: TopicConfig, err: crate::Error) -> Result<Sender<D>, crate::Error> {
+        if self.closed {
+            return Err(Error::NodeShutdown);
+        }
+        // topics closed by their last publisher keep their sender, which reopens them
+        if let Some(sender) = self.channels.get(&topic) {
+            return sender.as_any().downcast_ref::<Sender<D>>().cloned().ok_or(err);
+        }
+        // capacity is defined per topic in crosstalk::init!, otherwise
+        // size is defined during crosstalk::BoundedNode::new(size)
+        let capacity = match config.kind {
+            TopicKind::Watch => 1,
+            _ => config.capacity.unwrap_or(self.size),
+        };
+        let sender = Sender::new(Some(capacity), config, topic.name());
+        self.senders.insert(topic, Box::new(sender.clone()));
+        self.channels.insert(topic, Box::new(sender.clone()));
+        Ok(sender)
+    }
+
+    /// Closes the given topic, removing its channel from the node
+    /// 
+    /// See [`BoundedNode::close_topic`]
+    pub fn close_topic(&mut self, topic: T) -> bool {
+        self.senders.remove(&topic);
+        let closed = self.channels.remove(&topic).is_some_and(|sender| sender.close());
+        // dropped once the topic is closed, so that it is not closed as its last publisher
+        #[cfg(feature = "serde")]
+        self.byte_publishers.remove(&topic);
+        closed
+    }
+
+    /// Gets a snapshot of every topic of the node
+    /// 
+    /// See [`BoundedNode::topics`]
+    pub fn topics(&self) -> Vec<(T, TopicInfo)> {
+        self.channels.iter().map(|(topic, sender)| (*topic, sender.info())).collect()
+    }
+
+    /// Shuts down the node, closing every topic
+    /// 
+    /// See [`BoundedNode::shutdown`]
+    pub fn shutdown(&mut self) -> Shutdown {
+        self.closed = true;
+        self.senders.clear();
+        let shutdown = self.channels
+            .drain()
+            .fold(Shutdown::default(), |shutdown, (_, sender)| shutdown.merge(sender.shutdown()));
+        #[cfg(feature = "serde")]
+        self.byte_publishers.clear();
+        shutdown
+    }
+}
+
+/// The inner implementation of the unbounded node,
+/// which implements the [`AsTopic`] trait
+/// 
+/// This is auto-generated by the [`crosstalk_macros::init!`] macro
+/// 
+/// # Attributes
+/// 
+/// * `senders` - the senders of the topics of the node
+/// * `closed` - whether the node has been shut down
+/// * `channels` - the senders and services of the node, to close them and take snapshots of them
+/// * `byte_publishers` - the publishers of [`UnboundedNode::publish_bytes`], with the `serde` feature
+pub struct ImplementedUnboundedNode<T> {
+    pub senders: HashMap<T, Box<dyn std::any::Any + 'static>>,
+    pub closed: bool,
+    channels: HashMap<T, Box<dyn AnyChannel>>,
+    #[cfg(feature = "serde")]
+    byte_publishers: HashMap<T, BytePublisher<T>>,
+}
+
+/// [`ImplementedUnboundedNode`] implementation of [`Send`]
+unsafe impl<T> Send for ImplementedUnboundedNode<T> {}
+/// [`ImplementedUnboundedNode`] implementation of [`Sync`]
+unsafe impl<T> Sync for ImplementedUnboundedNode<T> {}
+
+/// [`ImplementedUnboundedNode`] implementation
+impl<T> ImplementedUnboundedNode<T>
+where
+    T: CrosstalkTopic,
+{
+    #[allow(clippy::new_without_default)]
+    /// See [`UnboundedNode::new`]
+    pub fn new() -> Self {
+        Self {
+            senders: HashMap::new(),
+            closed: false,
+            channels: HashMap::new(),
+            #[cfg(feature = "serde")]
+            byte_publishers: HashMap::new(),
+        }
+    }
+
+    /// Gets the [`Sender`] of the given topic, creating
+    /// the underlying channel if it does not exist yet
+    /// 
+    /// This is not meant to be used directly, and is called
+    /// from the arms generated by [`crosstalk_macros::init!`]
+    /// 
+    /// # Arguments
+    /// 
+    /// * `topic` - the topic to get the [`Sender`] of
+    /// * `config` - the configuration of the topic, used if the channel is created. The capacity is ignored
+    /// * `err` - the error to return if the existing [`Sender`] is not of type `D`
+    /// 
+    /// # Errors
+    /// 
+    /// * `err` - if the existing [`Sender`] is not of type `D`
+    /// * [`Error::NodeShutdown`] - if the node has been shut down
+    pub fn sender<D: CrosstalkData>(&mut self, topic: T, config: TopicConfig, err: crate::Error) -> Result<Sender<D>, crate::Error> {
+        if self.closed {
+            return Err(Error::NodeShutdown);
+        }
+        // topics closed by their last publisher keep their sender, which reopens them
+        if let Some(sender) = self.channels.get(&topic) {
+            return sender.as_any().downcast_ref::<Sender<D>>().cloned().ok_or(err);
+        }
+        let capacity = match config.kind {
+            TopicKind::Watch => Some(1),
+            _ => None,
+        };
+        let sender = Sender::new(capacity, config, topic.name());
+        self.senders.insert(topic, Box::new(sender.clone()));
+        self.channels.insert(topic, Box::new(sender.clone()));
+        Ok(sender)
+    }
+
+    /// Closes the given topic, removing its channel from the node
+    /// 
+    /// See [`BoundedNode::close_topic`]
+    pub fn close_topic(&mut self, topic: T) -> bool {
+        self.senders.remove(&topic);
+        let closed = self.channels.remove(&topic).is_some_and(|sender| sender.close());
+        // dropped once the topic is closed, so that it is not closed as its last publisher
+        #[cfg(feature = "serde")]
+        self.byte_publishers.remove(&topic);
+        closed
+    }
+
+    /// Gets a snapshot of every topic of the node
+    /// 
+    /// See [`BoundedNode::topics`]
+    pub fn topics(&self) -> Vec<(T, TopicInfo)> {
+        self.channels.iter().map(|(topic, sender)| (*topic, sender.info())).collect()
+    }
+
+    /// Shuts down the node, closing every topic
+    /// 
+    /// See [`BoundedNode::shutdown`]
+    pub fn shutdown(&mut self) -> Shutdown {
+        self.closed = true;
+        self.senders.clear();
+        let shutdown = self.channels
+            .drain()
+            .fold(Shutdown::default(), |shutdown, (_, sender)| shutdown.merge(sender.shutdown()));
+        #[cfg(feature = "serde")]
+        self.byte_publishers.clear();
+        shutdown
+    }
+}
+
+/// The sending half of a topic
+/// 
+/// One [`Sender`] exists per topic within a node, and is shared by every
+/// [`Publisher`] and [`Subscriber`] of that topic. Closing the [`Sender`]
+/// drops its channel, so that subscribers observe the end of the topic
+/// once they have read every sample still buffered
+/// 
+/// A topic closed by its last [`Publisher`] is reopened with a new channel by the
+/// next publisher or subscriber, and keeps its history, sequence numbers, publishers
+/// and metrics. Subscribers of the closed channel observe its end all the same, and
+/// have to subscribe again (e.g. with [`Subscriber::clone`]) to read from the new one.
+/// A topic closed by its node is never reopened
+/// 
+/// # Attributes
+/// 
+/// * `slot` - the channel of the topic, or `None` while the topic is closed, along with
+///   whether it is reopened, shared by every clone of the sender
+/// * `channel` - the channel the [`Publisher`] holding this sender writes to, held weakly
+///   so that closing the topic drops the channel
+/// * `publishers` - the live publishers of the topic, and their liveliness
+/// * `sequence` - the sequence number of the next sample published to the topic, if it is stamped
+/// * `observers` - the subscribers of the topic which have not yet observed its close
+/// * `kind` - the kind of the topic
+/// * `capacity` - the capacity of the channel, or [`None`] if it is unbounded
+/// * `history` - the latest samples replayed to new subscribers, if the topic has a history
+/// * `lifespan` - how long samples of the topic can be read for, if they expire
+/// * `deadline` - the maximum time expected between two samples, if the topic is periodic
+/// * `stamp` - whether samples are stamped, see [`TopicConfig`]
+/// * `deliver` - sends samples through the channel, recording them in the history. It is
+///   chosen where the sender is constructed, as the history and [`UnboundedSender`]s clone
+///   samples, so that publishing does not require `D: Clone`
+/// * `metrics` - the metrics of the topic, with the `metrics` feature
+/// * `wakers` - the tasks polling subscribers of the topic as streams, with the `stream` feature
+/// 
+/// This is not meant to be used directly, please
+/// use the [`crosstalk_macros::init!`] macro instead
+pub struct Sender<D> {
+    slot: Arc<std::sync::Mutex<Slot<D>>>,
+    channel: Weak<Channel<D>>,
+    publishers: Arc<Publishers>,
+    sequence: Arc<AtomicU64>,
+    observers: Arc<Observers>,
+    kind: TopicKind,
+    capacity: Option<usize>,
+    history: Option<Arc<History<Envelope<D>>>>,
+    lifespan: Option<std::time::Duration>,
+    deadline: Option<std::time::Duration>,
+    stamp: bool,
+    deliver: Deliver<D>,
+    #[cfg(feature = "metrics")]
+    metrics: TopicMetrics,
+    #[cfg(feature = "stream")]
+    wakers: Arc<Wakers>,
+}
+/// Sends a sample through the channel of a topic, recording it in the history
+/// of the topic, see [`Sender::deliver`] and [`Sender::deliver_plain`]
+type Deliver<D> = fn(&Channel<D>, Option<&History<Envelope<D>>>, Envelope<D>, bool) -> Result<usize, Envelope<D>>;
+/// [`Sender`] implementation
+impl<D: Clone> Sender<D> {
+    #[inline(always)]
+    /// Constructs a new open [`Sender`] without any publishers, where `capacity` is the
+    /// capacity of the channel (as resolved by the node), `config` the configuration of
+    /// the topic, and `topic` the name of the topic, see [`CrosstalkTopic::name`]
+    fn new(capacity: Option<usize>, config: TopicConfig, topic: &'static str) -> Self {
+        Self::with_deliver(capacity, config, topic, Self::deliver)
+    }
+
+    #[inline(always)]
+    /// Sends a sample through `channel` with [`Channel::send`] if `force`, or with
+    /// [`Channel::try_send`] otherwise, recording it in `history` unless it is handed back
+    /// 
+    /// Returns the number of receivers reached, which is only counted if not `force`
+    fn deliver(channel: &Channel<D>, history: Option<&History<Envelope<D>>>, sample: Envelope<D>, force: bool) -> Result<usize, Envelope<D>> {
+        let send = |sample| match force {
+            true => { channel.send(sample); Ok(0) },
+            false => channel.try_send(sample),
+        };
+        match history {
+            Some(history) => history.send(sample, send),
+            None => send(sample),
+        }
+    }
+
+    #[inline(always)]
+    /// Creates a new [`Receiver`] for the topic, reopening the topic
+    /// if it was closed by its last publisher, or [`None`] if the
+    /// topic was closed by its node
+    fn subscribe(&self) -> Option<Receiver<D>> {
+        let slot = self.open();
+        let channel = slot.channel.as_ref()?;
+        Some(match &self.history {
+            Some(history) => {
+                let (rcvr, samples) = history.subscribe(|| channel.subscribe());
+                Receiver::replay(samples, rcvr)
+            },
+            None => channel.subscribe(),
+        })
+    }
+}
+/// [`Sender`] implementation
+impl<D> Sender<D> {
+    #[inline(always)]
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+    /// Constructs a new open [`Sender`] like [`Sender::new`], sending samples with `deliver`
+    fn with_deliver(capacity: Option<usize>, config: TopicConfig, topic: &'static str, deliver: Deliver<D>) -> Self {
+        Self {
+            slot: Arc::new(std::sync::Mutex::new(Slot {
+                channel: Some(Arc::new(Channel::new(config.kind, capacity))),
+                reopen: true,
+            })),
+            channel: Weak::new(),
+            publishers: Arc::new(Publishers::new()),
+            sequence: Arc::new(AtomicU64::new(0)),
+            observers: Arc::new(Observers::default()),
+            kind: config.kind,
+            capacity,
+            history: (config.history > 0).then(|| Arc::new(History::new(config.history))),
+            lifespan: config.lifespan,
+            deadline: config.deadline,
+            stamp: config.stamp || config.lifespan.is_some() || config.deadline.is_some(),
+            deliver,
+            #[cfg(feature = "metrics")]
+            metrics: TopicMetrics::new(topic),
+            #[cfg(feature = "stream")]
+            wakers: Arc::new(Wakers::default()),
+        }
+    }
+
+    #[inline(always)]
+    /// Constructs a new [`Sender`] over a [`tokio::sync::broadcast`] channel of bare
+    /// samples, which is never reopened once closed, see [`Publisher::new`]
+    fn plain(buf: Arc<TokioSender<D>>) -> Self {
+        let sender = Self::with_deliver(None, TopicConfig::default(), std::any::type_name::<D>(), Self::deliver_plain);
+        *sender.lock() = Slot { channel: Some(Arc::new(Channel::Plain(buf))), reopen: false };
+        sender
+    }
+
+    #[inline(always)]
+    /// Sends a bare sample through a [`Channel::Plain`], which has no history
+    /// and never clones samples, see [`Sender::deliver`]
+    fn deliver_plain(channel: &Channel<D>, _: Option<&History<Envelope<D>>>, sample: Envelope<D>, _: bool) -> Result<usize, Envelope<D>> {
+        match channel {
+            Channel::Plain(buf) => buf.send(sample.data).map_err(|e| Envelope { data: e.0, ..sample }),
+            // plain senders are never reopened, so they only ever hold a plain channel
+            _ => unreachable!("plain senders only hold plain channels"),
+        }
+    }
+
+    #[inline(always)]
+    /// Locks the channel of the topic, reopening it
+    /// if it was closed by its last publisher
+    fn open(&self) -> std::sync::MutexGuard<'_, Slot<D>> {
+        let mut slot = self.lock();
+        if slot.channel.is_none() && slot.reopen {
+            slot.channel = Some(Arc::new(Channel::new(self.kind, self.capacity)));
+        }
+        slot
+    }
+
+    #[inline(always)]
+    /// Sends a sample to all receivers of the topic,
+    /// dropping it if the topic is closed
+    fn send(&self, sample: Envelope<D>) {
+        let Some(channel) = self.channel.upgrade() else {
+            #[cfg(feature = "metrics")]
+            self.metrics.closed();
+            return;
+        };
+        #[cfg(feature = "metrics")]
+        let subscribers = channel.subscriber_count();
+        let _ = (self.deliver)(&channel, self.history.as_deref(), sample, true);
+        #[cfg(feature = "metrics")]
+        self.metrics.published(subscribers, channel.len());
+        drop(channel);
+        #[cfg(feature = "stream")]
+        self.wakers.wake();
+    }
+
+    #[inline(always)]
+    /// Sends a sample only if there are receivers to deliver it to
+    /// 
+    /// Returns the number of receivers reached, or the sample if
+    /// there are none or the topic is closed
+    fn try_send(&self, sample: Envelope<D>) -> Result<usize, SendError<D>> {
+        match self.channel.upgrade() {
+            Some(channel) => {
+                let res = (self.deliver)(&channel, self.history.as_deref(), sample, false);
+                #[cfg(feature = "metrics")]
+                self.metrics.published(*res.as_ref().unwrap_or(&0), channel.len());
+                drop(channel);
+                #[cfg(feature = "stream")]
+                self.wakers.wake();
+                res.map_err(|sample| SendError { sample: sample.data, error: Error::NoSubscribers })
+            },
+            None => {
+                #[cfg(feature = "metrics")]
+                self.metrics.closed();
+                Err(SendError { sample: sample.data, error: Error::TopicClosed })
+            },
+        }
+    }
+
+    #[inline(always)]
+    /// Registers a new [`Publisher`] of the topic, reopening the topic if it
+    /// was closed by its last publisher, and writes to its channel from now on
+    fn add_publisher(&mut self) {
+        let slot = self.open();
+        let channel = slot.channel.as_ref().map_or_else(Weak::new, Arc::downgrade);
+        self.publishers.add();
+        drop(slot);
+        self.channel = channel;
+    }
+
+    #[inline(always)]
+    /// Locks the channel of the topic
+    fn lock(&self) -> std::sync::MutexGuard<'_, Slot<D>> {
+        self.slot.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    #[inline(always)]
+    /// Closes the topic for good, returning `false` if it was already closed
+    fn close(&self) -> bool {
+        let mut slot = self.lock();
+        slot.reopen = false;
+        let closed = slot.channel.take().is_some();
+        drop(slot);
+        #[cfg(feature = "stream")]
+        self.wakers.wake();
+        closed
+    }
+
+    #[inline(always)]
+    /// Unregisters a [`Publisher`] of the topic, closing
+    /// the topic if it was the last one
+    fn remove_publisher(&self) {
+        let mut slot = self.lock();
+        if self.publishers.remove() {
+            slot.channel = None;
+            drop(slot);
+            #[cfg(feature = "stream")]
+            self.wakers.wake();
+        }
+    }
+}
+/// [`Sender`] implementation of [`Clone`]
+impl<D> Clone for Sender<D> {
+    fn clone(&self) -> Self {
+        Self {
+            slot: self.slot.clone(),
+            channel: self.channel.clone(),
+            publishers: self.publishers.clone(),
+            sequence: self.sequence.clone(),
+            observers: self.observers.clone(),
+            kind: self.kind,
+            capacity: self.capacity,
+            history: self.history.clone(),
+            lifespan: self.lifespan,
+            deadline: self.deadline,
+            stamp: self.stamp,
+            deliver: self.deliver,
+            #[cfg(feature = "metrics")]
+            metrics: self.metrics.clone(),
+            #[cfg(feature = "stream")]
+            wakers: self.wakers.clone(),
+        }
+    }
 }
+/// [`Sender`] implementation of [`AnyChannel`]
+impl<D: Clone + 'static> AnyChannel for Sender<D> {
+    fn close(&self) -> bool {
+        Sender::close(self)
+    }
+
+    fn shutdown(&self) -> Shutdown {
+        Sender::close(self);
+        Shutdown::new(self.observers.clone())
+    }
+
+    fn is_closed(&self) -> bool {
+        self.lock().channel.is_none()
+    }
+
+    fn info(&self) -> TopicInfo {
+        let slot = self.lock();
+        let channel = slot.channel.as_deref();
+        TopicInfo {
+            kind: self.kind,
+            service: false,
+            data_type: std::any::type_name::<D>(),
+            capacity: self.capacity,
+            publishers: self.publishers.count(),
+            subscribers: channel.map_or(0, Channel::subscriber_count),
+            queued: channel.map_or(Some(0), Channel::len),
+            closed: channel.is_none(),
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+
+/// The channel of a topic, shared by every clone of its [`Sender`]
+/// 
+/// Publishers are registered and unregistered while the slot is locked, along with
+/// opening and closing the channel, so that a new publisher never gets the channel
+/// which the last publisher is closing
+/// 
+/// # Attributes
+/// 
+/// * `channel` - the channel of the topic, or [`None`] while the topic is closed
+/// * `reopen` - whether the channel is reopened once closed, which
+///   is the case unless the topic was closed by its node
+struct Slot<D> {
+    channel: Option<Arc<Channel<D>>>,
+    reopen: bool,
+}
+
+/// A type-erased [`Sender`] or `Service`, as stored in a node
+/// 
+/// This is not meant to be used directly, and is implemented
+/// for every channel a node can hold
+pub trait AnyChannel: std::any::Any {
+    /// Closes the channel, returning `false` if it was already closed
+    fn close(&self) -> bool;
+
+    /// Closes the channel, returning a [`Shutdown`] which resolves
+    /// once every subscriber of the channel has observed the close
+    fn shutdown(&self) -> Shutdown;
+
+    /// Whether the channel is closed
+    fn is_closed(&self) -> bool;
+
+    /// A snapshot of the channel, see [`BoundedNode::topics`]
+    fn info(&self) -> TopicInfo;
+
+    /// Gets the channel as [`std::any::Any`], to downcast it
+    fn as_any(&self) -> &dyn std::any::Any;
+}
+
+#[derive(Clone)]
+/// A sample as it is sent through the channel of a topic
+/// 
+/// # Attributes
+/// 
+/// * `data` - the sample
+/// * `publisher` - the id of the [`Publisher`] of the sample
+/// * `stamp` - the sequence number of the sample and when it was published,
+///   if the topic is stamped, see [`TopicConfig`]
+/// * `span` - the id of the span of the publisher, with the `tracing` feature
+///   if the publisher propagates its span, see [`Publisher::set_trace`]
+struct Envelope<D> {
+    data: D,
+    publisher: u64,
+    stamp: Option<Box<Stamp>>,
+    #[cfg(feature = "tracing")]
+    span: Option<tracing::Id>,
+}
+/// [`Envelope`] implementation
+impl<D> Envelope<D> {
+    #[inline(always)]
+    /// Wraps a bare sample, received without knowing
+    /// its publisher, see [`Channel::Plain`]
+    fn plain(data: D) -> Self {
+        Self {
+            data,
+            publisher: u64::MAX,
+            stamp: None,
+            #[cfg(feature = "tracing")]
+            span: None,
+        }
+    }
+
+    #[inline(always)]
+    /// Unwraps the sample
+    fn into_inner(self) -> D {
+        self.data
+    }
+}
+
+/// The channel of a topic, held by its [`Sender`]
+/// 
+/// # Variants
+/// 
+/// * `Bounded` - a [`tokio::sync::broadcast`] channel, created by a [`BoundedNode`]
+/// * `Unbounded` - an [`UnboundedSender`], created by an [`UnboundedNode`]
+/// * `Watch` - a [`tokio::sync::watch`] channel, created for [`TopicKind::Watch`] topics.
+///   The value is [`None`] until the first sample is published
+/// * `Queue` - an `async_channel` channel, created for `TopicKind::Queue` topics.
+///   A receiver is kept so that the channel stays open while there are no subscribers
+/// * `Plain` - a [`tokio::sync::broadcast`] channel of bare samples, given to
+///   [`Publisher::new`] or [`Subscriber::new`]
+enum Channel<D> {
+    Bounded(TokioSender<Envelope<D>>),
+    Unbounded(UnboundedSender<Envelope<D>>),
+    Watch(TokioWatchSender<Option<Envelope<D>>>),
+    #[cfg(feature = "queue")]
+    Queue(AsyncSender<Envelope<D>>, AsyncReceiver<Envelope<D>>),
+    Plain(Arc<TokioSender<D>>),
+}
+/// [`Channel`] implementation
+impl<D> Channel<D> {
+    #[inline(always)]
+    /// Constructs a new [`Channel`] of the given kind, which is
+    /// unbounded if `capacity` is [`None`] (ignored for watch topics)
+    fn new(kind: TopicKind, capacity: Option<usize>) -> Self {
+        match (kind, capacity) {
+            (TopicKind::Broadcast, Some(capacity)) => Channel::Bounded(TokioSender::new(capacity)),
+            (TopicKind::Broadcast, None) => Channel::Unbounded(UnboundedSender::new()),
+            (TopicKind::Watch, _) => Channel::Watch(TokioWatchSender::new(None)),
+            #[cfg(feature = "queue")]
+            (TopicKind::Queue, Some(capacity)) => {
+                let (sender, receiver) = async_channel::bounded(capacity);
+                Channel::Queue(sender, receiver)
+            },
+            #[cfg(feature = "queue")]
+            (TopicKind::Queue, None) => {
+                let (sender, receiver) = async_channel::unbounded();
+                Channel::Queue(sender, receiver)
+            },
+        }
+    }
+
+    #[inline(always)]
+    /// Creates a new [`Receiver`] for the topic
+    fn subscribe(&self) -> Receiver<D> {
+        match self {
+            Channel::Bounded(buf) => Receiver::Bounded(buf.subscribe()),
+            Channel::Unbounded(buf) => Receiver::Unbounded(buf.subscribe()),
+            Channel::Watch(buf) => {
+                // a new receiver has already "seen" the current value,
+                // so mark it as changed to deliver it to late subscribers
+                let mut rcvr = buf.subscribe();
+                if rcvr.borrow().is_some() {
+                    rcvr.mark_changed();
+                }
+                Receiver::Watch(rcvr)
+            },
+            #[cfg(feature = "queue")]
+            Channel::Queue(_, buf) => Receiver::Queue(buf.clone()),
+            Channel::Plain(buf) => Receiver::Plain(buf.subscribe()),
+        }
+    }
+
+    #[inline(always)]
+    /// The number of receivers of the topic
+    fn subscriber_count(&self) -> usize {
+        match self {
+            Channel::Bounded(buf) => buf.receiver_count(),
+            Channel::Unbounded(buf) => buf.receiver_count(),
+            Channel::Watch(buf) => buf.receiver_count(),
+            // the channel holds a receiver to keep itself open
+            #[cfg(feature = "queue")]
+            Channel::Queue(buf, _) => buf.receiver_count().saturating_sub(1),
+            Channel::Plain(buf) => buf.receiver_count(),
+        }
+    }
+
+    #[inline(always)]
+    /// The number of samples buffered in the channel, or [`None`] for
+    /// [`UnboundedSender`]s, which buffer samples per receiver
+    fn len(&self) -> Option<usize> {
+        match self {
+            Channel::Bounded(buf) => Some(buf.len()),
+            Channel::Unbounded(_) => None,
+            Channel::Watch(buf) => Some(buf.borrow().is_some() as usize),
+            #[cfg(feature = "queue")]
+            Channel::Queue(buf, _) => Some(buf.len()),
+            Channel::Plain(buf) => Some(buf.len()),
+        }
+    }
+}
+/// [`Channel`] implementation
+impl<D: Clone> Channel<D> {
+    #[inline(always)]
+    /// Sends a sample to all receivers of the topic
+    fn send(&self, sample: Envelope<D>) {
+        match self {
+            Channel::Bounded(buf) => { let _ = buf.send(sample); },
+            Channel::Unbounded(buf) => buf.send(sample),
+            // send_replace stores the sample even without receivers,
+            // so that late subscribers can observe it
+            Channel::Watch(buf) => { buf.send_replace(Some(sample)); },
+            // force_send evicts the oldest sample if the queue is full,
+            // matching the behavior of the bounded broadcast channels
+            #[cfg(feature = "queue")]
+            Channel::Queue(buf, _) => { let _ = buf.force_send(sample); },
+            Channel::Plain(buf) => { let _ = buf.send(sample.data); },
+        }
+    }
+
+    #[inline(always)]
+    /// Sends a sample only if there are receivers to deliver it to
+    /// 
+    /// Returns the number of receivers reached, or the
+    /// sample if there are none (in which case it is not stored)
+    fn try_send(&self, sample: Envelope<D>) -> Result<usize, Envelope<D>> {
+        match self {
+            Channel::Bounded(buf) => buf.send(sample).map_err(|e| e.0),
+            Channel::Unbounded(buf) => buf.try_send(sample),
+            Channel::Watch(buf) => match buf.receiver_count() {
+                0 => Err(sample),
+                n => { buf.send_replace(Some(sample)); Ok(n) },
+            },
+            // the channel holds a receiver to keep itself open, so only
+            // the other receivers belong to subscribers, and each sample is
+            // delivered to exactly one of the competing subscribers
+            #[cfg(feature = "queue")]
+            Channel::Queue(buf, _) => match buf.receiver_count().saturating_sub(1) {
+                0 => Err(sample),
+                _ => { let _ = buf.force_send(sample); Ok(1) },
+            },
+            Channel::Plain(buf) => buf.send(sample.data).map_err(|e| Envelope { data: e.0, ..sample }),
+        }
+    }
+}
+/// An unbounded broadcasting sender
+/// 
+/// [`tokio`] does not provide an unbounded broadcast channel,
+/// so every receiver is given its own unbounded [`tokio::sync::mpsc`]
+/// channel, and each sample is cloned into all of them
+/// 
+/// # Attributes
+/// 
+/// * `bufs` - the senders of every receiver subscribed to the topic
+pub struct UnboundedSender<D> {
+    bufs: Arc<std::sync::Mutex<Vec<TokioUnboundedSender<D>>>>,
+}
+/// [`UnboundedSender`] implementation
+impl<D> UnboundedSender<D> {
+    #[inline(always)]
+    #[allow(clippy::new_without_default)]
+    /// Constructs a new [`UnboundedSender`] without any receivers
+    pub fn new() -> Self {
+        Self { bufs: Arc::new(std::sync::Mutex::new(Vec::new())) }
+    }
+
+    /// The number of receivers which have not been dropped
+    fn receiver_count(&self) -> usize {
+        let mut bufs = self.bufs.lock().unwrap_or_else(|e| e.into_inner());
+        bufs.retain(|buf| !buf.is_closed());
+        bufs.len()
+    }
+
+    /// Creates a new receiver, which will receive
+    /// every sample sent after this call
+    fn subscribe(&self) -> TokioUnboundedReceiver<D> {
+        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+        self.bufs.lock().unwrap_or_else(|e| e.into_inner()).push(sender);
+        receiver
+    }
+}
+/// [`UnboundedSender`] implementation
+impl<D: Clone> UnboundedSender<D> {
+    #[inline(always)]
+    /// Sends a sample to every receiver, removing
+    /// any receivers which have been dropped
+    fn send(&self, sample: D) {
+        let _ = self.try_send(sample);
+    }
+
+    /// Sends a sample to every receiver, removing any receivers which
+    /// have been dropped, and returning the sample if there are none left
+    fn try_send(&self, sample: D) -> Result<usize, D> {
+        let mut bufs = self.bufs.lock().unwrap_or_else(|e| e.into_inner());
+        bufs.retain(|buf| !buf.is_closed());
+        match bufs.split_last() {
+            Some((last, rest)) => {
+                for buf in rest {
+                    let _ = buf.send(sample.clone());
+                }
+                let _ = last.send(sample);
+                Ok(bufs.len())
+            },
+            None => Err(sample),
+        }
+    }
+}
+/// [`UnboundedSender`] implementation of [`Clone`]
+impl<D> Clone for UnboundedSender<D> {
+    fn clone(&self) -> Self {
+        Self { bufs: self.bufs.clone() }
+    }
+}
+
+/// The latest samples of a topic, replayed to new subscribers
+/// 
+/// # Attributes
+/// 
+/// * `depth` - the maximum number of samples kept
+/// * `samples` - the latest samples, oldest first
+struct History<D> {
+    depth: usize,
+    samples: std::sync::Mutex<VecDeque<D>>,
+}
+/// [`History`] implementation
+impl<D> History<D> {
+    #[inline(always)]
+    /// Constructs a new empty [`History`] of `depth` samples
+    fn new(depth: usize) -> Self {
+        Self { depth, samples: std::sync::Mutex::new(VecDeque::with_capacity(depth)) }
+    }
+}
+/// [`History`] implementation
+impl<D: Clone> History<D> {
+    #[inline(always)]
+    /// Sends a sample with `send`, recording it unless it is handed back
+    /// 
+    /// The history stays locked while sending, so that new subscribers
+    /// replay exactly the samples sent before they subscribed
+    fn send<R>(&self, sample: D, send: impl FnOnce(D) -> Result<R, D>) -> Result<R, D> {
+        let mut samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        let res = send(sample.clone());
+        if res.is_ok() {
+            if samples.len() == self.depth {
+                samples.pop_front();
+            }
+            samples.push_back(sample);
+        }
+        res
+    }
+
+    #[inline(always)]
+    /// Subscribes with `subscribe`, along with the samples to replay
+    fn subscribe<R>(&self, subscribe: impl FnOnce() -> R) -> (R, VecDeque<D>) {
+        let samples = self.samples.lock().unwrap_or_else(|e| e.into_inner());
+        (subscribe(), samples.clone())
+    }
+}
+
+/// The id of the next [`Publisher`]
+static NEXT_PUBLISHER: AtomicU64 = AtomicU64::new(0);
+
+/// A `crosstalk` [`Publisher`]
+/// 
+/// # Attributes
+/// 
+/// * `topic` - the topic of the publisher
+/// * `buf` - the buffer which broadcasts the data
+/// * `id` - the process-unique id of the publisher, see [`Sample::publisher`]
+/// * `deadline` - the deadline of the publisher, if the topic has one
+/// * `sink` - the [`SinkPolicy`] of the publisher, with the `sink` feature
+/// * `trace` - whether the publisher propagates its span to subscribers, with the `tracing` feature
+/// 
+/// # Type Parameters
+/// 
+/// * `T` - the topic of the publisher
+/// * `D` - the data type of the publisher
+/// 
+/// This is not meant to be used directly, please
+/// use the [`crosstalk_macros::init!`] macro instead
+/// and produce a [`Publisher`] with [`BoundedNode::publisher`]
+/// or [`BoundedNode::pubsub`]
+pub struct Publisher<D, T> {
+    pub topic: T,
+    buf: Sender<D>,
+    id: u64,
+    deadline: Option<std::sync::Mutex<Deadline>>,
+    #[cfg(feature = "sink")]
+    sink: SinkPolicy,
+    #[cfg(feature = "tracing")]
+    trace: bool,
+}
+/// [`Publisher`] implementation
+impl<D, T> Publisher<D, T> {
+    #[inline(always)]
+    /// Constructs a [`Publisher`] writing to a [`tokio::sync::broadcast`] channel
+    /// outside of any node, to be read by subscribers of the same channel (see
+    /// [`Subscriber::new`]). Samples are sent bare, without their publisher or stamp
+    /// 
+    /// Publishers of a node are produced with [`BoundedNode::publisher`]
+    pub fn new(topic: T, buf: TokioSender<D>) -> Self {
+        Self::from_sender(topic, Sender::plain(Arc::new(buf)))
+    }
+
+    #[inline(always)]
+    /// See [`BoundedNode::publisher`]
+    pub fn from_sender(topic: T, mut buf: Sender<D>) -> Self {
+        buf.add_publisher();
+        Self {
+            topic,
+            deadline: buf.deadline.map(|period| std::sync::Mutex::new(Deadline::new(period))),
+            buf,
+            id: NEXT_PUBLISHER.fetch_add(1, Ordering::Relaxed),
+            #[cfg(feature = "sink")]
+            sink: SinkPolicy::default(),
+            #[cfg(feature = "tracing")]
+            trace: false,
+        }
+    }
+
+    #[inline(always)]
+    /// The process-unique id of the publisher, which every [`Sample`]
+    /// it publishes carries. Clones of a publisher have their own id
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    #[inline(always)]
+    /// Publishes data to a topic, broadcasting it to all subscribers
+    /// 
+    /// The sample is dropped if the topic has been closed
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sample` - the sample to publish
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bedroom,
+    ///     LivingRoom,
+    ///     Kitchen,
+    ///     Bathroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bedroom => String,
+    ///     House::LivingRoom => String,
+    ///     House::Kitchen => Vec<f32>,
+    ///     House::Bathroom => u8,
+    /// }
+    /// 
+    /// let mut node = crosstalk::BoundedNode::<House>::new(10);
+    /// let (publisher, mut subscriber) = node.pubsub_blocking(House::Bedroom).unwrap();
+    /// publisher.write("hello".to_string());
+    /// std::thread::spawn(move || {
+    ///     assert_eq!(subscriber.try_read().unwrap(), "hello");
+    /// });
+    /// ```
+    pub fn write(&self, sample: D) {
+        self.buf.send(self.envelope(sample));
+    }
+
+    #[inline(always)]
+    /// Publishes data to a topic, reporting whether anyone received it
+    /// 
+    /// Unlike [`Publisher::write`], the sample is not published at all if
+    /// there are no subscribers, including the latest value of
+    /// [`TopicKind::Watch`] topics and the backlog of `TopicKind::Queue` topics
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sample` - the sample to publish
+    /// 
+    /// # Returns
+    /// 
+    /// The number of subscribers the sample was delivered to. For
+    /// `TopicKind::Queue` topics this is always 1, as only one
+    /// subscriber receives each sample
+    /// 
+    /// # Errors
+    /// 
+    /// A [`SendError`] handing back the sample, with [`Error::NoSubscribers`]
+    /// if there are no subscribers to the topic, or [`Error::TopicClosed`]
+    /// if the topic has been closed
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bedroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bedroom => String,
+    /// }
+    /// 
+    /// let mut node = crosstalk::BoundedNode::<House>::new(10);
+    /// let publisher = node.publisher_blocking::<String>(House::Bedroom).unwrap();
+    /// let err = publisher.try_write("hello".to_string()).unwrap_err();
+    /// assert!(matches!(err.error, crosstalk::Error::NoSubscribers));
+    /// assert_eq!(err.into_inner(), "hello");
+    /// 
+    /// let _subscriber = node.subscriber_blocking::<String>(House::Bedroom).unwrap();
+    /// assert_eq!(publisher.try_write("hello".to_string()).unwrap(), 1);
+    /// ```
+    pub fn try_write(&self, sample: D) -> Result<usize, SendError<D>> {
+        self.buf.try_send(self.envelope(sample))
+    }
+
+    #[inline(always)]
+    /// Wraps a sample to be sent through the channel of the topic
+    fn envelope(&self, data: D) -> Envelope<D> {
+        self.wrote();
+        self.buf.publishers.assert_alive();
+        Envelope {
+            data,
+            publisher: self.id,
+            stamp: self.buf.stamp.then(|| Box::new(Stamp {
+                sequence: self.buf.sequence.fetch_add(1, Ordering::Relaxed),
+                published: tokio::time::Instant::now().into_std(),
+                timestamp: std::time::SystemTime::now(),
+            })),
+            #[cfg(feature = "tracing")]
+            span: self.trace.then(tracing::Span::current).and_then(|span| span.id()),
+        }
+    }
+}
+/// [`Publisher`] implementation of [`Clone`]
+impl<D, T: Clone> Clone for Publisher<D, T> {
+    fn clone(&self) -> Self {
+        let mut buf = self.buf.clone();
+        buf.add_publisher();
+        Self {
+            topic: self.topic.clone(),
+            buf,
+            id: NEXT_PUBLISHER.fetch_add(1, Ordering::Relaxed),
+            deadline: self.buf.deadline.map(|period| std::sync::Mutex::new(Deadline::new(period))),
+            #[cfg(feature = "sink")]
+            sink: self.sink,
+            #[cfg(feature = "tracing")]
+            trace: self.trace,
+        }
+    }
+}
+/// [`Publisher`] implementation of [`Drop`]
+impl<D, T> Drop for Publisher<D, T> {
+    /// Closes the topic if this is the last publisher, so that
+    /// subscribers observe the end of the topic
+    fn drop(&mut self) {
+        self.buf.remove_publisher();
+    }
+}
+
+/// A `crosstalk` [`Subscriber`]
+/// 
+/// # Attributes
+/// 
+/// * `topic` - the topic of the subscriber
+/// * `rcvr` - the receiver of the subscriber
+/// * `sndr` - the sender for the topic. This is used to spawn multiple receivers upon [`Subscriber::clone`]
+/// * `lag` - the [`LagPolicy`] of the subscriber, and the number of samples it has lost
+/// * `observer` - tracks whether the subscriber has observed the close of the topic, see [`BoundedNode::shutdown`]
+/// 
+/// # Type Parameters
+/// 
+/// * `T` - the topic of the subscriber
+/// * `D` - the data type of the subscriber
+/// 
+/// This is not meant to be used directly, please
+/// use the [`crosstalk_macros::init!`] macro instead
+/// and produce a [`Subscriber`] with [`BoundedNode::subscriber`]
+/// or [`BoundedNode::pubsub`]
+pub struct Subscriber<D, T> {
+    pub topic: T,
+    rcvr: Receiver<D>,
+    sndr: Sender<D>,
+    lag: Lag,
+    observer: Observer,
+}
+/// [`Subscriber`] implementation 
+impl<D: Clone, T: Clone> Subscriber<D, T> {
+    #[inline(always)]
+    /// Constructs a [`Subscriber`] reading from a [`tokio::sync::broadcast`] channel
+    /// outside of any node, from `rcvr` if given or from a new receiver of `sndr`
+    /// otherwise. Clones of the subscriber subscribe to `sndr`
+    /// 
+    /// Samples read from the channel do not carry the id of their publisher
+    /// ([`Sample::publisher`] is [`u64::MAX`]) nor a stamp. Subscribers of a node
+    /// are produced with [`BoundedNode::subscriber`]
+    pub fn new(
+        topic: T,
+        rcvr: Option<TokioReceiver<D>>,
+        sndr: Arc<TokioSender<D>>,
+    ) -> Self {
+        let mut subscriber = Self::from_sender(topic, Sender::plain(sndr));
+        if let Some(rcvr) = rcvr {
+            subscriber.rcvr = Receiver::Plain(rcvr);
+        }
+        subscriber
+    }
+
+    #[inline(always)]
+    /// See [`BoundedNode::subscriber`]
+    pub fn from_sender(
+        topic: T,
+        sndr: Sender<D>,
+    ) -> Self {
+        Self {
+            topic,
+            rcvr: sndr.subscribe().unwrap_or_else(Receiver::closed),
+            observer: sndr.observers.observer(),
+            lag: Lag::new(LagPolicy::default(), &sndr),
+            sndr,
+        }
+    }
+
+    #[inline(always)]
+    /// The [`LagPolicy`] of the subscriber
+    pub fn lag_policy(&self) -> LagPolicy {
+        self.lag.policy
+    }
+
+    #[inline(always)]
+    /// Sets how the subscriber handles lost samples when it falls behind
+    /// 
+    /// Clones of the subscriber inherit its [`LagPolicy`]
+    /// 
+    /// # Arguments
+    /// 
+    /// * `policy` - the [`LagPolicy`] of the subscriber
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bathroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bathroom => u8,
+    /// }
+    /// 
+    /// let mut node = crosstalk::BoundedNode::<House>::new(2);
+    /// let (publisher, mut subscriber) = node.pubsub_blocking(House::Bathroom).unwrap();
+    /// subscriber.set_lag_policy(crosstalk::LagPolicy::Latest);
+    /// for i in 0..5u8 {
+    ///     publisher.write(i);
+    /// }
+    /// assert_eq!(subscriber.try_read(), Some(4));
+    /// assert_eq!(subscriber.lagged_total(), 4);
+    /// ```
+    pub fn set_lag_policy(&mut self, policy: LagPolicy) {
+        self.lag.policy = policy;
+    }
+
+    #[inline(always)]
+    /// The total number of samples this subscriber has lost by
+    /// falling behind, since it was created
+    /// 
+    /// Lost samples are not counted with [`LagPolicy::Skip`]
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bathroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bathroom => u8,
+    /// }
+    /// 
+    /// let mut node = crosstalk::BoundedNode::<House>::new(2);
+    /// let (publisher, mut subscriber) = node.pubsub_blocking(House::Bathroom).unwrap();
+    /// subscriber.set_lag_policy(crosstalk::LagPolicy::Count);
+    /// for i in 0..5u8 {
+    ///     publisher.write(i);
+    /// }
+    /// assert_eq!(subscriber.try_read(), Some(3));
+    /// assert_eq!(subscriber.lagged_total(), 3);
+    /// ```
+    pub fn lagged_total(&self) -> u64 {
+        self.lag.total
+    }
+
+    #[inline(always)]
+    /// The lifespan of the samples of the topic, or [`None`] if they never expire
+    /// 
+    /// Declared with `#[lifespan(<Duration>)]` in [`crosstalk_macros::init!`]
+    pub fn lifespan(&self) -> Option<std::time::Duration> {
+        self.lag.lifespan
+    }
+
+    #[inline(always)]
+    /// The total number of samples this subscriber has skipped because
+    /// they outlived their lifespan, since it was created
+    /// 
+    /// Expired samples are counted separately from lost samples,
+    /// see [`Subscriber::lagged_total`]
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bathroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     #[lifespan(std::time::Duration::from_millis(100))]
+    ///     House::Bathroom => u8,
+    /// }
+    /// 
+    /// #[tokio::main(flavor = "current_thread", start_paused = true)]
+    /// async fn main() {
+    ///     let mut node = crosstalk::BoundedNode::<House>::new(10);
+    ///     let (publisher, mut subscriber) = node.pubsub(House::Bathroom).await.unwrap();
+    ///     publisher.write(0u8);
+    ///     tokio::time::advance(std::time::Duration::from_millis(200)).await;
+    ///     publisher.write(1u8);
+    ///     assert_eq!(subscriber.try_read(), Some(1));
+    ///     assert_eq!(subscriber.expired_total(), 1);
+    /// }
+    /// ```
+    pub fn expired_total(&self) -> u64 {
+        self.lag.expired
+    }
+
+    #[inline(always)]
+    /// Asynchronous blocking read from the [`TokioReceiver`]
+    /// 
+    /// The sequential equivalent to this function is [`Subscriber::read_blocking`]
+    /// which can be used outside of an asynchronous context
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bedroom,
+    ///     LivingRoom,
+    ///     Kitchen,
+    ///     Bathroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bedroom => String,
+    ///     House::LivingRoom => String,
+    ///     House::Kitchen => Vec<f32>,
+    ///     House::Bathroom => u8,
+    /// }
+    /// 
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut node = crosstalk::BoundedNode::<House>::new(10);
+    ///     let (publisher, mut subscriber) = node.pubsub(House::Bedroom).await.unwrap();
+    ///     publisher.write("hello".to_string());
+    ///     assert_eq!(subscriber.read().await, Some("hello".to_string()));
+    /// }
+    /// ```
+    pub async fn read(&mut self) -> Option<D> {
+        let res = self.rcvr.read(&mut self.lag).await;
+        ok_or_log(self.observe(res)).map(Envelope::into_inner)
+    }
+    
+    #[inline(always)]
+    /// Non-blocking read from [`TokioReceiver`]
+    /// Upon immediate failure, [`None`] will be returned
+    /// 
+    /// Difference between this function and [`Subscriber::try_read_raw`]
+    /// is that this function continuously loops upon [`tokio`] error of
+    /// [`tokio::sync::broadcast::error::TryRecvError::Lagged`], looping
+    /// until a valid message is received OR the buffer is determined to be
+    /// empty
+    /// 
+    /// [`Subscriber::try_read_raw`] will return [`None`] upon
+    /// [`tokio::sync::broadcast::error::TryRecvError::Lagged`], which
+    /// can cause some unexpected behavior
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bedroom,
+    ///     LivingRoom,
+    ///     Kitchen,
+    ///     Bathroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bedroom => String,
+    ///     House::LivingRoom => String,
+    ///     House::Kitchen => Vec<f32>,
+    ///     House::Bathroom => u8,
+    /// }
+    /// 
+    /// let mut node = crosstalk::BoundedNode::<House>::new(10);
+    /// let (publisher, mut subscriber) = node.pubsub_blocking(House::Bedroom).unwrap();
+    /// publisher.write("hello".to_string());
+    /// assert_eq!(subscriber.try_read(), Some(String::from("hello")));
+    /// assert_eq!(subscriber.try_read(), None);
+    /// ```
+    pub fn try_read(&mut self) -> Option<D> {
+        let res = self.rcvr.try_read(&mut self.lag);
+        ok_or_log(self.observe(res)).map(Envelope::into_inner)
+    }
+
+    #[inline(always)]
+    /// Non-blocking read from [`TokioReceiver`], returning
+    /// [`None`] if there are no messages available or if 
+    /// [`tokio::sync::broadcast::error::TryRecvError::Lagged`] occurs.
+    /// 
+    /// This function can cause some unexpected behavior. It is recommended
+    /// to use [`Subscriber::try_read`] instead.
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bedroom,
+    ///     LivingRoom,
+    ///     Kitchen,
+    ///     Bathroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bedroom => String,
+    ///     House::LivingRoom => String,
+    ///     House::Kitchen => Vec<f32>,
+    ///     House::Bathroom => u8,
+    /// }
+    /// 
+    /// let mut node = crosstalk::BoundedNode::<House>::new(10);
+    /// let (publisher, mut subscriber) = node.pubsub_blocking(House::Bedroom).unwrap();
+    /// publisher.write("hello".to_string());
+    /// assert_eq!(subscriber.try_read_raw(), Some(String::from("hello")));
+    /// assert_eq!(subscriber.try_read_raw(), None);
+    /// ```
+    pub fn try_read_raw(&mut self) -> Option<D> {
+        let res = self.rcvr.try_read_raw(&mut self.lag);
+        ok_or_log(self.observe(res)).map(Envelope::into_inner)
+    }
+    
+    #[inline(always)]
+    /// Sequential blocking read from [`TokioReceiver`]
+    /// 
+    /// The asynchronous equivalent to this function is [`Subscriber::read`], 
+    /// which must be used in an asynchronous context with `.await`
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bedroom,
+    ///     LivingRoom,
+    ///     Kitchen,
+    ///     Bathroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bedroom => String,
+    ///     House::LivingRoom => String,
+    ///     House::Kitchen => Vec<f32>,
+    ///     House::Bathroom => u8,
+    /// }
+    /// 
+    /// let mut node = crosstalk::BoundedNode::<House>::new(10);
+    /// let (publisher, mut subscriber) = node.pubsub_blocking(House::Bedroom).unwrap();
+    /// publisher.write("hello".to_string());
+    /// assert_eq!(subscriber.read_blocking(), Some("hello".to_string()));
+    /// ```
+    pub fn read_blocking(&mut self) -> Option<D> {
+        let res = self.rcvr.read_blocking(&mut self.lag);
+        ok_or_log(self.observe(res)).map(Envelope::into_inner)
+    }
+    
+    #[inline(always)]
+    /// Asynchronous non-blocking read from [`TokioReceiver`]
+    /// with a given timeout. After the timeout if there are no messages,
+    /// returns [`None`].
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bedroom,
+    ///     LivingRoom,
+    ///     Kitchen,
+    ///     Bathroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bedroom => String,
+    ///     House::LivingRoom => String,
+    ///     House::Kitchen => Vec<f32>,
+    ///     House::Bathroom => u8,
+    /// }
+    /// 
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut node = crosstalk::BoundedNode::<House>::new(10);
+    ///     let (publisher, mut subscriber) = node.pubsub(House::Bedroom).await.unwrap();
+    ///     publisher.write("hello".to_string());
+    ///     assert_eq!(subscriber.read_timeout(std::time::Duration::from_millis(100)).await, Some("hello".to_string()));
+    /// }
+    /// ```
+    pub async fn read_timeout(&mut self, timeout: std::time::Duration) -> Option<D> {
+        let res = self.rcvr.read_timeout(timeout, &mut self.lag).await;
+        ok_or_log(self.observe(res)).map(Envelope::into_inner)
+    }
+
+    #[inline(always)]
+    /// Asynchronous blocking read, like [`Subscriber::read`], but
+    /// reporting why no sample was received
+    /// 
+    /// Unlike [`Subscriber::read`], lagging is always reported with
+    /// [`RecvError::Lagged`] regardless of the [`LagPolicy`]. The next
+    /// call continues from the oldest sample still in the buffer
+    /// 
+    /// # Errors
+    /// 
+    /// * [`RecvError::Closed`] - if the topic is closed
+    /// * [`RecvError::Lagged`] - if the subscriber fell behind, with the number of samples skipped
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bedroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bedroom => String,
+    /// }
+    /// 
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut node = crosstalk::BoundedNode::<House>::new(1);
+    ///     let (publisher, mut subscriber) = node.pubsub(House::Bedroom).await.unwrap();
+    ///     publisher.write("hello".to_string());
+    ///     publisher.write("world".to_string());
+    ///     assert_eq!(subscriber.recv().await, Err(crosstalk::RecvError::Lagged(1)));
+    ///     assert_eq!(subscriber.recv().await, Ok("world".to_string()));
+    /// }
+    /// ```
+    pub async fn recv(&mut self) -> Result<D, RecvError> {
+        let res = self.rcvr.recv(&mut self.lag).await;
+        let res = self.lag.count(res);
+        self.observe(res).map(Envelope::into_inner)
+    }
+
+    #[inline(always)]
+    /// Non-blocking read, like [`Subscriber::try_read_raw`], but
+    /// reporting why no sample was received
+    /// 
+    /// # Errors
+    /// 
+    /// * [`RecvError::Empty`] - if there are no samples available
+    /// * [`RecvError::Closed`] - if the topic is closed
+    /// * [`RecvError::Lagged`] - if the subscriber fell behind, with the number of samples skipped
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bedroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bedroom => String,
+    /// }
+    /// 
+    /// let mut node = crosstalk::BoundedNode::<House>::new(10);
+    /// let (publisher, mut subscriber) = node.pubsub_blocking(House::Bedroom).unwrap();
+    /// assert_eq!(subscriber.try_recv(), Err(crosstalk::RecvError::Empty));
+    /// publisher.write("hello".to_string());
+    /// assert_eq!(subscriber.try_recv(), Ok("hello".to_string()));
+    /// ```
+    pub fn try_recv(&mut self) -> Result<D, RecvError> {
+        let res = self.rcvr.try_recv(&mut self.lag);
+        let res = self.lag.count(res);
+        self.observe(res).map(Envelope::into_inner)
+    }
+
+    #[inline(always)]
+    /// Sequential blocking read, like [`Subscriber::read_blocking`], but
+    /// reporting why no sample was received
+    /// 
+    /// # Errors
+    /// 
+    /// * [`RecvError::Closed`] - if the topic is closed
+    /// * [`RecvError::Lagged`] - if the subscriber fell behind, with the number of samples skipped
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bedroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bedroom => String,
+    /// }
+    /// 
+    /// let mut node = crosstalk::BoundedNode::<House>::new(10);
+    /// let (publisher, mut subscriber) = node.pubsub_blocking(House::Bedroom).unwrap();
+    /// publisher.write("hello".to_string());
+    /// assert_eq!(subscriber.recv_blocking(), Ok("hello".to_string()));
+    /// ```
+    pub fn recv_blocking(&mut self) -> Result<D, RecvError> {
+        let res = self.rcvr.recv_blocking(&mut self.lag);
+        let res = self.lag.count(res);
+        self.observe(res).map(Envelope::into_inner)
+    }
+
+    #[inline(always)]
+    /// Asynchronous read with a given timeout, like
+    /// [`Subscriber::read_timeout`], but reporting why no sample was received
+    /// 
+    /// # Errors
+    /// 
+    /// * [`RecvError::Timeout`] - if no sample was received within the timeout
+    /// * [`RecvError::Closed`] - if the topic is closed
+    /// * [`RecvError::Lagged`] - if the subscriber fell behind, with the number of samples skipped
+    /// * [`RecvError::NoRuntime`] - if called outside of a [`tokio`] runtime
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bedroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bedroom => String,
+    /// }
+    /// 
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut node = crosstalk::BoundedNode::<House>::new(10);
+    ///     let (publisher, mut subscriber) = node.pubsub(House::Bedroom).await.unwrap();
+    ///     let timeout = std::time::Duration::from_millis(100);
+    ///     assert_eq!(subscriber.recv_timeout(timeout).await, Err(crosstalk::RecvError::Timeout));
+    ///     publisher.write("hello".to_string());
+    ///     assert_eq!(subscriber.recv_timeout(timeout).await, Ok("hello".to_string()));
+    /// }
+    /// ```
+    pub async fn recv_timeout(&mut self, timeout: std::time::Duration) -> Result<D, RecvError> {
+        let res = self.rcvr.recv_timeout(timeout, &mut self.lag).await;
+        let res = self.lag.count(res);
+        self.observe(res).map(Envelope::into_inner)
+    }
+
+    #[inline(always)]
+    /// Non-blocking read of the latest sample of the topic
+    /// 
+    /// For [`TopicKind::Watch`] topics, this returns the current value
+    /// of the topic (even if it was already read), or [`None`] if nothing
+    /// was published yet
+    /// 
+    /// For all other topics, this reads all available samples and
+    /// returns the newest one, or [`None`] if there are no samples available
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bedroom,
+    ///     LivingRoom,
+    ///     Kitchen,
+    ///     Bathroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bedroom => String,
+    ///     #[watch]
+    ///     House::LivingRoom => String,
+    ///     House::Kitchen => Vec<f32>,
+    ///     House::Bathroom => u8,
+    /// }
+    /// 
+    /// let mut node = crosstalk::BoundedNode::<House>::new(10);
+    /// let publisher = node.publisher_blocking(House::LivingRoom).unwrap();
+    /// publisher.write("lights on".to_string());
+    /// publisher.write("lights off".to_string());
+    /// 
+    /// // late subscribers still observe the current value
+    /// let mut subscriber = node.subscriber_blocking::<String>(House::LivingRoom).unwrap();
+    /// assert_eq!(subscriber.latest(), Some("lights off".to_string()));
+    /// assert_eq!(subscriber.latest(), Some("lights off".to_string()));
+    /// ```
+    pub fn latest(&mut self) -> Option<D> {
+        self.rcvr.latest(&mut self.lag).map(Envelope::into_inner)
+    }
+
+    #[inline(always)]
+    /// Asynchronously waits for the value of a [`TopicKind::Watch`] topic
+    /// to change, after which it can be read with [`Subscriber::latest`]
+    /// 
+    /// Returns [`RecvError::Closed`] if the topic is closed, and
+    /// [`RecvError::NotWatch`] immediately for all other kinds of topics,
+    /// since only watch topics track changes
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bedroom,
+    ///     LivingRoom,
+    ///     Kitchen,
+    ///     Bathroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bedroom => String,
+    ///     #[watch]
+    ///     House::LivingRoom => String,
+    ///     House::Kitchen => Vec<f32>,
+    ///     House::Bathroom => u8,
+    /// }
+    /// 
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut node = crosstalk::BoundedNode::<House>::new(10);
+    ///     let (publisher, mut subscriber) = node.pubsub(House::LivingRoom).await.unwrap();
+    ///     tokio::spawn(async move { publisher.write("lights on".to_string()); });
+    ///     assert!(subscriber.changed().await.is_ok());
+    ///     assert_eq!(subscriber.latest(), Some("lights on".to_string()));
+    /// 
+    ///     let (_publisher, mut subscriber) = node.pubsub::<u8>(House::Bathroom).await.unwrap();
+    ///     assert_eq!(subscriber.changed().await, Err(crosstalk::RecvError::NotWatch));
+    /// }
+    /// ```
+    pub async fn changed(&mut self) -> Result<(), RecvError> {
+        self.rcvr.changed().await
+    }
+
+    #[inline(always)]
+    /// Records the result of a read, tracking whether
+    /// the subscriber has observed the close of the topic
+    fn observe(&mut self, res: Result<Envelope<D>, RecvError>) -> Result<Envelope<D>, RecvError> {
+        #[cfg(feature = "metrics")]
+        self.lag.metrics.received(&res);
+        self.observer.observe(res)
+    }
+}
+/// [`Subscriber`] implementation of [`Clone`]
+impl<D: Clone, T: Clone> Clone for Subscriber<D, T> {
+    #[inline(always)]
+    /// Clones a [`Subscriber`]
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bedroom,
+    ///     LivingRoom,
+    ///     Kitchen,
+    ///     Bathroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bedroom => String,
+    ///     House::LivingRoom => String,
+    ///     House::Kitchen => Vec<f32>,
+    ///     House::Bathroom => u8,
+    /// }
+    /// 
+    /// let mut node = crosstalk::BoundedNode::<House>::new(10);
+    /// let (publisher, mut subscriber) = node.pubsub_blocking(House::Bedroom).unwrap();
+    /// let mut subscriber_2 = subscriber.clone();
+    /// publisher.write("hello".to_string());
+    /// assert_eq!(subscriber.try_read().unwrap(), "hello");
+    /// assert_eq!(subscriber_2.try_read().unwrap(), "hello");
+    /// ```
+    fn clone(&self) -> Self {
+        Self {
+            topic: self.topic.clone(),
+            rcvr: self.sndr.subscribe().unwrap_or_else(Receiver::closed),
+            sndr: self.sndr.clone(),
+            lag: Lag::new(self.lag.policy, &self.sndr),
+            observer: self.sndr.observers.observer(),
+        }
+    }
+}
+
+/// Receiver
+/// 
+/// Define a receiver for subscribing messages
+/// 
+/// Reads from [`TokioReceiver`], [`TokioUnboundedReceiver`], [`TokioWatchReceiver`] or `AsyncReceiver`,
+/// depending on the node and the kind of the topic
+/// 
+/// A `Replay` receiver first reads the history of its topic, after which it is
+/// replaced by the receiver it wraps, see [`TopicConfig::history`]. A `Plain`
+/// receiver reads bare samples, see [`Subscriber::new`]
+enum Receiver<D> {
+    Bounded(TokioReceiver<Envelope<D>>),
+    Unbounded(TokioUnboundedReceiver<Envelope<D>>),
+    Watch(TokioWatchReceiver<Option<Envelope<D>>>),
+    #[cfg(feature = "queue")]
+    Queue(AsyncReceiver<Envelope<D>>),
+    Replay(VecDeque<Envelope<D>>, Box<Receiver<D>>),
+    Plain(TokioReceiver<D>),
+}
+/// [`Receiver`] implementation
+impl<D: Clone> Receiver<D> {
+    /// Creates a [`Receiver`] of a topic which is already closed
+    fn closed() -> Self {
+        let (_, buf) = tokio::sync::mpsc::unbounded_channel();
+        Receiver::Unbounded(buf)
+    }
+
+    /// Creates a [`Receiver`] which first reads the given `samples`
+    fn replay(samples: VecDeque<Envelope<D>>, rcvr: Self) -> Self {
+        match samples.is_empty() {
+            true => rcvr,
+            false => Receiver::Replay(samples, Box::new(rcvr)),
+        }
+    }
+
+    /// Takes the next sample to replay, replacing a drained
+    /// `Replay` receiver by the receiver it wraps
+    fn replayed(&mut self) -> Option<Envelope<D>> {
+        let Receiver::Replay(samples, _) = self else {
+            return None;
+        };
+        if let Some(sample) = samples.pop_front() {
+            return Some(sample);
+        }
+        if let Receiver::Replay(_, rcvr) = std::mem::replace(self, Receiver::closed()) {
+            *self = *rcvr;
+        }
+        None
+    }
+
+    /// Receives from [`TokioReceiver`], [`TokioUnboundedReceiver`], [`TokioWatchReceiver`] or `AsyncReceiver`
+    /// 
+    /// This struct/function is not meant to be used directly,
+    /// rather through the [`Subscriber`] struct with [`Subscriber::recv`]
+    async fn recv(&mut self, lag: &mut Lag) -> Result<Envelope<D>, RecvError> {
+        loop {
+            // --------------------------------------------------
+            // wait no longer than the deadline, if any (and if
+            // there is a runtime to time out with)
+            // --------------------------------------------------
+            let res = match lag.deadline.as_ref().map(Deadline::remaining) {
+                Some(remaining) if tokio::runtime::Handle::try_current().is_ok() => {
+                    match tokio::time::timeout(remaining, self.recv_next()).await {
+                        Ok(res) => res,
+                        Err(_) => return Err(lag.missed()),
+                    }
+                },
+                _ => self.recv_next().await,
+            };
+            match res {
+                Ok(sample) if lag.expired(&sample) => continue,
+                res => return lag.heard(res),
+            }
+        }
+    }
+
+    /// Receives the next sample, including expired ones
+    async fn recv_next(&mut self) -> Result<Envelope<D>, RecvError> {
+        if let Some(sample) = self.replayed() {
+            return Ok(sample);
+        }
+        match self {
+            Receiver::Bounded(buf) => buf.recv().await.map_err(RecvError::from),
+            Receiver::Unbounded(buf) => buf.recv().await.ok_or(RecvError::Closed),
+            Receiver::Watch(buf) => {
+                buf.changed().await.map_err(|_| RecvError::Closed)?;
+                buf.borrow_and_update().clone().ok_or(RecvError::Empty)
+            },
+            #[cfg(feature = "queue")]
+            Receiver::Queue(buf) => buf.recv().await.map_err(|_| RecvError::Closed),
+            Receiver::Plain(buf) => buf.recv().await.map(Envelope::plain).map_err(RecvError::from),
+            Receiver::Replay(..) => unreachable!("drained replay receivers are unwrapped"),
+        }
+    }
+
+    /// Receives from [`TokioReceiver`], [`TokioUnboundedReceiver`], [`TokioWatchReceiver`] or `AsyncReceiver`
+    /// 
+    /// This struct/function is not meant to be used directly,
+    /// rather through the [`Subscriber`] struct with [`Subscriber::try_recv`]
+    fn try_recv(&mut self, lag: &mut Lag) -> Result<Envelope<D>, RecvError> {
+        loop {
+            match self.try_recv_next() {
+                Ok(sample) if lag.expired(&sample) => continue,
+                Err(RecvError::Empty) => match lag.deadline.as_ref().is_some_and(Deadline::overdue) {
+                    true => return Err(lag.missed()),
+                    false => return Err(RecvError::Empty),
+                },
+                res => return lag.heard(res),
+            }
+        }
+    }
+
+    /// Receives the next sample without waiting, including expired ones
+    fn try_recv_next(&mut self) -> Result<Envelope<D>, RecvError> {
+        if let Some(sample) = self.replayed() {
+            return Ok(sample);
+        }
+        match self {
+            Receiver::Bounded(buf) => buf.try_recv().map_err(RecvError::from),
+            Receiver::Unbounded(buf) => buf.try_recv().map_err(|e| match e {
+                tokio::sync::mpsc::error::TryRecvError::Empty => RecvError::Empty,
+                tokio::sync::mpsc::error::TryRecvError::Disconnected => RecvError::Closed,
+            }),
+            Receiver::Watch(buf) => match buf.has_changed() {
+                Ok(true) => buf.borrow_and_update().clone().ok_or(RecvError::Empty),
+                Ok(false) => Err(RecvError::Empty),
+                Err(_) => Err(RecvError::Closed),
+            },
+            #[cfg(feature = "queue")]
+            Receiver::Queue(buf) => buf.try_recv().map_err(|e| match e {
+                async_channel::TryRecvError::Empty => RecvError::Empty,
+                async_channel::TryRecvError::Closed => RecvError::Closed,
+            }),
+            Receiver::Plain(buf) => buf.try_recv().map(Envelope::plain).map_err(RecvError::from),
+            Receiver::Replay(..) => unreachable!("drained replay receivers are unwrapped"),
+        }
+    }
+
+    /// Receives from [`TokioReceiver`], [`TokioUnboundedReceiver`], [`TokioWatchReceiver`] or `AsyncReceiver`
+    /// 
+    /// This struct/function is not meant to be used directly,
+    /// rather through the [`Subscriber`] struct with [`Subscriber::recv_blocking`]
+    fn recv_blocking(&mut self, lag: &mut Lag) -> Result<Envelope<D>, RecvError> {
+        loop {
+            match self.recv_blocking_next() {
+                Ok(sample) if lag.expired(&sample) => continue,
+                res => return lag.heard(res),
+            }
+        }
+    }
+
+    /// Receives the next sample while blocking, including expired ones
+    fn recv_blocking_next(&mut self) -> Result<Envelope<D>, RecvError> {
+        if let Some(sample) = self.replayed() {
+            return Ok(sample);
+        }
+        match self {
+            Receiver::Bounded(buf) => buf.blocking_recv().map_err(RecvError::from),
+            Receiver::Unbounded(buf) => buf.blocking_recv().ok_or(RecvError::Closed),
+            Receiver::Watch(buf) => {
+                block_on(buf.changed()).map_err(|_| RecvError::Closed)?;
+                buf.borrow_and_update().clone().ok_or(RecvError::Empty)
+            },
+            #[cfg(feature = "queue")]
+            Receiver::Queue(buf) => buf.recv_blocking().map_err(|_| RecvError::Closed),
+            Receiver::Plain(buf) => buf.blocking_recv().map(Envelope::plain).map_err(RecvError::from),
+            Receiver::Replay(..) => unreachable!("drained replay receivers are unwrapped"),
+        }
+    }
+
+    /// Receives from [`TokioReceiver`], [`TokioUnboundedReceiver`], [`TokioWatchReceiver`] or `AsyncReceiver`
+    /// 
+    /// This struct/function is not meant to be used directly,
+    /// rather through the [`Subscriber`] struct with [`Subscriber::recv_timeout`]
+    async fn recv_timeout(&mut self, timeout: std::time::Duration, lag: &mut Lag) -> Result<Envelope<D>, RecvError> {
+        if tokio::runtime::Handle::try_current().is_err() {
+            return Err(RecvError::NoRuntime);
+        }
+        match tokio::time::timeout(timeout, self.recv(lag)).await {
+            Ok(res) => res,
+            Err(_) => Err(RecvError::Timeout),
+        }
+    }
+
+    /// Reads from [`TokioReceiver`], [`TokioUnboundedReceiver`], [`TokioWatchReceiver`] or `AsyncReceiver`
+    /// 
+    /// This struct/function is not meant to be used directly,
+    /// rather through the [`Subscriber`] struct with [`Subscriber::read`]
+    async fn read(&mut self, lag: &mut Lag) -> Result<Envelope<D>, RecvError> {
+        loop {
+            match self.recv(lag).await {
+                Err(RecvError::Lagged(n)) => match self.lagged(n, lag) {
+                    Ok(Some(d)) => return Ok(d),
+                    Ok(None) => continue,
+                    Err(e) => return Err(e),
+                },
+                // missed deadlines are counted, but do not end the read
+                Err(RecvError::DeadlineMissed) => continue,
+                res => return res,
+            }
+        }
+    }    
+
+    /// Reads from [`TokioReceiver`], [`TokioUnboundedReceiver`], [`TokioWatchReceiver`] or `AsyncReceiver`
+    /// 
+    /// This struct/function is not meant to be used directly,
+    /// rather through the [`Subscriber`] struct with [`Subscriber::try_read`]
+    fn try_read(&mut self, lag: &mut Lag) -> Result<Envelope<D>, RecvError> {
+        loop {
+            match self.try_recv(lag) {
+                Err(RecvError::Lagged(n)) => match self.lagged(n, lag) {
+                    Ok(Some(d)) => return Ok(d),
+                    Ok(None) => continue,
+                    Err(e) => return Err(e),
+                },
+                Err(RecvError::DeadlineMissed) => return Err(RecvError::Empty),
+                res => return res,
+            }
+        }
+    }
+
+    /// Reads from [`TokioReceiver`], [`TokioUnboundedReceiver`], [`TokioWatchReceiver`] or `AsyncReceiver`
+    /// 
+    /// This struct/function is not meant to be used directly,
+    /// rather through the [`Subscriber`] struct with [`Subscriber::try_read_raw`]
+    fn try_read_raw(&mut self, lag: &mut Lag) -> Result<Envelope<D>, RecvError> {
+        let res = self.try_recv(lag);
+        lag.count(res)
+    }
+    
+    /// Reads from [`TokioReceiver`], [`TokioUnboundedReceiver`], [`TokioWatchReceiver`] or `AsyncReceiver`
+    /// 
+    /// This struct/function is not meant to be used directly,
+    /// rather through the [`Subscriber`] struct with [`Subscriber::read_blocking`]
+    fn read_blocking(&mut self, lag: &mut Lag) -> Result<Envelope<D>, RecvError> {
+        loop {
+            match self.recv_blocking(lag) {
+                Err(RecvError::Lagged(n)) => match self.lagged(n, lag) {
+                    Ok(Some(d)) => return Ok(d),
+                    Ok(None) => continue,
+                    Err(e) => return Err(e),
+                },
+                res => return res,
+            }
+        }
+    }
+    
+    /// Reads from [`TokioReceiver`], [`TokioUnboundedReceiver`], [`TokioWatchReceiver`] or `AsyncReceiver`
+    /// 
+    /// This struct/function is not meant to be used directly,
+    /// rather through the [`Subscriber`] struct with [`Subscriber::read_timeout`]
+    async fn read_timeout(&mut self, timeout: std::time::Duration, lag: &mut Lag) -> Result<Envelope<D>, RecvError> {
+        if tokio::runtime::Handle::try_current().is_err() {
+            return Err(RecvError::NoRuntime);
+        }
+        match tokio::time::timeout(timeout, self.read(lag)).await {
+            Ok(res) => res,
+            Err(_) => Err(RecvError::Timeout),
+        }
+    }
+
+    /// Handles `n` lost samples according to the [`LagPolicy`]
+    /// 
+    /// Returns the sample to read instead of the lost ones, or [`None`]
+    /// to continue reading from the oldest sample still in the buffer
+    fn lagged(&mut self, n: u64, lag: &mut Lag) -> Result<Option<Envelope<D>>, RecvError> {
+        lag.add(n);
+        match lag.policy {
+            LagPolicy::Skip | LagPolicy::Count => Ok(None),
+            LagPolicy::Error => Err(RecvError::Lagged(n)),
+            LagPolicy::Latest => {
+                // drain the buffer, counting every sample skipped on the way
+                let mut newest = None;
+                loop {
+                    match self.try_recv(lag) {
+                        Ok(d) => if newest.replace(d).is_some() { lag.add(1) },
+                        Err(RecvError::Lagged(n)) => lag.add(n),
+                        Err(_) => return Ok(newest),
+                    }
+                }
+            },
+        }
+    }
+
+    /// Reads the latest sample, without waiting
+    /// 
+    /// This struct/function is not meant to be used directly,
+    /// rather through the [`Subscriber`] struct with [`Subscriber::latest`]
+    fn latest(&mut self, lag: &mut Lag) -> Option<Envelope<D>> {
+        match self {
+            Receiver::Watch(buf) => {
+                // --------------------------------------------------
+                // the current value is seen on every call, but only
+                // counted as expired the first time
+                // --------------------------------------------------
+                let unseen = buf.has_changed().unwrap_or(false);
+                let sample = buf.borrow_and_update().clone()?;
+                match unseen {
+                    true => (!lag.expired(&sample)).then_some(sample),
+                    false => (!lag.outlived(&sample)).then_some(sample),
+                }
+            },
+            _ => {
+                let mut newest = None;
+                loop {
+                    match self.try_recv(lag) {
+                        Ok(d) => newest = Some(d),
+                        Err(RecvError::Lagged(n)) => lag.add(n),
+                        Err(_) => return newest,
+                    }
+                }
+            },
+        }
+    }
+
+    /// Waits for the value of a watch topic to change
+    /// 
+    /// This struct/function is not meant to be used directly,
+    /// rather through the [`Subscriber`] struct with [`Subscriber::changed`]
+    async fn changed(&mut self) -> Result<(), RecvError> {
+        match self {
+            Receiver::Watch(buf) => buf.changed().await.map_err(|_| RecvError::Closed),
+            _ => Err(RecvError::NotWatch),
+        }
+    }
+}
+
+/// The lag, expiry and deadline accounting of a [`Subscriber`]
+/// 
+/// # Attributes
+/// 
+/// * `policy` - how lost samples are handled
+/// * `total` - the number of samples lost, unless the policy is [`LagPolicy::Skip`]
+/// * `lifespan` - how long samples can be read for, see [`TopicConfig::lifespan`]
+/// * `expired` - the number of samples skipped because they outlived their lifespan
+/// * `deadline` - the deadline of the subscriber, see [`TopicConfig::deadline`]
+/// * `metrics` - the metrics of the subscriber, with the `metrics` feature
+struct Lag {
+    policy: LagPolicy,
+    total: u64,
+    lifespan: Option<std::time::Duration>,
+    expired: u64,
+    deadline: Option<Deadline>,
+    #[cfg(feature = "metrics")]
+    metrics: SubscriberMetrics,
+}
+/// [`Lag`] implementation
+impl Lag {
+    #[inline(always)]
+    /// Constructs a new [`Lag`] with nothing lost, for a subscriber of `sndr`
+    fn new<D>(policy: LagPolicy, sndr: &Sender<D>) -> Self {
+        Self {
+            policy,
+            total: 0,
+            lifespan: sndr.lifespan,
+            expired: 0,
+            deadline: sndr.deadline.map(Deadline::new),
+            #[cfg(feature = "metrics")]
+            metrics: sndr.metrics.subscriber(),
+        }
+    }
+
+    #[inline(always)]
+    /// Adds `n` lost samples to the total
+    fn add(&mut self, n: u64) {
+        #[cfg(feature = "metrics")]
+        self.metrics.lagged(n);
+        if self.policy != LagPolicy::Skip {
+            self.total += n;
+        }
+    }
+
+    #[inline(always)]
+    /// Whether a sample has outlived its lifespan
+    fn outlived<D>(&self, sample: &Envelope<D>) -> bool {
+        self.lifespan.zip(sample.stamp.as_ref()).is_some_and(|(lifespan, stamp)| stamp.age() > lifespan)
+    }
+
+    #[inline(always)]
+    /// Whether a sample has outlived its lifespan, counting it if so
+    fn expired<D>(&mut self, sample: &Envelope<D>) -> bool {
+        let expired = self.outlived(sample);
+        if expired {
+            self.expired += 1;
+            #[cfg(feature = "metrics")]
+            self.metrics.expired();
+        }
+        expired
+    }
+
+    #[inline(always)]
+    /// Records a deadline missed without receiving any sample
+    fn missed(&mut self) -> RecvError {
+        if let Some(deadline) = &mut self.deadline {
+            deadline.miss();
+        }
+        #[cfg(feature = "metrics")]
+        self.metrics.deadline_missed();
+        RecvError::DeadlineMissed
+    }
+
+    #[inline(always)]
+    /// Restarts the deadline (if any) from when the received sample was
+    /// published, or from now if samples were lost
+    fn heard<D>(&mut self, res: Result<Envelope<D>, RecvError>) -> Result<Envelope<D>, RecvError> {
+        if let Some(deadline) = &mut self.deadline {
+            match &res {
+                Ok(sample) => deadline.heard(sample.stamp.as_ref().map_or_else(tokio::time::Instant::now, |stamp| stamp.published.into())),
+                Err(RecvError::Lagged(_)) => deadline.heard(tokio::time::Instant::now()),
+                Err(_) => (),
+            }
+        }
+        res
+    }
+
+    #[inline(always)]
+    /// Adds the lost samples of a [`RecvError::Lagged`] to the total
+    fn count<D>(&mut self, res: Result<D, RecvError>) -> Result<D, RecvError> {
+        if let Err(RecvError::Lagged(n)) = res {
+            self.add(n);
+        }
+        res
+    }
+}
+
+#[inline(always)]
+/// Converts the result of a [`Receiver`] into an [`Option`], logging
+/// the [`RecvError`] under the `log` or `tracing` features
+fn ok_or_log<D>(res: Result<D, RecvError>) -> Option<D> {
+    match res {
+        Ok(d) => Some(d),
+
+        #[cfg(not(any(feature = "log", feature = "tracing")))]
+        Err(_) => None,
+
+        #[cfg(any(feature = "log", feature = "tracing"))]
+        Err(e) => {
+            #[cfg(feature = "log")]
+            log::error!("{}", e);
+            #[cfg(feature = "tracing")]
+            tracing::error!("{}", e);
+            None
+        },
+    }
+}
+
+/// Blocks the current thread on a future, without a [`tokio`] runtime
+/// 
+/// This is used for channels which do not provide a blocking receive
+/// (e.g. [`TokioWatchReceiver`]), and therefore must only be used with
+/// futures that do not rely on a runtime (timers, io, etc.)
+/// 
+/// Like [`TokioReceiver::blocking_recv`], this must not be called
+/// within an asynchronous context
+fn block_on<F: std::future::Future>(fut: F) -> F::Output {
+    struct ThreadWaker(std::thread::Thread);
+    impl std::task::Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+    let waker = std::task::Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = std::task::Context::from_waker(&waker);
+    let mut fut = std::pin::pin!(fut);
+    loop {
+        match fut.as_mut().poll(&mut cx) {
+            std::task::Poll::Ready(res) => return res,
+            std::task::Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+// --------------------------------------------------
+// for testing
+// --------------------------------------------------
+#[allow(unused_imports)]
+use crosstalk_macros::init_test;
+#[allow(unused_imports)]
+use crosstalk_macros::AsTopicTest;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[derive(AsTopicTest)]
+    enum TestTopic {
+        A,
+        B,
+        C,
+    }
+    super::init_test! {
+        TestTopic::A => String,
+        TestTopic::B => bool,
+        TestTopic::C => i32,
+    }
+
+    #[derive(AsTopicTest)]
+    enum AnotherTestTopic {
+        Foo,
+        Bar,
+    }
+    super::init_test! {
+        AnotherTestTopic::Foo => Vec<String>,
+        AnotherTestTopic::Bar => Vec<bool>,
+    }
+
+    #[derive(AsTopicTest)]
+    enum WatchTestTopic {
+        Mode,
+        Pose,
+    }
+    super::init_test! {
+        #[watch]
+        WatchTestTopic::Mode => String,
+        WatchTestTopic::Pose => (f32, f32),
+    }
+
+    #[cfg(feature = "queue")]
+    #[derive(AsTopicTest)]
+    enum QueueTestTopic {
+        Jobs,
+        Small,
+        Expiring,
+    }
+    #[cfg(feature = "queue")]
+    super::init_test! {
+        #[queue]
+        QueueTestTopic::Jobs => u32,
+        #[queue]
+        QueueTestTopic::Small => u32 @ 2,
+        #[queue]
+        #[lifespan(Duration::from_millis(20))]
+        QueueTestTopic::Expiring => u32,
+    }
+
+    #[derive(AsTopicTest)]
+    enum CapacityTestTopic {
+        Deep,
+        Shallow,
+        Default,
+    }
+    super::init_test! {
+        CapacityTestTopic::Deep => u32 @ 64,
+        CapacityTestTopic::Shallow => u32 @ 1,
+        CapacityTestTopic::Default => u32,
+    }
+
+    #[derive(AsTopicTest)]
+    enum HistoryTestTopic {
+        Config,
+        Live,
+    }
+    super::init_test! {
+        #[history(2)]
+        #[stamp]
+        HistoryTestTopic::Config => u32 @ 8,
+        HistoryTestTopic::Live => u32,
+    }
+
+    #[derive(AsTopicTest)]
+    enum StampTestTopic {
+        Frame,
+        Plain,
+    }
+    super::init_test! {
+        #[stamp]
+        StampTestTopic::Frame => i32,
+        StampTestTopic::Plain => i32,
+    }
+
+    #[derive(AsTopicTest)]
+    enum LifespanTestTopic {
+        Pose,
+        Mode,
+    }
+    super::init_test! {
+        #[lifespan(Duration::from_millis(20))]
+        LifespanTestTopic::Pose => u32,
+        #[watch]
+        #[lifespan(Duration::from_millis(20))]
+        LifespanTestTopic::Mode => u32,
+    }
+
+    #[derive(AsTopicTest)]
+    enum DeadlineTestTopic {
+        Heartbeat,
+    }
+    super::init_test! {
+        #[deadline(Duration::from_millis(20))]
+        DeadlineTestTopic::Heartbeat => u32,
+    }
+
+    #[cfg(feature = "serde")]
+    #[derive(AsTopicTest)]
+    enum SerdeTestTopic {
+        Json,
+        Bincode,
+        Cbor,
+        Raw,
+    }
+    #[cfg(feature = "serde")]
+    super::init_test! {
+        #[serde(json)]
+        SerdeTestTopic::Json => Vec<String>,
+        #[serde(bincode)]
+        SerdeTestTopic::Bincode => (u32, String),
+        #[serde(cbor)]
+        SerdeTestTopic::Cbor => Option<f64>,
+        SerdeTestTopic::Raw => u32,
+    }
+
+    #[cfg(feature = "shm")]
+    #[derive(Clone, Copy, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
+    #[repr(C)]
+    struct Frame {
+        id: u32,
+        pixels: [u8; 252],
+    }
+    #[cfg(feature = "shm")]
+    #[derive(AsTopicTest)]
+    enum ShmTestTopic {
+        Frame,
+        Other,
+        Image,
+    }
+    #[cfg(feature = "shm")]
+    const IMAGE: usize = 4 << 20;
+    #[cfg(feature = "shm")]
+    super::init_test! {
+        ShmTestTopic::Frame => Arc<Frame>,
+        ShmTestTopic::Other => Arc<[u8; 256]>,
+        ShmTestTopic::Image => Arc<[u8; IMAGE]>,
+    }
+
+    #[cfg(feature = "queue")]
+    #[derive(AsTopicTest)]
+    enum ServiceTestTopic {
+        Add,
+        Echo,
+        Plain,
+    }
+    #[cfg(feature = "queue")]
+    super::init_test! {
+        ServiceTestTopic::Add => (i32, i32) -> i32,
+        ServiceTestTopic::Echo => String -> String @ 4,
+        ServiceTestTopic::Plain => String,
+    }
+
+    #[test]
+    fn test_single_pubsub_blocking() {
+        let mut node = BoundedNode::<TestTopic>::new(10);
+        let (publisher, mut subscriber) = node.pubsub_blocking(TestTopic::A).unwrap();
+        publisher.write("test".to_string());
+        assert_eq!(subscriber.try_read().unwrap(), "test");
+    }
+
+    #[test]
+    fn test_multiple_subscribers_blocking() {
+        let mut node = BoundedNode::<TestTopic>::new(10);
+        let (publisher, mut sub1) = node.pubsub_blocking(TestTopic::A).unwrap();
+        let mut sub2 = node.subscriber_blocking::<String>(TestTopic::A).unwrap();
+
+        publisher.write("hello".to_string());
+        assert_eq!(sub1.try_read().unwrap(), "hello");
+        assert_eq!(sub2.try_read().unwrap(), "hello");
+    }
+
+    #[test]
+    fn test_cross_topic_isolation() {
+        let mut node = BoundedNode::<TestTopic>::new(10);
+        let (pub_a, mut sub_a) = node.pubsub_blocking(TestTopic::A).unwrap();
+        let (pub_b, mut sub_b) = node.pubsub_blocking(TestTopic::B).unwrap();
+
+        pub_a.write("string".to_string());
+        pub_b.write(true);
+
+        assert_eq!(sub_a.try_read().unwrap(), "string");
+        assert!(sub_b.try_read().unwrap());
+        assert!(sub_a.try_read().is_none());
+        assert!(sub_b.try_read().is_none());
+    }
+
+    #[test]
+    fn test_multiple_threads_blocking() {
+        let mut node = BoundedNode::<TestTopic>::new(10);
+        let (publisher, mut subscriber) = node.pubsub_blocking(TestTopic::A).unwrap();
+
+        let handle = std::thread::spawn(move || {
+            publisher.write("threaded".to_string());
+        });
+
+        handle.join().unwrap();
+        assert_eq!(subscriber.try_read().unwrap(), "threaded");
+    }
+
+    #[tokio::test]
+    async fn test_async_pubsub_single_runtime() {
+        let mut node = BoundedNode::<TestTopic>::new(10);
+        let (publisher, mut subscriber) = node.pubsub(TestTopic::A).await.unwrap();
+        publisher.write("async".to_string());
+        assert_eq!(subscriber.read().await.unwrap(), "async");
+    }
+
+    #[test]
+    fn test_high_volume_blocking() {
+        let mut node = BoundedNode::<TestTopic>::new(100);
+        let (publisher, mut subscriber) = node.pubsub_blocking(TestTopic::C).unwrap();
+
+        for i in 0..100 {
+            publisher.write(i);
+        }
+
+        for i in 0..100 {
+            assert_eq!(subscriber.try_read().unwrap(), i);
+        }
+        assert!(subscriber.try_read().is_none());
+    }
+
+    #[test]
+    fn test_cloned_subscribers() {
+        let mut node = BoundedNode::<TestTopic>::new(10);
+        let (publisher, mut sub1) = node.pubsub_blocking(TestTopic::A).unwrap();
+        let mut sub2 = sub1.clone();
+
+        publisher.write("clone".to_string());
+        assert_eq!(sub1.try_read().unwrap(), "clone");
+        assert_eq!(sub2.try_read().unwrap(), "clone");
+    }
+
+    #[test]
+    fn test_buffer_overflow_handling() {
+        let mut node = BoundedNode::<TestTopic>::new(2);
+        let (publisher, mut subscriber) = node.pubsub_blocking(TestTopic::A).unwrap();
+
+        publisher.write("msg1".to_string());
+        publisher.write("msg2".to_string());
+        publisher.write("msg3".to_string());
+
+        assert_eq!(subscriber.try_read().unwrap(), "msg2");
+        assert_eq!(subscriber.try_read().unwrap(), "msg3");
+        assert!(subscriber.try_read().is_none());
+    }
+
+    #[test]
+    fn test_type_mismatch_errors() {
+        let mut node = BoundedNode::<TestTopic>::new(10);
+        
+        let publisher_res = node.publisher_blocking::<i32>(TestTopic::A);
+        assert!(matches!(publisher_res, Err(Error::PublisherMismatch(_, _))));
+        
+        let subscriber_res = node.subscriber_blocking::<i32>(TestTopic::A);
+        assert!(matches!(subscriber_res, Err(Error::SubscriberMismatch(_, _))));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_multiple_async_runtimes() {
+        let mut node = BoundedNode::<TestTopic>::new(10);
+        let (publisher, mut subscriber) = node.pubsub(TestTopic::A).await.unwrap();
+
+        let handle = tokio::spawn(async move {
+            publisher.write("async".to_string());
+        });
+
+        handle.await.unwrap();
+        assert_eq!(subscriber.read().await.unwrap(), "async");
+    }
+
+    #[test]
+    fn test_mixed_async_blocking() {
+        let mut node = BoundedNode::<TestTopic>::new(10);
+        let (publisher, mut subscriber) = node.pubsub_blocking(TestTopic::A).unwrap();
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        rt.block_on(async {
+            publisher.write("mixed".to_string());
+        });
+
+        assert_eq!(subscriber.try_read().unwrap(), "mixed");
+    }
+
+    #[test]
+    fn test_complex_data_types() {
+        let mut node = BoundedNode::<AnotherTestTopic>::new(10);
+        let (pub_foo, mut sub_foo) = node.pubsub_blocking(AnotherTestTopic::Foo).unwrap();
+        let (pub_bar, mut sub_bar) = node.pubsub_blocking(AnotherTestTopic::Bar).unwrap();
+
+        pub_foo.write(vec!["a".to_string(), "b".to_string()]);
+        pub_bar.write(vec![true, false]);
+
+        assert_eq!(sub_foo.try_read().unwrap(), vec!["a", "b"]);
+        assert_eq!(sub_bar.try_read().unwrap(), vec![true, false]);
+    }
+
+    #[test]
+    fn test_concurrent_publishers() {
+        let mut node = BoundedNode::<TestTopic>::new(100);
+        let (pub1, mut sub) = node.pubsub_blocking(TestTopic::C).unwrap();
+        let pub2 = node.publisher_blocking::<i32>(TestTopic::C).unwrap();
+
+        let handle1 = std::thread::spawn(move || {
+            for i in 0..50 {
+                pub1.write(i);
+            }
+        });
+
+        let handle2 = std::thread::spawn(move || {
+            for i in 50..100 {
+                pub2.write(i);
+            }
+        });
+
+        handle1.join().unwrap();
+        handle2.join().unwrap();
+
+        let mut received = Vec::new();
+        while let Some(msg) = sub.try_read() {
+            received.push(msg);
+        }
+        assert_eq!(received.len(), 100);
+    }
+
+    #[tokio::test]
+    async fn test_async_subscriber_cloning() {
+        let mut node = BoundedNode::<TestTopic>::new(10);
+        let (publisher, mut sub1) = node.pubsub(TestTopic::A).await.unwrap();
+        let mut sub2 = sub1.clone();
+
+        publisher.write("async_clone".to_string());
+        assert_eq!(sub1.read().await.unwrap(), "async_clone");
+        assert_eq!(sub2.read().await.unwrap(), "async_clone");
+    }
+
+    #[test]
+    fn test_dropped_publisher_behavior() {
+        let mut node = BoundedNode::<TestTopic>::new(10);
+        let (publisher, mut subscriber) = node.pubsub_blocking::<String>(TestTopic::A).unwrap();
+        drop(publisher);
+        assert!(subscriber.try_read().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_multiple_async_publishers() {
+        const LOOP_COUNT: usize = 10;
+        const BUFFER_SIZE: usize = 10;
+        let mut node = BoundedNode::<TestTopic>::new(BUFFER_SIZE);
+        let (publisher, mut subscriber) = node.pubsub(TestTopic::A).await.unwrap();
+        let publisher_1 = publisher.clone();
+        let publisher_2 = publisher.clone();
+
+        let task1 = tokio::spawn({
+            async move {
+                for _ in 0..LOOP_COUNT {
+                    publisher_1.write("task1".to_string());
+                }
+            }
+        });
+
+        let task2 = tokio::spawn({
+            async move {
+                for _ in 0..LOOP_COUNT {
+                    publisher_2.write("task2".to_string());
+                }
+            }
+        });
+
+        let _ = tokio::join!(task1, task2);
+
+        let mut task1_count = 0;
+        let mut task2_count = 0;
+        for _ in 0..BUFFER_SIZE {
+            let msg = subscriber.read().await.unwrap();
+            if msg == "task1" { task1_count += 1; }
+            if msg == "task2" { task2_count += 1; }
+        }
+        assert_eq!(task1_count + task2_count, BUFFER_SIZE);
+    }
+
+    #[test]
+    fn test_blocking_read_with_delay() {
+        let mut node = BoundedNode::<TestTopic>::new(10);
+        let (publisher, mut subscriber) = node.pubsub_blocking(TestTopic::A).unwrap();
+
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(500));
+            publisher.write("delayed".to_string());
+        });
+
+        assert_eq!(subscriber.read_blocking().unwrap(), "delayed");
+    }
+
+    #[tokio::test]
+    async fn test_read_timeout_behavior() {
+        let mut node = BoundedNode::<TestTopic>::new(10);
+        let (publisher, mut subscriber) = node.pubsub(TestTopic::A).await.unwrap();
+
+        let timeout = std::time::Duration::from_millis(100);
+        assert!(subscriber.read_timeout(timeout).await.is_none());
+
+        publisher.write("timeout_test".to_string());
+        assert_eq!(subscriber.read_timeout(timeout).await.unwrap(), "timeout_test");
+    }
+
+    #[test]
+    fn test_multiple_topics_concurrently() {
+        let mut node = BoundedNode::<AnotherTestTopic>::new(10);
+        let (pub_foo, mut sub_foo) = node.pubsub_blocking(AnotherTestTopic::Foo).unwrap();
+        let (pub_bar, mut sub_bar) = node.pubsub_blocking(AnotherTestTopic::Bar).unwrap();
+
+        let handle1 = std::thread::spawn(move || {
+            pub_foo.write(vec!["thread".to_string()]);
+        });
+
+        let handle2 = std::thread::spawn(move || {
+            pub_bar.write(vec![true]);
+        });
+
+        handle1.join().unwrap();
+        handle2.join().unwrap();
+
+        assert_eq!(sub_foo.try_read().unwrap(), vec!["thread"]);
+        assert_eq!(sub_bar.try_read().unwrap(), vec![true]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_zero_capacity_node() {
+        let _ = BoundedNode::<TestTopic>::new(0);
+    }
+
+    #[tokio::test]
+    async fn test_async_unbounded_messaging() {
+        let mut node = BoundedNode::<TestTopic>::new(1000);
+        let (publisher, mut subscriber) = node.pubsub(TestTopic::A).await.unwrap();
+
+        let messages = vec!["msg1", "msg2", "msg3", "msg4", "msg5"];
+        for msg in &messages {
+            publisher.write(msg.to_string());
+        }
+
+        for expected in messages {
+            assert_eq!(subscriber.read().await.unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn test_error_handling_lagged_messages() {
+        let mut node = BoundedNode::<TestTopic>::new(2);
+        let (publisher, mut subscriber) = node.pubsub_blocking(TestTopic::A).unwrap();
+
+        for i in 0..5 {
+            publisher.write(format!("msg{}", i));
+        }
+
+        let mut received = Vec::new();
+        while let Some(msg) = subscriber.try_read() {
+            received.push(msg);
+        }
+        assert_eq!(received, vec!["msg3", "msg4"]);
+    }
+
+    #[test]
+    fn test_per_topic_capacity() {
+        let mut node = BoundedNode::<CapacityTestTopic>::new(4);
+        let (pub_deep, mut sub_deep) = node.pubsub_blocking(CapacityTestTopic::Deep).unwrap();
+        let (pub_shallow, mut sub_shallow) = node.pubsub_blocking(CapacityTestTopic::Shallow).unwrap();
+        let (pub_default, mut sub_default) = node.pubsub_blocking(CapacityTestTopic::Default).unwrap();
+
+        for i in 0..64u32 {
+            pub_deep.write(i);
+            pub_shallow.write(i);
+            pub_default.write(i);
+        }
+
+        let deep = std::iter::from_fn(|| sub_deep.try_read()).collect::<Vec<_>>();
+        let shallow = std::iter::from_fn(|| sub_shallow.try_read()).collect::<Vec<_>>();
+        let default = std::iter::from_fn(|| sub_default.try_read()).collect::<Vec<_>>();
+        assert_eq!(deep, (0..64).collect::<Vec<_>>());
+        assert_eq!(shallow, vec![63]);
+        assert_eq!(default, vec![60, 61, 62, 63]);
+    }
+
+    #[test]
+    fn test_per_topic_capacity_unbounded() {
+        let mut node = UnboundedNode::<CapacityTestTopic>::new();
+        let (publisher, mut subscriber) = node.pubsub_blocking(CapacityTestTopic::Shallow).unwrap();
+
+        for i in 0..64u32 {
+            publisher.write(i);
+        }
+
+        let received = std::iter::from_fn(|| subscriber.try_read()).collect::<Vec<_>>();
+        assert_eq!(received, (0..64).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_watch_latest_value() {
+        let mut node = BoundedNode::<WatchTestTopic>::new(10);
+        let (publisher, mut early) = node.pubsub_blocking::<String>(WatchTestTopic::Mode).unwrap();
+        assert!(early.latest().is_none());
+        assert!(early.try_read().is_none());
+
+        publisher.write("idle".to_string());
+        publisher.write("armed".to_string());
+        let mut late = node.subscriber_blocking::<String>(WatchTestTopic::Mode).unwrap();
+
+        assert_eq!(early.try_read().unwrap(), "armed");
+        assert!(early.try_read().is_none());
+        assert_eq!(late.try_read().unwrap(), "armed");
+        assert!(late.try_read().is_none());
+        assert_eq!(late.latest().unwrap(), "armed");
+    }
+
+    #[tokio::test]
+    async fn test_watch_changed() {
+        let mut node = BoundedNode::<WatchTestTopic>::new(10);
+        let (publisher, mut subscriber) = node.pubsub::<String>(WatchTestTopic::Mode).await.unwrap();
+        let mut cloned = subscriber.clone();
+
+        let handle = tokio::spawn(async move {
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+            publisher.write("armed".to_string());
+        });
+
+        assert!(subscriber.changed().await.is_ok());
+        assert_eq!(subscriber.latest().unwrap(), "armed");
+        assert_eq!(cloned.read().await.unwrap(), "armed");
+        handle.await.unwrap();
+
+        let timeout = std::time::Duration::from_millis(100);
+        assert!(subscriber.read_timeout(timeout).await.is_none());
+    }
+
+    #[test]
+    fn test_watch_blocking_read() {
+        let mut node = UnboundedNode::<WatchTestTopic>::new();
+        let (publisher, mut subscriber) = node.pubsub_blocking::<String>(WatchTestTopic::Mode).unwrap();
+
+        std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            publisher.write("delayed".to_string());
+        });
+
+        assert_eq!(subscriber.read_blocking().unwrap(), "delayed");
+    }
+
+    #[test]
+    fn test_latest_on_broadcast_topic() {
+        let mut node = BoundedNode::<WatchTestTopic>::new(10);
+        let (publisher, mut subscriber) = node.pubsub_blocking(WatchTestTopic::Pose).unwrap();
+
+        publisher.write((0.0f32, 0.0f32));
+        publisher.write((1.0f32, 2.0f32));
+        assert_eq!(subscriber.latest().unwrap(), (1.0, 2.0));
+        assert!(subscriber.latest().is_none());
+    }
+
+    #[cfg(feature = "queue")]
+    #[test]
+    fn test_queue_competing_subscribers() {
+        let mut node = BoundedNode::<QueueTestTopic>::new(100);
+        let (publisher, mut sub1) = node.pubsub_blocking(QueueTestTopic::Jobs).unwrap();
+        let mut sub2 = node.subscriber_blocking::<u32>(QueueTestTopic::Jobs).unwrap();
+        let mut sub3 = sub2.clone();
+
+        for i in 0..99u32 {
+            publisher.write(i);
+        }
+
+        let mut received = Vec::new();
+        for _ in 0..33 {
+            received.push(sub1.try_read().unwrap());
+            received.push(sub2.try_read().unwrap());
+            received.push(sub3.try_read().unwrap());
+        }
+        assert!(sub1.try_read().is_none());
+        assert!(sub2.try_read().is_none());
+        assert!(sub3.try_read().is_none());
+        received.sort();
+        assert_eq!(received, (0..99).collect::<Vec<_>>());
+    }
+
+    #[cfg(feature = "queue")]
+    #[test]
+    fn test_queue_retains_without_subscribers() {
+        let mut node = UnboundedNode::<QueueTestTopic>::new();
+        let publisher = node.publisher_blocking::<u32>(QueueTestTopic::Small).unwrap();
+
+        for i in 0..5u32 {
+            publisher.write(i);
+        }
+
+        let mut subscriber = node.subscriber_blocking::<u32>(QueueTestTopic::Small).unwrap();
+        let received = std::iter::from_fn(|| subscriber.try_read()).collect::<Vec<_>>();
+        assert_eq!(received, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[cfg(feature = "queue")]
+    #[test]
+    fn test_queue_bounded_overflow() {
+        let mut node = BoundedNode::<QueueTestTopic>::new(100);
+        let (publisher, mut subscriber) = node.pubsub_blocking(QueueTestTopic::Small).unwrap();
+
+        for i in 0..5u32 {
+            publisher.write(i);
+        }
+
+        let received = std::iter::from_fn(|| subscriber.try_read()).collect::<Vec<_>>();
+        assert_eq!(received, vec![3, 4]);
+    }
+
+    #[cfg(feature = "queue")]
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_queue_worker_pool() {
+        let mut node = BoundedNode::<QueueTestTopic>::new(100);
+        let (publisher, subscriber) = node.pubsub(QueueTestTopic::Jobs).await.unwrap();
+
+        let workers = (0..4)
+            .map(|_| {
+                let mut worker = subscriber.clone();
+                tokio::spawn(async move {
+                    let timeout = std::time::Duration::from_millis(100);
+                    let mut jobs = Vec::new();
+                    while let Some(job) = worker.read_timeout(timeout).await {
+                        jobs.push(job);
+                    }
+                    jobs
+                })
+            })
+            .collect::<Vec<_>>();
+        drop(subscriber);
+
+        for i in 0..100u32 {
+            publisher.write(i);
+        }
+
+        let mut received = Vec::new();
+        for worker in workers {
+            received.extend(worker.await.unwrap());
+        }
+        received.sort();
+        assert_eq!(received, (0..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_unbounded_no_lagged_messages() {
+        let mut node = UnboundedNode::<TestTopic>::new();
+        let (publisher, mut sub1) = node.pubsub_blocking(TestTopic::C).unwrap();
+        let mut sub2 = sub1.clone();
+
+        for i in 0..10_000 {
+            publisher.write(i);
+        }
+
+        for i in 0..10_000 {
+            assert_eq!(sub1.try_read().unwrap(), i);
+            assert_eq!(sub2.try_read().unwrap(), i);
+        }
+        assert!(sub1.try_read().is_none());
+        assert!(sub2.try_read().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unbounded_async_pubsub() {
+        let mut node = UnboundedNode::<TestTopic>::new();
+        let (publisher, mut subscriber) = node.pubsub(TestTopic::A).await.unwrap();
+
+        let handle = tokio::spawn(async move {
+            publisher.write("unbounded".to_string());
+        });
+
+        handle.await.unwrap();
+        assert_eq!(subscriber.read().await.unwrap(), "unbounded");
+        let timeout = std::time::Duration::from_millis(100);
+        assert!(subscriber.read_timeout(timeout).await.is_none());
+    }
+
+    #[test]
+    fn test_unbounded_dropped_subscriber() {
+        let mut node = UnboundedNode::<TestTopic>::new();
+        let (publisher, sub1) = node.pubsub_blocking(TestTopic::B).unwrap();
+        let mut sub2 = node.subscriber_blocking::<bool>(TestTopic::B).unwrap();
+        drop(sub1);
+
+        publisher.write(true);
+        assert!(sub2.read_blocking().unwrap());
+    }
+
+    #[test]
+    fn test_unbounded_type_mismatch_errors() {
+        let mut node = UnboundedNode::<TestTopic>::new();
+
+        let publisher_res = node.publisher_blocking::<i32>(TestTopic::A);
+        assert!(matches!(publisher_res, Err(Error::PublisherMismatch(_, _))));
 
-// --------------------------------------------------
-// for testing
-// --------------------------------------------------
-#[allow(unused_imports)]
-use crosstalk_macros::init_test;
-#[allow(unused_imports)]
-use crosstalk_macros::AsTopicTest;
+        let subscriber_res = node.subscriber_blocking::<i32>(TestTopic::A);
+        assert!(matches!(subscriber_res, Err(Error::SubscriberMismatch(_, _))));
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[cfg(feature = "queue")]
+    #[tokio::test]
+    async fn test_service_call() {
+        let mut node = BoundedNode::<ServiceTestTopic>::new(10);
+        let mut server = node.service_server::<(i32, i32), i32>(ServiceTestTopic::Add).await.unwrap();
+        let client = node.service_client::<(i32, i32), i32>(ServiceTestTopic::Add).await.unwrap();
+        assert_eq!(client.server_count(), 1);
 
-    #[derive(AsTopicTest)]
-    enum TestTopic {
-        A,
-        B,
-        C,
+        tokio::spawn(async move { server.serve(|(a, b)| a + b).await; });
+        assert_eq!(client.call((1, 2)).await.unwrap(), 3);
+        assert_eq!(client.clone().call((40, 2)).await.unwrap(), 42);
     }
-    super::init_test! {
-        TestTopic::A => String,
-        TestTopic::B => bool,
-        TestTopic::C => i32,
+
+    #[cfg(feature = "queue")]
+    #[test]
+    fn test_service_call_blocking() {
+        let mut node = UnboundedNode::<ServiceTestTopic>::new();
+        let mut server = node.service_server_blocking::<String, String>(ServiceTestTopic::Echo).unwrap();
+        let client = node.service_client_blocking::<String, String>(ServiceTestTopic::Echo).unwrap();
+
+        let handle = std::thread::spawn(move || {
+            let request = server.read_blocking().unwrap();
+            let response = format!("{}:{}", request.id, request.request);
+            assert!(request.respond(response));
+        });
+        assert_eq!(client.call_blocking("echo".to_string()).unwrap(), "0:echo");
+        handle.join().unwrap();
     }
 
-    #[derive(AsTopicTest)]
-    enum AnotherTestTopic {
-        Foo,
-        Bar,
+    #[cfg(feature = "queue")]
+    #[test]
+    fn test_service_no_server() {
+        let mut node = BoundedNode::<ServiceTestTopic>::new(10);
+        let client = node.service_client_blocking::<(i32, i32), i32>(ServiceTestTopic::Add).unwrap();
+        assert_eq!(client.server_count(), 0);
+        assert!(matches!(client.call_blocking((1, 2)), Err(Error::NoServer)));
+
+        let server = node.service_server_blocking::<(i32, i32), i32>(ServiceTestTopic::Add).unwrap();
+        assert_eq!(client.server_count(), 1);
+        drop(server);
+        assert!(matches!(client.call_blocking((1, 2)), Err(Error::NoServer)));
     }
-    super::init_test! {
-        AnotherTestTopic::Foo => Vec<String>,
-        AnotherTestTopic::Bar => Vec<bool>,
+
+    #[cfg(feature = "queue")]
+    #[tokio::test]
+    async fn test_service_no_response() {
+        let mut node = BoundedNode::<ServiceTestTopic>::new(10);
+        let mut server = node.service_server::<(i32, i32), i32>(ServiceTestTopic::Add).await.unwrap();
+        let client = node.service_client::<(i32, i32), i32>(ServiceTestTopic::Add).await.unwrap();
+
+        tokio::spawn(async move {
+            // drop the request without responding
+            let _ = server.read().await;
+        });
+        assert!(matches!(client.call((1, 2)).await, Err(Error::NoResponse)));
+    }
+
+    #[cfg(feature = "queue")]
+    #[tokio::test]
+    async fn test_service_timeout() {
+        let mut node = BoundedNode::<ServiceTestTopic>::new(10);
+        let _server = node.service_server::<(i32, i32), i32>(ServiceTestTopic::Add).await.unwrap();
+        let client = node.service_client::<(i32, i32), i32>(ServiceTestTopic::Add).await.unwrap();
+
+        let timeout = std::time::Duration::from_millis(50);
+        assert!(matches!(client.call_timeout((1, 2), timeout).await, Err(Error::Timeout)));
+    }
+
+    #[cfg(feature = "queue")]
+    #[tokio::test]
+    async fn test_service_drop_server_of_closed_topic() {
+        let mut node = BoundedNode::<ServiceTestTopic>::new(10);
+        let first = node.service_server::<(i32, i32), i32>(ServiceTestTopic::Add).await.unwrap();
+        let mut second = node.service_server::<(i32, i32), i32>(ServiceTestTopic::Add).await.unwrap();
+        let client = node.service_client::<(i32, i32), i32>(ServiceTestTopic::Add).await.unwrap();
+
+        let calls = tokio::spawn(async move { tokio::join!(client.call((1, 2)), client.call((40, 2))) });
+        while node.topics().await.iter().all(|(_, info)| info.queued != Some(2)) {
+            tokio::task::yield_now().await;
+        }
+
+        // the node no longer holds a receiver once the topic is closed, yet
+        // dropping one server leaves the pending calls to the other one
+        assert!(node.close_topic(ServiceTestTopic::Add).await);
+        drop(first);
+        second.serve(|(a, b)| a + b).await;
+        let (a, b) = calls.await.unwrap();
+        assert_eq!((a.unwrap(), b.unwrap()), (3, 42));
     }
 
+    #[cfg(feature = "queue")]
     #[test]
-    fn test_single_pubsub_blocking() {
+    fn test_service_type_mismatch_errors() {
+        let mut node = BoundedNode::<ServiceTestTopic>::new(10);
+
+        let server_res = node.service_server_blocking::<String, i32>(ServiceTestTopic::Add);
+        assert!(matches!(server_res, Err(Error::ServiceMismatch(_, _))));
+
+        let client_res = node.service_client_blocking::<String, String>(ServiceTestTopic::Plain);
+        assert!(matches!(client_res, Err(Error::ServiceMismatch(_, _))));
+
+        let publisher_res = node.publisher_blocking::<(i32, i32)>(ServiceTestTopic::Add);
+        assert!(matches!(publisher_res, Err(Error::PublisherMismatch(_, _))));
+
+        let subscriber_res = node.subscriber_blocking::<String>(ServiceTestTopic::Echo);
+        assert!(matches!(subscriber_res, Err(Error::SubscriberMismatch(_, _))));
+    }
+
+    #[test]
+    fn test_try_write_counts_subscribers() {
+        let mut node = BoundedNode::<TestTopic>::new(10);
+        let (publisher, mut sub1) = node.pubsub_blocking(TestTopic::C).unwrap();
+        assert_eq!(publisher.try_write(1).unwrap(), 1);
+
+        let mut sub2 = sub1.clone();
+        assert_eq!(publisher.try_write(2).unwrap(), 2);
+        assert_eq!(sub1.try_read().unwrap(), 1);
+        assert_eq!(sub1.try_read().unwrap(), 2);
+        assert_eq!(sub2.try_read().unwrap(), 2);
+
+        drop(sub1);
+        drop(sub2);
+        let err = publisher.try_write(3).unwrap_err();
+        assert!(matches!(err.error, Error::NoSubscribers));
+        assert_eq!(err.into_inner(), 3);
+    }
+
+    #[test]
+    fn test_try_write_no_subscribers() {
+        let mut node = UnboundedNode::<TestTopic>::new();
+        let publisher = node.publisher_blocking::<i32>(TestTopic::C).unwrap();
+        assert!(publisher.try_write(1).is_err());
+        let mut subscriber = node.subscriber_blocking::<i32>(TestTopic::C).unwrap();
+        assert_eq!(publisher.try_write(2).unwrap(), 1);
+        assert_eq!(subscriber.try_read().unwrap(), 2);
+        drop(subscriber);
+        let res: Result<usize, Error> = publisher.try_write(3).map_err(Error::from);
+        assert!(matches!(res, Err(Error::NoSubscribers)));
+
+        let mut node = BoundedNode::<WatchTestTopic>::new(10);
+        let publisher = node.publisher_blocking::<String>(WatchTestTopic::Mode).unwrap();
+        assert!(publisher.try_write("idle".to_string()).is_err());
+        let mut subscriber = node.subscriber_blocking::<String>(WatchTestTopic::Mode).unwrap();
+        assert!(subscriber.latest().is_none());
+        assert_eq!(publisher.try_write("run".to_string()).unwrap(), 1);
+        assert_eq!(subscriber.latest().unwrap(), "run");
+
+        #[cfg(feature = "queue")]
+        {
+            let mut node = UnboundedNode::<QueueTestTopic>::new();
+            let publisher = node.publisher_blocking::<u32>(QueueTestTopic::Jobs).unwrap();
+            assert!(publisher.try_write(1).is_err());
+            let mut sub1 = node.subscriber_blocking::<u32>(QueueTestTopic::Jobs).unwrap();
+            let _sub2 = sub1.clone();
+            assert_eq!(publisher.try_write(2).unwrap(), 1);
+            assert_eq!(sub1.try_read().unwrap(), 2);
+            assert!(sub1.try_read().is_none());
+        }
+    }
+
+    #[test]
+    fn test_recv_errors() {
+        let mut node = BoundedNode::<TestTopic>::new(2);
+        let (publisher, mut subscriber) = node.pubsub_blocking(TestTopic::C).unwrap();
+        assert_eq!(subscriber.try_recv(), Err(RecvError::Empty));
+
+        for i in 0..5 {
+            publisher.write(i);
+        }
+        assert_eq!(subscriber.try_recv(), Err(RecvError::Lagged(3)));
+        assert_eq!(subscriber.try_recv(), Ok(3));
+        assert_eq!(subscriber.recv_blocking(), Ok(4));
+
+        #[cfg(feature = "queue")]
+        {
+            let mut node = UnboundedNode::<QueueTestTopic>::new();
+            let mut subscriber = node.subscriber_blocking::<u32>(QueueTestTopic::Jobs).unwrap();
+            assert_eq!(subscriber.try_recv(), Err(RecvError::Empty));
+        }
+
+        let mut node = BoundedNode::<WatchTestTopic>::new(10);
+        let (publisher, mut subscriber) = node.pubsub_blocking(WatchTestTopic::Mode).unwrap();
+        assert_eq!(subscriber.try_recv(), Err(RecvError::Empty));
+        publisher.write("idle".to_string());
+        assert_eq!(subscriber.recv_blocking(), Ok("idle".to_string()));
+    }
+
+    #[test]
+    fn test_recv_timeout_no_runtime() {
+        let mut node = BoundedNode::<TestTopic>::new(10);
+        let mut subscriber = node.subscriber_blocking::<i32>(TestTopic::C).unwrap();
+        let timeout = std::time::Duration::from_millis(10);
+        assert_eq!(block_on(subscriber.recv_timeout(timeout)), Err(RecvError::NoRuntime));
+    }
+
+    #[tokio::test]
+    async fn test_recv_timeout() {
+        let mut node = UnboundedNode::<TestTopic>::new();
+        let (publisher, mut subscriber) = node.pubsub(TestTopic::A).await.unwrap();
+        let timeout = std::time::Duration::from_millis(50);
+        assert_eq!(subscriber.recv_timeout(timeout).await, Err(RecvError::Timeout));
+        publisher.write("hello".to_string());
+        assert_eq!(subscriber.recv().await, Ok("hello".to_string()));
+    }
+
+    #[test]
+    fn test_lag_policies() {
+        let mut node = BoundedNode::<TestTopic>::new(2);
+        let (publisher, mut skip) = node.pubsub_blocking(TestTopic::C).unwrap();
+        let mut count = skip.clone();
+        count.set_lag_policy(LagPolicy::Count);
+        let mut error = count.clone();
+        error.set_lag_policy(LagPolicy::Error);
+        let mut latest = count.clone();
+        latest.set_lag_policy(LagPolicy::Latest);
+        assert_eq!(latest.clone().lag_policy(), LagPolicy::Latest);
+
+        for i in 0..5 {
+            publisher.write(i);
+        }
+
+        assert_eq!(skip.try_read(), Some(3));
+        assert_eq!(skip.lagged_total(), 0);
+
+        assert_eq!(count.read_blocking(), Some(3));
+        assert_eq!(count.lagged_total(), 3);
+
+        assert_eq!(error.try_read(), None);
+        assert_eq!(error.lagged_total(), 3);
+        assert_eq!(error.try_read(), Some(3));
+
+        assert_eq!(latest.try_read(), Some(4));
+        assert_eq!(latest.lagged_total(), 4);
+        assert_eq!(latest.try_read(), None);
+    }
+
+    #[tokio::test]
+    async fn test_lag_policy_read_timeout() {
+        let mut node = BoundedNode::<TestTopic>::new(2);
+        let (publisher, mut subscriber) = node.pubsub(TestTopic::C).await.unwrap();
+        subscriber.set_lag_policy(LagPolicy::Count);
+        for i in 0..5 {
+            publisher.write(i);
+        }
+        let timeout = std::time::Duration::from_millis(50);
+        assert_eq!(subscriber.read_timeout(timeout).await, Some(3));
+        assert_eq!(subscriber.recv().await, Ok(4));
+        assert_eq!(subscriber.lagged_total(), 3);
+
+        for i in 0..5 {
+            publisher.write(i);
+        }
+        assert_eq!(subscriber.recv().await, Err(RecvError::Lagged(3)));
+        assert_eq!(subscriber.latest(), Some(4));
+        assert_eq!(subscriber.lagged_total(), 6);
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn test_subscriber_stream() {
+        use futures::StreamExt;
+
+        let mut node = BoundedNode::<TestTopic>::new(10);
+        let (pub_a, sub_a) = node.pubsub(TestTopic::A).await.unwrap();
+        let (pub_c, sub_c) = node.pubsub(TestTopic::C).await.unwrap();
+        pub_a.write("hello".to_string());
+        pub_c.write(42);
+
+        let mut merged = futures::stream::select(
+            sub_a.map(|a| a.map(|a| a.len() as i32)),
+            sub_c,
+        );
+        let mut received = vec![merged.next().await.unwrap().unwrap(), merged.next().await.unwrap().unwrap()];
+        received.sort();
+        assert_eq!(received, vec![5, 42]);
+
+        // waiting streams are woken by new samples
+        let waiting = tokio::spawn(async move { merged.next().await });
+        tokio::task::yield_now().await;
+        pub_c.write(7);
+        assert_eq!(waiting.await.unwrap(), Some(Ok(7)));
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn test_stream_ends_on_close() {
+        use futures::StreamExt;
+
+        let mut node = BoundedNode::<TestTopic>::new(10);
+        let (publisher, subscriber) = node.pubsub(TestTopic::C).await.unwrap();
+        let collected = tokio::spawn(subscriber.collect::<Vec<_>>());
+        tokio::task::yield_now().await;
+        for i in 0..3 {
+            publisher.write(i);
+        }
+        assert!(node.close_topic(TestTopic::C).await);
+        assert_eq!(collected.await.unwrap(), vec![Ok(0), Ok(1), Ok(2)]);
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn test_stream_yields_lag() {
+        use futures::StreamExt;
+
+        let mut node = BoundedNode::<TestTopic>::new(1);
+        let (publisher, mut subscriber) = node.pubsub(TestTopic::C).await.unwrap();
+        subscriber.set_lag_policy(LagPolicy::Error);
+        publisher.write(0);
+        publisher.write(1);
+        assert_eq!(subscriber.next().await, Some(Err(RecvError::Lagged(1))));
+        assert_eq!(subscriber.next().await, Some(Ok(1)));
+        publisher.write(2);
+        publisher.write(3);
+        assert_eq!(subscriber.next().await, Some(Err(RecvError::Lagged(1))));
+        assert_eq!(subscriber.next().await, Some(Ok(3)));
+        assert_eq!(subscriber.lagged_total(), 2);
+    }
+
+    #[cfg(feature = "stream")]
+    #[tokio::test]
+    async fn test_subscriber_into_stream() {
+        use futures::StreamExt;
+
+        let mut node = BoundedNode::<TestTopic>::new(1);
+        let (publisher, mut subscriber) = node.pubsub(TestTopic::C).await.unwrap();
+        subscriber.set_lag_policy(LagPolicy::Error);
+        publisher.write(0);
+        publisher.write(1);
+
+        // lost samples are skipped rather than yielded, and counted per the lag policy
+        let mut stream = subscriber.into_stream();
+        assert_eq!(stream.next().await, Some(1));
+        publisher.write(2);
+        publisher.write(3);
+        drop(publisher);
+        assert_eq!(stream.by_ref().map(|i| i * 2).collect::<Vec<_>>().await, vec![6]);
+        assert_eq!(stream.into_inner().lagged_total(), 2);
+
+        // waiting streams are woken by new samples, and end on close
+        let (publisher, subscriber) = node.pubsub(TestTopic::C).await.unwrap();
+        let collected = tokio::spawn(subscriber.into_stream().collect::<Vec<_>>());
+        tokio::task::yield_now().await;
+        publisher.write(4);
+        assert!(node.close_topic(TestTopic::C).await);
+        assert_eq!(collected.await.unwrap(), vec![4]);
+    }
+
+    #[cfg(feature = "sink")]
+    #[tokio::test]
+    async fn test_publisher_sink() {
+        use futures::{SinkExt, StreamExt};
+
+        let mut node = BoundedNode::<TestTopic>::new(10);
+        let (mut publisher, mut subscriber) = node.pubsub(TestTopic::C).await.unwrap();
+        publisher.send(1).await.unwrap();
+        futures::stream::iter((2..4).map(Ok)).forward(&mut publisher).await.unwrap();
+        assert_eq!(subscriber.read().await, Some(1));
+        assert_eq!(subscriber.read().await, Some(2));
+        assert_eq!(subscriber.read().await, Some(3));
+
+        drop(subscriber);
+        assert_eq!(publisher.sink_policy(), SinkPolicy::Drop);
+        assert!(publisher.send(4).await.is_ok());
+        publisher.set_sink_policy(SinkPolicy::Error);
+        assert!(matches!(publisher.clone().send(5).await, Err(Error::NoSubscribers)));
+    }
+
+    #[test]
+    fn test_close_topic() {
         let mut node = BoundedNode::<TestTopic>::new(10);
         let (publisher, mut subscriber) = node.pubsub_blocking(TestTopic::A).unwrap();
-        publisher.write("test".to_string());
-        assert_eq!(subscriber.try_read().unwrap(), "test");
+        let mut other = node.subscriber_blocking::<i32>(TestTopic::C).unwrap();
+        publisher.write("hello".to_string());
+
+        assert!(node.close_topic_blocking(TestTopic::A));
+        assert!(!node.close_topic_blocking(TestTopic::A));
+        assert!(matches!(publisher.try_write("world".to_string()), Err(SendError { error: Error::TopicClosed, .. })));
+        assert_eq!(subscriber.read_blocking(), Some("hello".to_string()));
+        assert_eq!(subscriber.recv_blocking(), Err(RecvError::Closed));
+        assert_eq!(subscriber.clone().try_recv(), Err(RecvError::Closed));
+        assert_eq!(other.try_recv(), Err(RecvError::Empty));
+
+        // the topic opens again for new publishers and subscribers
+        let (publisher, mut subscriber) = node.pubsub_blocking(TestTopic::A).unwrap();
+        publisher.write("again".to_string());
+        assert_eq!(subscriber.try_read(), Some("again".to_string()));
     }
 
     #[test]
-    fn test_multiple_subscribers_blocking() {
+    fn test_close_topic_kinds() {
+        let mut node = UnboundedNode::<TestTopic>::new();
+        let (publisher, mut subscriber) = node.pubsub_blocking(TestTopic::C).unwrap();
+        publisher.write(1);
+        assert!(node.close_topic_blocking(TestTopic::C));
+        assert_eq!(subscriber.read_blocking(), Some(1));
+        assert_eq!(subscriber.read_blocking(), None);
+
+        let mut node = BoundedNode::<WatchTestTopic>::new(10);
+        let (publisher, mut subscriber) = node.pubsub_blocking(WatchTestTopic::Mode).unwrap();
+        publisher.write("idle".to_string());
+        assert!(node.close_topic_blocking(WatchTestTopic::Mode));
+        assert_eq!(subscriber.read_blocking(), Some("idle".to_string()));
+        assert_eq!(subscriber.recv_blocking(), Err(RecvError::Closed));
+
+        #[cfg(feature = "queue")]
+        {
+            let mut node = BoundedNode::<QueueTestTopic>::new(10);
+            let (publisher, mut subscriber) = node.pubsub_blocking(QueueTestTopic::Jobs).unwrap();
+            publisher.write(1u32);
+            assert!(node.close_topic_blocking(QueueTestTopic::Jobs));
+            assert_eq!(subscriber.read_blocking(), Some(1));
+            assert_eq!(subscriber.recv_blocking(), Err(RecvError::Closed));
+
+            let mut node = BoundedNode::<ServiceTestTopic>::new(10);
+            let mut server = node.service_server_blocking::<(i32, i32), i32>(ServiceTestTopic::Add).unwrap();
+            let client = node.service_client_blocking::<(i32, i32), i32>(ServiceTestTopic::Add).unwrap();
+            assert!(node.close_topic_blocking(ServiceTestTopic::Add));
+            assert!(server.read_blocking().is_none());
+            assert!(matches!(client.call_blocking((1, 2)), Err(Error::NoServer)));
+        }
+    }
+
+    #[test]
+    fn test_last_publisher_closes_topic() {
         let mut node = BoundedNode::<TestTopic>::new(10);
-        let (publisher, mut sub1) = node.pubsub_blocking(TestTopic::A).unwrap();
-        let mut sub2 = node.subscriber_blocking::<String>(TestTopic::A).unwrap();
+        let mut subscriber = node.subscriber_blocking::<i32>(TestTopic::C).unwrap();
+        let publisher = node.publisher_blocking::<i32>(TestTopic::C).unwrap();
+        let clone = publisher.clone();
+        publisher.write(1);
+        drop(publisher);
+        clone.write(2);
+        drop(clone);
+
+        assert_eq!(subscriber.read_blocking(), Some(1));
+        assert_eq!(subscriber.read_blocking(), Some(2));
+        assert_eq!(subscriber.read_blocking(), None);
+    }
+
+    #[test]
+    fn test_last_publisher_reopens_topic() {
+        let mut node = BoundedNode::<HistoryTestTopic>::new(10);
+        let mut subscriber = node.subscriber_blocking::<u32>(HistoryTestTopic::Config).unwrap();
+        let publisher = node.publisher_blocking::<u32>(HistoryTestTopic::Config).unwrap();
+        publisher.write(0);
+        publisher.write(1);
+        drop(publisher);
+        assert_eq!(subscriber.read_blocking(), Some(0));
+        assert_eq!(subscriber.read_blocking(), Some(1));
+        assert_eq!(subscriber.read_blocking(), None);
+
+        // late subscribers replay the history of the closed topic, which reopens
+        // for them and the next publisher, keeping its sequence numbers
+        let mut late = node.subscriber_blocking::<u32>(HistoryTestTopic::Config).unwrap();
+        assert_eq!(late.try_read(), Some(0));
+        assert_eq!(late.try_read(), Some(1));
+        let publisher = node.publisher_blocking::<u32>(HistoryTestTopic::Config).unwrap();
+        publisher.write(2);
+        assert_eq!(late.try_read_sample().map(|sample| (sample.data, sample.stamp.unwrap().sequence)), Some((2, 2)));
+
+        // subscribers of the closed topic subscribe again to read from the reopened one
+        assert_eq!(subscriber.try_recv(), Err(RecvError::Closed));
+        let mut again = subscriber.clone();
+        assert_eq!(again.try_read(), Some(1));
+        assert_eq!(again.try_read(), Some(2));
+    }
+
+    #[test]
+    fn test_publisher_races_last_publisher() {
+        let mut node = BoundedNode::<TestTopic>::new(10);
+        for i in 0..100 {
+            // the new publisher either keeps the topic open, or reopens it
+            let publisher = node.publisher_blocking::<i32>(TestTopic::C).unwrap();
+            let dropping = std::thread::spawn(move || drop(publisher));
+            let publisher = node.publisher_blocking::<i32>(TestTopic::C).unwrap();
+            dropping.join().unwrap();
+            let mut subscriber = node.subscriber_blocking::<i32>(TestTopic::C).unwrap();
+            publisher.write(i);
+            assert_eq!(subscriber.try_read(), Some(i));
+        }
+    }
 
+    #[tokio::test]
+    async fn test_last_publisher_ends_async_read() {
+        let mut node = UnboundedNode::<TestTopic>::new();
+        let (publisher, mut subscriber) = node.pubsub(TestTopic::A).await.unwrap();
+        let reader = tokio::spawn(async move {
+            let mut received = Vec::new();
+            while let Some(sample) = subscriber.read().await {
+                received.push(sample);
+            }
+            received
+        });
         publisher.write("hello".to_string());
-        assert_eq!(sub1.try_read().unwrap(), "hello");
-        assert_eq!(sub2.try_read().unwrap(), "hello");
+        drop(publisher);
+        assert_eq!(reader.await.unwrap(), vec!["hello".to_string()]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_shutdown_drains_subscribers() {
+        let mut node = BoundedNode::<TestTopic>::new(10);
+        let (pub_a, mut sub_a) = node.pubsub(TestTopic::A).await.unwrap();
+        let (pub_c, mut sub_c) = node.pubsub(TestTopic::C).await.unwrap();
+        let idle = node.subscriber::<bool>(TestTopic::B).await.unwrap();
+        pub_a.write("hello".to_string());
+        pub_c.write(1);
+        pub_c.write(2);
+
+        let shutdown = node.shutdown().await;
+        assert_eq!(shutdown.pending(), 3);
+        assert!(matches!(node.publisher::<String>(TestTopic::A).await, Err(Error::NodeShutdown)));
+        assert!(matches!(node.subscriber::<i32>(TestTopic::C).await, Err(Error::NodeShutdown)));
+        assert!(!node.close_topic(TestTopic::A).await);
+
+        let reader = tokio::spawn(async move {
+            let mut received = Vec::new();
+            while let Some(sample) = sub_c.read().await {
+                received.push(sample);
+            }
+            received
+        });
+        assert_eq!(sub_a.read().await, Some("hello".to_string()));
+        assert_eq!(sub_a.recv().await, Err(RecvError::Closed));
+        drop(idle);
+
+        shutdown.await;
+        assert_eq!(reader.await.unwrap(), vec![1, 2]);
+        assert!(pub_a.try_write("late".to_string()).is_err());
+    }
+
+    #[cfg(feature = "queue")]
+    #[test]
+    fn test_shutdown_blocking() {
+        let mut node = UnboundedNode::<ServiceTestTopic>::new();
+        let (publisher, mut subscriber) = node.pubsub_blocking(ServiceTestTopic::Plain).unwrap();
+        let mut server = node.service_server_blocking::<(i32, i32), i32>(ServiceTestTopic::Add).unwrap();
+        let client = node.service_client_blocking::<(i32, i32), i32>(ServiceTestTopic::Add).unwrap();
+        let reader = std::thread::spawn(move || {
+            while subscriber.read_blocking().is_some() {}
+        });
+        publisher.write("hello".to_string());
+        let caller = std::thread::spawn(move || client.call_blocking((1, 2)));
+        while node.topics_blocking().iter().all(|(_, info)| info.queued != Some(1)) {
+            std::thread::yield_now();
+        }
+
+        // the shutdown waits for the server to handle the pending request
+        let shutdown = node.shutdown_blocking();
+        let handled = std::thread::spawn(move || server.serve_blocking(|(a, b)| a + b));
+        shutdown.wait_blocking();
+        reader.join().unwrap();
+        handled.join().unwrap();
+        assert_eq!(caller.join().unwrap().unwrap(), 3);
+        assert!(matches!(
+            node.service_client_blocking::<(i32, i32), i32>(ServiceTestTopic::Add),
+            Err(Error::NodeShutdown),
+        ));
+    }
+
+    #[test]
+    fn test_topics_blocking() {
+        let mut node = BoundedNode::<CapacityTestTopic>::new(10);
+        assert!(node.topics_blocking().is_empty());
+        let (publisher, _subscriber) = node.pubsub_blocking::<u32>(CapacityTestTopic::Deep).unwrap();
+        let _idle = node.subscriber_blocking::<u32>(CapacityTestTopic::Default).unwrap();
+        let _another = publisher.clone();
+        publisher.write(1);
+        publisher.write(2);
+
+        let topics = node.topics_blocking().into_iter().collect::<HashMap<_, _>>();
+        assert_eq!(topics.len(), 2);
+        assert_eq!(topics[&CapacityTestTopic::Deep], TopicInfo {
+            kind: TopicKind::Broadcast,
+            service: false,
+            data_type: "u32",
+            capacity: Some(64),
+            publishers: 2,
+            subscribers: 1,
+            queued: Some(2),
+            closed: false,
+        });
+        let default = topics[&CapacityTestTopic::Default];
+        assert_eq!(default.capacity, Some(10));
+        assert_eq!((default.publishers, default.subscribers), (0, 1));
+        assert_eq!(default.queued, Some(0));
+
+        assert!(node.close_topic_blocking(CapacityTestTopic::Deep));
+        assert_eq!(node.topics_blocking().len(), 1);
+    }
+
+    #[cfg(feature = "queue")]
+    #[tokio::test]
+    async fn test_topics_kinds() {
+        let mut node = UnboundedNode::<ServiceTestTopic>::new();
+        let (publisher, subscriber) = node.pubsub::<String>(ServiceTestTopic::Plain).await.unwrap();
+        let _server = node.service_server::<String, String>(ServiceTestTopic::Echo).await.unwrap();
+        let _client = node.service_client::<String, String>(ServiceTestTopic::Echo).await.unwrap();
+        publisher.write("hello".to_string());
+
+        let topics = node.topics().await.into_iter().collect::<HashMap<_, _>>();
+        let plain = topics[&ServiceTestTopic::Plain];
+        assert_eq!(plain.kind, TopicKind::Broadcast);
+        assert!(!plain.service);
+        assert_eq!(plain.data_type, std::any::type_name::<String>());
+        assert_eq!((plain.capacity, plain.queued), (None, None));
+        assert_eq!((plain.publishers, plain.subscribers), (1, 1));
+        let echo = topics[&ServiceTestTopic::Echo];
+        assert_eq!(echo.kind, TopicKind::Queue);
+        assert!(echo.service);
+        assert_eq!(echo.data_type, std::any::type_name::<(String, String)>());
+        assert_eq!(echo.capacity, None);
+        assert_eq!((echo.publishers, echo.subscribers), (1, 1));
+        assert_eq!(echo.queued, Some(0));
+
+        drop(publisher);
+        let plain = node.topics().await.into_iter().find(|(t, _)| *t == ServiceTestTopic::Plain).unwrap().1;
+        assert!(plain.closed);
+        assert_eq!((plain.publishers, plain.subscribers), (0, 0));
+        drop(subscriber);
+
+        let mut node = BoundedNode::<WatchTestTopic>::new(10);
+        let publisher = node.publisher::<String>(WatchTestTopic::Mode).await.unwrap();
+        let info = node.topics().await[0].1;
+        assert_eq!((info.kind, info.capacity, info.queued), (TopicKind::Watch, Some(1), Some(0)));
+        publisher.write("auto".to_string());
+        assert_eq!(node.topics().await[0].1.queued, Some(1));
+
+        let mut node = BoundedNode::<QueueTestTopic>::new(10);
+        let (publisher, _subscriber) = node.pubsub::<u32>(QueueTestTopic::Small).await.unwrap();
+        publisher.write(1);
+        let info = node.topics().await[0].1;
+        assert_eq!(info.kind, TopicKind::Queue);
+        assert_eq!((info.capacity, info.queued, info.subscribers), (Some(2), Some(1), 1));
+    }
+
+    #[test]
+    fn test_topic_names() {
+        assert_eq!(TestTopic::A.name(), "TestTopic::A");
+        assert_eq!(WatchTestTopic::Mode.name(), "WatchTestTopic::Mode");
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn test_metrics() {
+        use metrics_util::debugging::{
+            DebugValue,
+            DebuggingRecorder,
+        };
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let (publisher, mut subscriber) = ::metrics::with_local_recorder(&recorder, || {
+            let mut node = BoundedNode::<CapacityTestTopic>::new(10);
+            let publisher = node.publisher_blocking::<u32>(CapacityTestTopic::Shallow).unwrap();
+            publisher.write(0);
+            assert!(publisher.try_write(0).is_err());
+            let subscriber = node.subscriber_blocking::<u32>(CapacityTestTopic::Shallow).unwrap();
+            (publisher, subscriber)
+        });
+        subscriber.set_lag_policy(LagPolicy::Skip);
+        for i in 0..3 {
+            publisher.write(i);
+        }
+        assert_eq!(subscriber.try_read(), Some(2));
+        assert_eq!(subscriber.try_read(), None);
+        assert_eq!(subscriber.lagged_total(), 0);
+        assert!(publisher.try_write(3).is_ok());
+        assert_eq!(subscriber.try_recv(), Ok(3));
+
+        // the metrics of every subscriber of the topic add up
+        let mut clone = subscriber.clone();
+        publisher.write(4);
+        assert_eq!(subscriber.try_read(), Some(4));
+        assert_eq!(clone.try_read(), Some(4));
+
+        let snapshot = snapshotter.snapshot().into_vec();
+        let value = |name: &str, label: Option<(&str, &str)>| {
+            snapshot.iter().find_map(|(key, _, _, value)| {
+                let key = key.key();
+                let labelled = key.labels().any(|l| label == Some((l.key(), l.value())));
+                (key.name() == name && (label.is_none() || labelled)).then(|| match value {
+                    DebugValue::Counter(n) => *n as f64,
+                    DebugValue::Gauge(n) => n.0,
+                    DebugValue::Histogram(_) => unreachable!(),
+                })
+            })
+        };
+        assert!(snapshot.iter().all(|(key, _, _, _)| key.key().labels().any(|l| l.key() == "topic" && l.value() == "CapacityTestTopic::Shallow")));
+        assert!(snapshot.iter().all(|(key, _, _, _)| key.key().labels().all(|l| l.key() != "subscriber")));
+        assert_eq!(value("crosstalk_published_total", None), Some(5.0));
+        assert_eq!(value("crosstalk_send_failed_total", Some(("reason", "no_subscribers"))), Some(2.0));
+        assert_eq!(value("crosstalk_send_failed_total", Some(("reason", "closed"))), Some(0.0));
+        assert_eq!(value("crosstalk_received_total", None), Some(4.0));
+        assert_eq!(value("crosstalk_lagged_total", None), Some(2.0));
+        assert_eq!(value("crosstalk_subscribers", None), Some(2.0));
+        assert_eq!(value("crosstalk_queue_depth", None), Some(1.0));
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_trace_propagation() {
+        use std::sync::Mutex;
+        use tracing_core::span::Current;
+        use tracing::span::{
+            Id,
+            Record,
+            Attributes,
+        };
+
+        /// Records new spans and the links between them
+        #[derive(Default)]
+        struct Links {
+            spans: Mutex<Vec<&'static tracing::Metadata<'static>>>,
+            refs: Mutex<std::collections::HashMap<u64, usize>>,
+            entered: Mutex<Vec<u64>>,
+            follows: Mutex<Vec<(u64, u64)>>,
+        }
+        impl Links {
+            fn closed(&self, id: u64) -> bool {
+                self.refs.lock().unwrap().get(&id) == Some(&0)
+            }
+        }
+        impl tracing::Subscriber for Links {
+            fn enabled(&self, _: &tracing::Metadata<'_>) -> bool { true }
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                let mut spans = self.spans.lock().unwrap();
+                spans.push(span.metadata());
+                self.refs.lock().unwrap().insert(spans.len() as u64, 1);
+                Id::from_u64(spans.len() as u64)
+            }
+            fn clone_span(&self, span: &Id) -> Id {
+                *self.refs.lock().unwrap().get_mut(&span.into_u64()).unwrap() += 1;
+                span.clone()
+            }
+            fn try_close(&self, span: Id) -> bool {
+                let mut refs = self.refs.lock().unwrap();
+                let refs = refs.get_mut(&span.into_u64()).unwrap();
+                *refs -= 1;
+                *refs == 0
+            }
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+            fn record_follows_from(&self, span: &Id, follows: &Id) {
+                self.follows.lock().unwrap().push((span.into_u64(), follows.into_u64()));
+            }
+            fn event(&self, _: &tracing::Event<'_>) {}
+            fn enter(&self, span: &Id) {
+                self.entered.lock().unwrap().push(span.into_u64());
+            }
+            fn exit(&self, _: &Id) {
+                self.entered.lock().unwrap().pop();
+            }
+            fn current_span(&self) -> Current {
+                match self.entered.lock().unwrap().last() {
+                    Some(&id) => Current::new(Id::from_u64(id), self.spans.lock().unwrap()[id as usize - 1]),
+                    None => Current::none(),
+                }
+            }
+        }
+
+        let links = Arc::new(Links::default());
+        tracing::subscriber::with_default(links.clone(), || {
+            let mut node = BoundedNode::<TestTopic>::new(10);
+            let (mut publisher, mut subscriber) = node.pubsub_blocking::<i32>(TestTopic::C).unwrap();
+            let untraced = publisher.clone();
+            publisher.set_trace(true);
+            assert!(publisher.clone().trace());
+            tracing::info_span!("producer").in_scope(|| {
+                publisher.write(1);
+                untraced.write(2);
+            });
+            // buffered samples do not keep the span of their publisher open
+            assert!(links.closed(1));
+
+            let sample = subscriber.read_blocking_traced().unwrap();
+            assert_eq!(sample.span.metadata().unwrap().name(), "crosstalk.read");
+            assert_eq!(sample.in_scope(|data| data), 1);
+            assert_eq!(subscriber.try_read_traced().unwrap().into_inner(), 2);
+
+            tracing::info_span!("producer").in_scope(|| publisher.write(3));
+            assert_eq!(subscriber.try_recv_traced().map(Traced::into_inner), Ok(3));
+            assert_eq!(subscriber.try_recv_traced().map(Traced::into_inner), Err(RecvError::Empty));
+        });
+        let names = links.spans.lock().unwrap().iter().map(|span| span.name()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["producer", "crosstalk.read", "crosstalk.read", "producer", "crosstalk.read"]);
+        assert_eq!(*links.follows.lock().unwrap(), vec![(2, 1), (5, 4)]);
+    }
+
+    #[test]
+    fn test_plain_channel() {
+        let (sndr, rcvr) = tokio::sync::broadcast::channel::<i32>(10);
+        let sndr = Arc::new(sndr);
+        let mut subscriber = Subscriber::new(TestTopic::C, Some(rcvr), sndr.clone());
+        let mut clone = subscriber.clone();
+        let publisher = Publisher::new(TestTopic::C, (*sndr).clone());
+        publisher.write(1);
+        assert_eq!(subscriber.try_read(), Some(1));
+        let sample = clone.try_read_sample().unwrap();
+        assert_eq!((sample.data, sample.publisher), (1, u64::MAX));
+        assert!(sample.stamp.is_none());
+
+        // samples sent straight through the channel are read all the same
+        drop(publisher);
+        sndr.send(2).unwrap();
+        assert_eq!(subscriber.try_read(), Some(2));
+        assert_eq!(Subscriber::new(TestTopic::C, None, sndr).try_recv(), Err(RecvError::Empty));
     }
 
     #[test]
-    fn test_cross_topic_isolation() {
-        let mut node = BoundedNode::<TestTopic>::new(10);
-        let (pub_a, mut sub_a) = node.pubsub_blocking(TestTopic::A).unwrap();
-        let (pub_b, mut sub_b) = node.pubsub_blocking(TestTopic::B).unwrap();
+    fn test_read_sample() {
+        let mut node = BoundedNode::<StampTestTopic>::new(10);
+        let (publisher, mut subscriber) = node.pubsub_blocking::<i32>(StampTestTopic::Frame).unwrap();
+        let another = publisher.clone();
+        assert_ne!(publisher.id(), another.id());
+        assert!(subscriber.try_read_sample().is_none());
 
-        pub_a.write("string".to_string());
-        pub_b.write(true);
+        let before = std::time::SystemTime::now();
+        publisher.write(0);
+        another.write(1);
+        assert!(publisher.try_write(2).is_ok());
+        let samples = (0..3).map(|_| subscriber.read_blocking_sample().unwrap()).collect::<Vec<_>>();
+        let stamps = samples.iter().map(|s| s.stamp.unwrap()).collect::<Vec<_>>();
+        assert_eq!(samples.iter().map(|s| s.data).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(samples.iter().map(|s| s.publisher).collect::<Vec<_>>(), vec![publisher.id(), another.id(), publisher.id()]);
+        assert_eq!(stamps.iter().map(|s| s.sequence).collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert!(stamps.windows(2).all(|w| w[0].published <= w[1].published));
+        assert!(stamps[0].timestamp >= before);
 
-        assert_eq!(sub_a.try_read().unwrap(), "string");
-        assert!(sub_b.try_read().unwrap());
-        assert!(sub_a.try_read().is_none());
-        assert!(sub_b.try_read().is_none());
+        // samples of topics which are not stamped only carry their publisher
+        let (plain, mut unstamped) = node.pubsub_blocking::<i32>(StampTestTopic::Plain).unwrap();
+        plain.write(3);
+        let sample = unstamped.try_read_sample().unwrap();
+        assert_eq!((sample.data, sample.publisher), (3, plain.id()));
+        assert!(sample.stamp.is_none());
+        assert!(sample.age().is_none());
+
+        // samples lost by a lagging subscriber show up as a jump in the sequence
+        let mut node = BoundedNode::<StampTestTopic>::new(2);
+        let (publisher, mut subscriber) = node.pubsub_blocking::<i32>(StampTestTopic::Frame).unwrap();
+        let mut late = subscriber.clone();
+        publisher.write(0);
+        assert_eq!(subscriber.try_read_sample().unwrap().stamp.unwrap().sequence, 0);
+        for i in 1..4 {
+            publisher.write(i);
+        }
+        assert_eq!(subscriber.try_read_sample().unwrap().stamp.unwrap().sequence, 2);
+        assert_eq!(late.try_read_sample().unwrap().stamp.unwrap().sequence, 2);
     }
 
     #[test]
-    fn test_multiple_threads_blocking() {
-        let mut node = BoundedNode::<TestTopic>::new(10);
-        let (publisher, mut subscriber) = node.pubsub_blocking(TestTopic::A).unwrap();
+    fn test_history_replay() {
+        let mut node = BoundedNode::<HistoryTestTopic>::new(10);
+        let publisher = node.publisher_blocking::<u32>(HistoryTestTopic::Config).unwrap();
+        let live = node.publisher_blocking::<u32>(HistoryTestTopic::Live).unwrap();
+        for i in 0..3 {
+            publisher.write(i);
+            live.write(i);
+        }
+        assert!(publisher.try_write(3).is_err());
 
-        let handle = std::thread::spawn(move || {
-            publisher.write("threaded".to_string());
-        });
+        // late subscribers first receive the last samples, then new ones
+        let mut subscriber = node.subscriber_blocking::<u32>(HistoryTestTopic::Config).unwrap();
+        let mut late = node.subscriber_blocking::<u32>(HistoryTestTopic::Live).unwrap();
+        publisher.write(4);
+        live.write(4);
+        assert_eq!(subscriber.try_read(), Some(1));
+        let mut clone = subscriber.clone();
+        assert_eq!(subscriber.try_read_sample().unwrap().stamp.unwrap().sequence, 2);
+        assert_eq!(subscriber.try_read(), Some(4));
+        assert_eq!(subscriber.try_read(), None);
+        assert_eq!(late.try_read(), Some(4));
+        assert_eq!(late.try_read(), None);
 
-        handle.join().unwrap();
-        assert_eq!(subscriber.try_read().unwrap(), "threaded");
+        // clones are new subscribers, and replay the history too
+        assert_eq!(clone.try_read(), Some(2));
+        assert_eq!(clone.try_read(), Some(4));
+        assert_eq!(clone.try_read(), None);
     }
 
     #[tokio::test]
-    async fn test_async_pubsub_single_runtime() {
-        let mut node = BoundedNode::<TestTopic>::new(10);
-        let (publisher, mut subscriber) = node.pubsub(TestTopic::A).await.unwrap();
-        publisher.write("async".to_string());
-        assert_eq!(subscriber.read().await.unwrap(), "async");
+    async fn test_history_replay_unbounded() {
+        let mut node = UnboundedNode::<HistoryTestTopic>::new();
+        let publisher = node.publisher::<u32>(HistoryTestTopic::Config).await.unwrap();
+        publisher.write(0);
+        let mut subscriber = node.subscriber::<u32>(HistoryTestTopic::Config).await.unwrap();
+        publisher.write(1);
+        drop(publisher);
+        assert_eq!(subscriber.recv().await, Ok(0));
+        assert_eq!(subscriber.read().await, Some(1));
+        assert_eq!(subscriber.recv().await, Err(RecvError::Closed));
     }
 
-    #[test]
-    fn test_high_volume_blocking() {
-        let mut node = BoundedNode::<TestTopic>::new(100);
-        let (publisher, mut subscriber) = node.pubsub_blocking(TestTopic::C).unwrap();
-
-        for i in 0..100 {
+    #[tokio::test(start_paused = true)]
+    async fn test_lifespan() {
+        let mut node = BoundedNode::<LifespanTestTopic>::new(2);
+        let (publisher, mut subscriber) = node.pubsub::<u32>(LifespanTestTopic::Pose).await.unwrap();
+        let (watch, mut mode) = node.pubsub::<u32>(LifespanTestTopic::Mode).await.unwrap();
+        #[cfg(feature = "queue")]
+        let (queue, mut jobs) = BoundedNode::<QueueTestTopic>::new(2).pubsub::<u32>(QueueTestTopic::Expiring).await.unwrap();
+        assert_eq!(subscriber.lifespan(), Some(Duration::from_millis(20)));
+        for i in 0..4 {
             publisher.write(i);
         }
+        watch.write(0);
+        #[cfg(feature = "queue")]
+        queue.write(0);
+        tokio::time::advance(Duration::from_millis(40)).await;
+        publisher.write(4);
+        #[cfg(feature = "queue")]
+        queue.write(1);
 
-        for i in 0..100 {
-            assert_eq!(subscriber.try_read().unwrap(), i);
+        // expired samples are skipped, and counted separately from lost ones
+        subscriber.set_lag_policy(LagPolicy::Count);
+        assert_eq!(subscriber.try_read(), Some(4));
+        assert_eq!(subscriber.lagged_total(), 3);
+        assert_eq!(subscriber.expired_total(), 1);
+        assert_eq!(subscriber.try_read(), None);
+        assert_eq!(mode.latest(), None);
+        assert_eq!(mode.latest(), None);
+        assert_eq!(mode.expired_total(), 1);
+        #[cfg(feature = "queue")]
+        {
+            assert_eq!(jobs.try_read(), Some(1));
+            assert_eq!(jobs.expired_total(), 1);
         }
-        assert!(subscriber.try_read().is_none());
-    }
 
-    #[test]
-    fn test_cloned_subscribers() {
-        let mut node = BoundedNode::<TestTopic>::new(10);
-        let (publisher, mut sub1) = node.pubsub_blocking(TestTopic::A).unwrap();
-        let mut sub2 = sub1.clone();
-
-        publisher.write("clone".to_string());
-        assert_eq!(sub1.try_read().unwrap(), "clone");
-        assert_eq!(sub2.try_read().unwrap(), "clone");
+        // fresh samples are read as usual
+        watch.write(1);
+        assert_eq!(mode.latest(), Some(1));
+        assert_eq!(mode.expired_total(), 1);
     }
 
-    #[test]
-    fn test_buffer_overflow_handling() {
-        let mut node = BoundedNode::<TestTopic>::new(2);
-        let (publisher, mut subscriber) = node.pubsub_blocking(TestTopic::A).unwrap();
+    #[tokio::test]
+    async fn test_deadline() {
+        let mut node = BoundedNode::<DeadlineTestTopic>::new(10);
+        let (publisher, mut subscriber) = node.pubsub::<u32>(DeadlineTestTopic::Heartbeat).await.unwrap();
+        assert_eq!(publisher.deadline(), Some(Duration::from_millis(20)));
+        assert_eq!(subscriber.deadline(), Some(Duration::from_millis(20)));
 
-        publisher.write("msg1".to_string());
-        publisher.write("msg2".to_string());
-        publisher.write("msg3".to_string());
+        // samples within the deadline
+        publisher.write(0);
+        assert_eq!(subscriber.try_recv(), Ok(0));
+        assert_eq!(subscriber.try_recv(), Err(RecvError::Empty));
+        assert!(!publisher.is_late());
 
-        assert_eq!(subscriber.try_read().unwrap(), "msg2");
-        assert_eq!(subscriber.try_read().unwrap(), "msg3");
-        assert!(subscriber.try_read().is_none());
-    }
+        // the subscriber is notified once per missed deadline, while waiting
+        let start = std::time::Instant::now();
+        assert_eq!(subscriber.recv().await, Err(RecvError::DeadlineMissed));
+        assert!(start.elapsed() < Duration::from_millis(200));
+        assert_eq!(subscriber.deadlines_missed(), 1);
 
-    #[test]
-    fn test_type_mismatch_errors() {
-        let mut node = BoundedNode::<TestTopic>::new(10);
-        
-        let publisher_res = node.publisher_blocking::<i32>(TestTopic::A);
-        assert!(matches!(publisher_res, Err(Error::PublisherMismatch(_, _))));
-        
-        let subscriber_res = node.subscriber_blocking::<i32>(TestTopic::A);
-        assert!(matches!(subscriber_res, Err(Error::SubscriberMismatch(_, _))));
-    }
+        // reads count missed deadlines, but keep waiting for the next sample
+        let (read, ()) = tokio::join!(subscriber.read(), async {
+            let restarted = publisher.clone();
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            restarted.write(9);
+        });
+        assert_eq!(read, Some(9));
+        let missed = subscriber.deadlines_missed();
+        assert!(missed >= 2);
+        assert!(publisher.is_late());
 
-    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
-    async fn test_multiple_async_runtimes() {
-        let mut node = BoundedNode::<TestTopic>::new(10);
-        let (publisher, mut subscriber) = node.pubsub(TestTopic::A).await.unwrap();
+        // ... or when polling
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(subscriber.try_recv(), Err(RecvError::DeadlineMissed));
+        assert_eq!(subscriber.try_recv(), Err(RecvError::Empty));
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(subscriber.try_read(), None);
+        assert_eq!(subscriber.deadlines_missed(), missed + 2);
 
-        let handle = tokio::spawn(async move {
-            publisher.write("async".to_string());
-        });
+        // late samples are counted by the publisher, and restart the deadline
+        publisher.write(1);
+        assert_eq!(publisher.deadlines_missed(), 1);
+        assert!(!publisher.is_late());
+        assert_eq!(subscriber.read().await, Some(1));
+        publisher.write(2);
+        assert_eq!(subscriber.read().await, Some(2));
+        assert_eq!(publisher.deadlines_missed(), 1);
+        assert_eq!(subscriber.deadlines_missed(), missed + 2);
 
-        handle.await.unwrap();
-        assert_eq!(subscriber.read().await.unwrap(), "async");
+        // clones have their own deadline
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert!(!publisher.clone().is_late());
+        assert_eq!(publisher.clone().deadlines_missed(), 0);
     }
 
+    #[cfg(feature = "serde")]
     #[test]
-    fn test_mixed_async_blocking() {
-        let mut node = BoundedNode::<TestTopic>::new(10);
-        let (publisher, mut subscriber) = node.pubsub_blocking(TestTopic::A).unwrap();
+    fn test_bytes() {
+        let mut node = BoundedNode::<SerdeTestTopic>::new(10);
 
-        let rt = tokio::runtime::Runtime::new().unwrap();
-        rt.block_on(async {
-            publisher.write("mixed".to_string());
-        });
+        // bytes published are read as typed samples, and vice versa
+        let publisher = node.byte_publisher_blocking(SerdeTestTopic::Json).unwrap();
+        let mut subscriber = node.subscriber_blocking::<Vec<String>>(SerdeTestTopic::Json).unwrap();
+        assert_eq!((publisher.format(), publisher.data_type()), (Format::Json, std::any::type_name::<Vec<String>>()));
+        publisher.publish_bytes(br#"["hello","world"]"#).unwrap();
+        assert_eq!(subscriber.try_read(), Some(vec!["hello".to_string(), "world".to_string()]));
+        assert!(matches!(publisher.publish_bytes(b"{"), Err(Error::Serialization)));
+        assert_eq!(subscriber.try_read(), None);
 
-        assert_eq!(subscriber.try_read().unwrap(), "mixed");
-    }
+        let sample = (7u32, "seven".to_string());
+        let publisher = node.publisher_blocking::<(u32, String)>(SerdeTestTopic::Bincode).unwrap();
+        let mut subscriber = node.byte_subscriber_blocking(SerdeTestTopic::Bincode).unwrap();
+        publisher.write(sample.clone());
+        let bytes = subscriber.try_read().unwrap();
+        assert_eq!(Format::Bincode.decode::<(u32, String)>(&bytes).unwrap(), sample);
+        assert_eq!(subscriber.try_read(), None);
 
-    #[test]
-    fn test_complex_data_types() {
-        let mut node = BoundedNode::<AnotherTestTopic>::new(10);
-        let (pub_foo, mut sub_foo) = node.pubsub_blocking(AnotherTestTopic::Foo).unwrap();
-        let (pub_bar, mut sub_bar) = node.pubsub_blocking(AnotherTestTopic::Bar).unwrap();
+        // byte publishers and subscribers can be chained, without knowing the data type
+        let publisher = node.byte_publisher_blocking(SerdeTestTopic::Cbor).unwrap();
+        let mut subscriber = node.byte_subscriber_blocking(SerdeTestTopic::Cbor).unwrap();
+        let bytes = Format::Cbor.encode(&Some(1.5f64)).unwrap();
+        publisher.publish_bytes(&bytes).unwrap();
+        assert_eq!(subscriber.read_blocking(), Some(bytes));
 
-        pub_foo.write(vec!["a".to_string(), "b".to_string()]);
-        pub_bar.write(vec![true, false]);
+        // topics without a format are not serializable
+        assert!(matches!(node.byte_publisher_blocking(SerdeTestTopic::Raw), Err(Error::NotSerializable)));
+        assert!(matches!(node.byte_subscriber_blocking(SerdeTestTopic::Raw), Err(Error::NotSerializable)));
+    }
 
-        assert_eq!(sub_foo.try_read().unwrap(), vec!["a", "b"]);
-        assert_eq!(sub_bar.try_read().unwrap(), vec![true, false]);
+    #[cfg(feature = "serde")]
+    #[tokio::test]
+    async fn test_bytes_unbounded() {
+        let mut node = UnboundedNode::<SerdeTestTopic>::new();
+        let publisher = node.byte_publisher(SerdeTestTopic::Json).await.unwrap();
+        let mut subscriber = node.byte_subscriber(SerdeTestTopic::Json).await.unwrap();
+        publisher.publish_bytes(br#"["hello"]"#).unwrap();
+        drop(publisher);
+        assert_eq!(subscriber.read().await, Some(br#"["hello"]"#.to_vec()));
+        assert_eq!(subscriber.read().await, None);
     }
 
+    #[cfg(feature = "serde")]
     #[test]
-    fn test_concurrent_publishers() {
-        let mut node = BoundedNode::<TestTopic>::new(100);
-        let (pub1, mut sub) = node.pubsub_blocking(TestTopic::C).unwrap();
-        let pub2 = node.publisher_blocking::<i32>(TestTopic::C).unwrap();
-
-        let handle1 = std::thread::spawn(move || {
-            for i in 0..50 {
-                pub1.write(i);
-            }
-        });
+    fn test_node_publish_bytes() {
+        let mut node = BoundedNode::<SerdeTestTopic>::new(10);
+        let mut subscriber = node.subscriber_blocking::<Vec<String>>(SerdeTestTopic::Json).unwrap();
 
-        let handle2 = std::thread::spawn(move || {
-            for i in 50..100 {
-                pub2.write(i);
-            }
-        });
+        // the node keeps a single publisher per topic, which keeps the topic open
+        node.publish_bytes_blocking(SerdeTestTopic::Json, br#"["hello"]"#).unwrap();
+        node.publish_bytes_blocking(SerdeTestTopic::Json, br#"["world"]"#).unwrap();
+        assert!(matches!(node.publish_bytes_blocking(SerdeTestTopic::Json, b"{"), Err(Error::Serialization)));
+        assert!(matches!(node.publish_bytes_blocking(SerdeTestTopic::Raw, b"1"), Err(Error::NotSerializable)));
+        assert_eq!(node.topics_blocking().iter().find(|(topic, _)| *topic == SerdeTestTopic::Json).unwrap().1.publishers, 1);
+        assert_eq!(subscriber.try_read(), Some(vec!["hello".to_string()]));
+        assert_eq!(subscriber.try_read(), Some(vec!["world".to_string()]));
+        assert_eq!(subscriber.try_recv(), Err(RecvError::Empty));
 
-        handle1.join().unwrap();
-        handle2.join().unwrap();
+        // closing the topic drops the publisher, and the next call publishes to the new topic
+        assert!(node.close_topic_blocking(SerdeTestTopic::Json));
+        assert_eq!(subscriber.try_recv(), Err(RecvError::Closed));
+        let mut subscriber = node.subscriber_blocking::<Vec<String>>(SerdeTestTopic::Json).unwrap();
+        node.publish_bytes_blocking(SerdeTestTopic::Json, br#"["again"]"#).unwrap();
+        assert_eq!(subscriber.try_read(), Some(vec!["again".to_string()]));
 
-        let mut received = Vec::new();
-        while let Some(msg) = sub.try_read() {
-            received.push(msg);
-        }
-        assert_eq!(received.len(), 100);
+        let _ = node.shutdown_blocking();
+        assert!(matches!(node.publish_bytes_blocking(SerdeTestTopic::Json, br#"["late"]"#), Err(Error::NodeShutdown)));
     }
 
+    #[cfg(feature = "bridge")]
     #[tokio::test]
-    async fn test_async_subscriber_cloning() {
-        let mut node = BoundedNode::<TestTopic>::new(10);
-        let (publisher, mut sub1) = node.pubsub(TestTopic::A).await.unwrap();
-        let mut sub2 = sub1.clone();
+    async fn test_bridge() {
+        let wait = Duration::from_secs(5);
+        let mut node_a = BoundedNode::<SerdeTestTopic>::new(10);
+        let mut node_b = BoundedNode::<SerdeTestTopic>::new(10);
+        let bridge = |node: &BoundedNode<SerdeTestTopic>, json, cbor| Bridge::new(node)
+            .topic(SerdeTestTopic::Json, json)
+            .topic(SerdeTestTopic::Bincode, Direction::Both)
+            .topic(SerdeTestTopic::Cbor, cbor)
+            .reconnect(Duration::from_millis(10));
+        let bridge_a = bridge(&node_a, Direction::Outbound, Direction::Inbound).listen("127.0.0.1:0").await.unwrap();
+        let addr = bridge_a.local_addr().unwrap();
+        let bridge_b = bridge(&node_b, Direction::Inbound, Direction::Outbound).connect(addr).await.unwrap();
+        assert_eq!(bridge_b.local_addr(), None);
+        tokio::time::timeout(wait, bridge_a.wait_connected()).await.unwrap();
+        tokio::time::timeout(wait, bridge_b.wait_connected()).await.unwrap();
+        assert_eq!((bridge_a.connections(), bridge_b.connections()), (1, 1));
 
-        publisher.write("async_clone".to_string());
-        assert_eq!(sub1.read().await.unwrap(), "async_clone");
-        assert_eq!(sub2.read().await.unwrap(), "async_clone");
-    }
+        // topics are forwarded in their direction only
+        let json_a = node_a.publisher::<Vec<String>>(SerdeTestTopic::Json).await.unwrap();
+        let mut json_b = node_b.subscriber::<Vec<String>>(SerdeTestTopic::Json).await.unwrap();
+        let cbor_a = node_a.publisher::<Option<f64>>(SerdeTestTopic::Cbor).await.unwrap();
+        let mut cbor_a_sub = node_a.subscriber::<Option<f64>>(SerdeTestTopic::Cbor).await.unwrap();
+        let cbor_b = node_b.publisher::<Option<f64>>(SerdeTestTopic::Cbor).await.unwrap();
+        let mut cbor_b_sub = node_b.subscriber::<Option<f64>>(SerdeTestTopic::Cbor).await.unwrap();
+        json_a.write(vec!["hello".to_string()]);
+        assert_eq!(tokio::time::timeout(wait, json_b.read()).await.unwrap(), Some(vec!["hello".to_string()]));
+        cbor_a.write(Some(1.0));
+        cbor_b.write(Some(2.0));
+        assert_eq!(cbor_a_sub.read().await, Some(Some(1.0)));
+        assert_eq!(tokio::time::timeout(wait, cbor_a_sub.read()).await.unwrap(), Some(Some(2.0)));
+        assert_eq!(cbor_b_sub.read().await, Some(Some(2.0)));
 
-    #[test]
-    fn test_dropped_publisher_behavior() {
-        let mut node = BoundedNode::<TestTopic>::new(10);
-        let (publisher, mut subscriber) = node.pubsub_blocking::<String>(TestTopic::A).unwrap();
-        drop(publisher);
-        assert!(subscriber.try_read().is_none());
+        // samples forwarded both ways are not echoed back
+        let bincode_a = node_a.publisher::<(u32, String)>(SerdeTestTopic::Bincode).await.unwrap();
+        let mut bincode_a_sub = node_a.subscriber::<(u32, String)>(SerdeTestTopic::Bincode).await.unwrap();
+        let bincode_b = node_b.publisher::<(u32, String)>(SerdeTestTopic::Bincode).await.unwrap();
+        let mut bincode_b_sub = node_b.subscriber::<(u32, String)>(SerdeTestTopic::Bincode).await.unwrap();
+        bincode_a.write((1, "a".to_string()));
+        assert_eq!(tokio::time::timeout(wait, bincode_b_sub.read()).await.unwrap(), Some((1, "a".to_string())));
+        bincode_b.write((2, "b".to_string()));
+        assert_eq!(tokio::time::timeout(wait, bincode_b_sub.read()).await.unwrap(), Some((2, "b".to_string())));
+        assert_eq!(bincode_a_sub.read().await, Some((1, "a".to_string())));
+        assert_eq!(tokio::time::timeout(wait, bincode_a_sub.read()).await.unwrap(), Some((2, "b".to_string())));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!((bincode_a_sub.try_read(), bincode_b_sub.try_read()), (None, None));
+        assert_eq!(cbor_b_sub.try_read(), None);
+
+        // the connecting end reconnects once the listener is back,
+        // without closing the topics in between
+        bridge_a.close();
+        while bridge_b.connections() > 0 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+        let bridge_a = loop {
+            match bridge(&node_a, Direction::Outbound, Direction::Inbound).listen(addr).await {
+                Ok(bridge_a) => break bridge_a,
+                Err(_) => tokio::time::sleep(Duration::from_millis(5)).await,
+            }
+        };
+        tokio::time::timeout(wait, bridge_b.wait_connected()).await.unwrap();
+        tokio::time::timeout(wait, bridge_a.wait_connected()).await.unwrap();
+        json_a.write(vec!["again".to_string()]);
+        assert_eq!(tokio::time::timeout(wait, json_b.read()).await.unwrap(), Some(vec!["again".to_string()]));
+
+        // topics without a format can not be forwarded
+        let err = Bridge::new(&node_a).topic(SerdeTestTopic::Raw, Direction::Inbound).listen("127.0.0.1:0").await.err().unwrap();
+        assert!(matches!(err.into_inner().unwrap().downcast_ref::<Error>(), Some(Error::NotSerializable)));
     }
 
+    #[cfg(feature = "bridge")]
     #[tokio::test]
-    async fn test_multiple_async_publishers() {
-        const LOOP_COUNT: usize = 10;
-        const BUFFER_SIZE: usize = 10;
-        let mut node = BoundedNode::<TestTopic>::new(BUFFER_SIZE);
-        let (publisher, mut subscriber) = node.pubsub(TestTopic::A).await.unwrap();
-        let publisher_1 = publisher.clone();
-        let publisher_2 = publisher.clone();
+    async fn test_bridge_mismatched_topics() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
-        let task1 = tokio::spawn({
-            async move {
-                for _ in 0..LOOP_COUNT {
-                    publisher_1.write("task1".to_string());
-                }
-            }
-        });
+        let wait = Duration::from_secs(5);
+        let mut node = BoundedNode::<SerdeTestTopic>::new(10);
+        let json = node.publisher::<Vec<String>>(SerdeTestTopic::Json).await.unwrap();
+        let mut json_sub = node.subscriber::<Vec<String>>(SerdeTestTopic::Json).await.unwrap();
+        let cbor = node.publisher::<Option<f64>>(SerdeTestTopic::Cbor).await.unwrap();
+        let mut cbor_sub = node.subscriber::<Option<f64>>(SerdeTestTopic::Cbor).await.unwrap();
+        let bridge = Bridge::new(&node)
+            .topic(SerdeTestTopic::Json, Direction::Both)
+            .topic(SerdeTestTopic::Cbor, Direction::Both)
+            .listen("127.0.0.1:0")
+            .await
+            .unwrap();
 
-        let task2 = tokio::spawn({
-            async move {
-                for _ in 0..LOOP_COUNT {
-                    publisher_2.write("task2".to_string());
-                }
-            }
-        });
+        let frame = |name: &str, data: &[u8]| {
+            let mut frame = ((2 + name.len() + data.len()) as u32).to_be_bytes().to_vec();
+            frame.extend_from_slice(&(name.len() as u16).to_be_bytes());
+            frame.extend_from_slice(name.as_bytes());
+            frame.extend_from_slice(data);
+            frame
+        };
+        let read_frame = async |peer: &mut tokio::net::TcpStream| {
+            let mut frame = vec![0; peer.read_u32().await.unwrap() as usize];
+            peer.read_exact(&mut frame).await.unwrap();
+            let name = u16::from_be_bytes([frame[0], frame[1]]) as usize + 2;
+            (String::from_utf8(frame[2..name].to_vec()).unwrap(), frame[name..].to_vec())
+        };
 
-        let _ = tokio::join!(task1, task2);
+        // the bridge declares its topics, along with their format and data type
+        let mut peer = tokio::net::TcpStream::connect(bridge.local_addr().unwrap()).await.unwrap();
+        let mut magic = [0; 5];
+        peer.read_exact(&mut magic).await.unwrap();
+        let (name, declaration) = read_frame(&mut peer).await;
+        let declaration = String::from_utf8(declaration).unwrap();
+        assert_eq!(name, "");
+        assert!(declaration.contains(&format!("SerdeTestTopic::Json\tJson\t{}\n", std::any::type_name::<Vec<String>>())));
 
-        let mut task1_count = 0;
-        let mut task2_count = 0;
-        for _ in 0..BUFFER_SIZE {
-            let msg = subscriber.read().await.unwrap();
-            if msg == "task1" { task1_count += 1; }
-            if msg == "task2" { task2_count += 1; }
-        }
-        assert_eq!(task1_count + task2_count, BUFFER_SIZE);
-    }
+        // the peer declares the json topic with another data type,
+        // so it is not forwarded either way, unlike the cbor topic
+        let declaration = format!(
+            "SerdeTestTopic::Json\tJson\tu32\nSerdeTestTopic::Cbor\tCbor\t{}\n",
+            std::any::type_name::<Option<f64>>(),
+        );
+        peer.write_all(&magic).await.unwrap();
+        peer.write_all(&frame("", declaration.as_bytes())).await.unwrap();
+        tokio::time::timeout(wait, bridge.wait_connected()).await.unwrap();
+        peer.write_all(&frame("SerdeTestTopic::Json", br#"["hello"]"#)).await.unwrap();
+        peer.write_all(&frame("SerdeTestTopic::Cbor", &Format::Cbor.encode(&Some(1.5f64)).unwrap())).await.unwrap();
+        assert_eq!(tokio::time::timeout(wait, cbor_sub.read()).await.unwrap(), Some(Some(1.5)));
+        assert_eq!(json_sub.try_read(), None);
 
-    #[test]
-    fn test_blocking_read_with_delay() {
-        let mut node = BoundedNode::<TestTopic>::new(10);
-        let (publisher, mut subscriber) = node.pubsub_blocking(TestTopic::A).unwrap();
+        json.write(vec!["hello".to_string()]);
+        cbor.write(Some(2.5));
+        let (name, data) = tokio::time::timeout(wait, read_frame(&mut peer)).await.unwrap();
+        assert_eq!(name, "SerdeTestTopic::Cbor");
+        assert_eq!(Format::Cbor.decode::<Option<f64>>(&data).unwrap(), Some(2.5));
+    }
 
-        std::thread::spawn(move || {
-            std::thread::sleep(std::time::Duration::from_millis(500));
-            publisher.write("delayed".to_string());
-        });
+    #[cfg(feature = "bridge")]
+    #[tokio::test]
+    async fn test_bridge_max_frame() {
+        let wait = Duration::from_secs(5);
+        let mut node_a = BoundedNode::<SerdeTestTopic>::new(10);
+        let mut node_b = BoundedNode::<SerdeTestTopic>::new(10);
+        let json_a = node_a.publisher::<Vec<String>>(SerdeTestTopic::Json).await.unwrap();
+        let mut json_b = node_b.subscriber::<Vec<String>>(SerdeTestTopic::Json).await.unwrap();
+        let bridge_a = Bridge::new(&node_a)
+            .topic(SerdeTestTopic::Json, Direction::Outbound)
+            .max_frame(64)
+            .listen("127.0.0.1:0")
+            .await
+            .unwrap();
+        let bridge_b = Bridge::new(&node_b)
+            .topic(SerdeTestTopic::Json, Direction::Inbound)
+            .connect(bridge_a.local_addr().unwrap())
+            .await
+            .unwrap();
+        tokio::time::timeout(wait, bridge_b.wait_connected()).await.unwrap();
 
-        assert_eq!(subscriber.read_blocking().unwrap(), "delayed");
+        // samples too large for a frame are dropped, without disconnecting
+        json_a.write(vec!["x".repeat(64)]);
+        json_a.write(vec!["small".to_string()]);
+        assert_eq!(tokio::time::timeout(wait, json_b.read()).await.unwrap(), Some(vec!["small".to_string()]));
+        assert_eq!(bridge_b.connections(), 1);
     }
 
+    #[cfg(all(feature = "bridge", unix))]
     #[tokio::test]
-    async fn test_read_timeout_behavior() {
-        let mut node = BoundedNode::<TestTopic>::new(10);
-        let (publisher, mut subscriber) = node.pubsub(TestTopic::A).await.unwrap();
+    async fn test_bridge_unix() {
+        use std::os::unix::fs::PermissionsExt;
+        let wait = Duration::from_secs(5);
+        let path = std::env::temp_dir().join(format!("crosstalk-test-{}.sock", std::process::id()));
+        let mut node_a = BoundedNode::<SerdeTestTopic>::new(10);
+        let mut node_b = BoundedNode::<SerdeTestTopic>::new(10);
+        let json_a = node_a.publisher::<Vec<String>>(SerdeTestTopic::Json).await.unwrap();
+        let mut json_b = node_b.subscriber::<Vec<String>>(SerdeTestTopic::Json).await.unwrap();
 
-        let timeout = std::time::Duration::from_millis(100);
-        assert!(subscriber.read_timeout(timeout).await.is_none());
+        // the socket is created with the permissions of the bridge
+        let bridge_a = Bridge::new(&node_a)
+            .topic(SerdeTestTopic::Json, Direction::Outbound)
+            .mode(0o660)
+            .listen_unix(&path)
+            .await
+            .unwrap();
+        assert_eq!(bridge_a.path(), Some(path.as_path()));
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o660);
+        let bridge_b = Bridge::new(&node_b)
+            .topic(SerdeTestTopic::Json, Direction::Inbound)
+            .reconnect(Duration::from_millis(10))
+            .connect_unix(&path)
+            .await
+            .unwrap();
+        assert_eq!(bridge_b.path(), None);
+        tokio::time::timeout(wait, bridge_b.wait_connected()).await.unwrap();
+        json_a.write(vec!["hello".to_string()]);
+        assert_eq!(tokio::time::timeout(wait, json_b.read()).await.unwrap(), Some(vec!["hello".to_string()]));
 
-        publisher.write("timeout_test".to_string());
-        assert_eq!(subscriber.read_timeout(timeout).await.unwrap(), "timeout_test");
+        // a socket in use is not replaced
+        let err = Bridge::new(&node_a).listen_unix(&path).await.err().unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::AddrInUse);
+
+        // the socket is removed with its bridge, and a stale socket is replaced
+        drop(bridge_a);
+        assert!(!path.exists());
+        let stale = std::os::unix::net::UnixListener::bind(&path).unwrap();
+        drop(stale);
+        let bridge_a = Bridge::new(&node_a)
+            .topic(SerdeTestTopic::Json, Direction::Outbound)
+            .listen_unix(&path)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().permissions().mode() & 0o777, 0o600);
+        tokio::time::timeout(wait, bridge_a.wait_connected()).await.unwrap();
+        json_a.write(vec!["again".to_string()]);
+        assert_eq!(tokio::time::timeout(wait, json_b.read()).await.unwrap(), Some(vec!["again".to_string()]));
+        drop(bridge_a);
+        assert!(!path.exists());
+
+        // other files are never replaced
+        std::fs::write(&path, b"data").unwrap();
+        let err = Bridge::new(&node_a).listen_unix(&path).await.err().unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::AddrInUse);
+        assert_eq!(std::fs::read(&path).unwrap(), b"data");
+        std::fs::remove_file(&path).unwrap();
     }
 
-    #[test]
-    fn test_multiple_topics_concurrently() {
-        let mut node = BoundedNode::<AnotherTestTopic>::new(10);
-        let (pub_foo, mut sub_foo) = node.pubsub_blocking(AnotherTestTopic::Foo).unwrap();
-        let (pub_bar, mut sub_bar) = node.pubsub_blocking(AnotherTestTopic::Bar).unwrap();
+    #[cfg(feature = "shm")]
+    #[tokio::test]
+    async fn test_shm() {
+        let wait = Duration::from_secs(5);
+        let path = std::env::temp_dir().join(format!("crosstalk-test-{}.shm", std::process::id()));
+        let frame = |id| Frame { id, pixels: [id as u8; 252] };
+        let mut node_a = BoundedNode::<ShmTestTopic>::new(10);
+        let mut node_b = BoundedNode::<ShmTestTopic>::new(10);
+        let publisher = node_a.publisher::<Arc<Frame>>(ShmTestTopic::Frame).await.unwrap();
+        let mut subscriber = node_b.subscriber::<Arc<Frame>>(ShmTestTopic::Frame).await.unwrap();
 
-        let handle1 = std::thread::spawn(move || {
-            pub_foo.write(vec!["thread".to_string()]);
-        });
+        // samples written to the topic of one node are read from the topic of the other
+        let shm_a = SharedMemory::new(&node_a).slots(2).publish::<Frame>(ShmTestTopic::Frame, &path).await.unwrap();
+        let shm_b = SharedMemory::new(&node_b)
+            .poll(Duration::from_millis(50))
+            .subscribe::<Frame>(ShmTestTopic::Frame, &path)
+            .await
+            .unwrap();
+        assert_eq!(shm_b.path(), path.as_path());
+        #[cfg(unix)]
+        assert_eq!(std::os::unix::fs::PermissionsExt::mode(&path.metadata().unwrap().permissions()) & 0o777, 0o600);
+        publisher.write(Arc::new(frame(0)));
+        assert_eq!(tokio::time::timeout(wait, subscriber.read()).await.unwrap(), Some(Arc::new(frame(0))));
+        assert_eq!(shm_b.lagged(), 0);
 
-        let handle2 = std::thread::spawn(move || {
-            pub_bar.write(vec![true]);
-        });
+        // samples overwritten before the ring is polled are skipped
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        for id in 1..6 {
+            publisher.write(Arc::new(frame(id)));
+        }
+        assert_eq!(tokio::time::timeout(wait, subscriber.read()).await.unwrap(), Some(Arc::new(frame(4))));
+        assert_eq!(subscriber.read().await, Some(Arc::new(frame(5))));
+        assert_eq!((shm_a.written(), shm_b.lagged()), (6, 3));
 
-        handle1.join().unwrap();
-        handle2.join().unwrap();
+        // rings only hold samples of their layout and tag
+        let err = SharedMemory::new(&node_b).subscribe::<[u8; 256]>(ShmTestTopic::Other, &path).await.err().unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        let err = SharedMemory::new(&node_b).tag(1).subscribe::<Frame>(ShmTestTopic::Frame, &path).await.err().unwrap();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
 
-        assert_eq!(sub_foo.try_read().unwrap(), vec!["thread"]);
-        assert_eq!(sub_bar.try_read().unwrap(), vec![true]);
-    }
 
-    #[test]
-    #[should_panic]
-    fn test_zero_capacity_node() {
-        let _ = BoundedNode::<TestTopic>::new(0);
+        // closing the ring removes it, and closes the topic of the other node
+        shm_a.close();
+        assert!(!path.exists());
+        assert_eq!(tokio::time::timeout(wait, subscriber.read()).await.unwrap(), None);
+        assert!(SharedMemory::new(&node_b).subscribe::<Frame>(ShmTestTopic::Frame, &path).await.is_err());
+
+        // samples are written and read without a topic, and a replaced
+        // ring keeps being read by the readers which opened it
+        let writer = SharedMemory::new(&node_a).slots(2).writer::<Frame>(&path).unwrap();
+        let mut reader = SharedMemory::new(&node_b).reader::<Frame>(&path).unwrap();
+        assert_eq!(reader.try_read(), None);
+        writer.write(&frame(7));
+        assert_eq!(tokio::time::timeout(wait, reader.read()).await.unwrap(), Some(&frame(7)));
+        let replacing = SharedMemory::new(&node_a).writer::<Frame>(&path).unwrap();
+        writer.write(&frame(8));
+        assert_eq!(reader.try_read(), Some(&frame(8)));
+        writer.close();
+        assert!(path.exists());
+        assert_eq!(tokio::time::timeout(wait, reader.read()).await.unwrap(), None);
+        assert_eq!(replacing.written(), 0);
+        drop(replacing);
+        assert!(!path.exists());
     }
 
+    #[cfg(feature = "shm")]
     #[tokio::test]
-    async fn test_async_unbounded_messaging() {
-        let mut node = BoundedNode::<TestTopic>::new(1000);
-        let (publisher, mut subscriber) = node.pubsub(TestTopic::A).await.unwrap();
+    async fn test_shm_large_samples() {
+        let wait = Duration::from_secs(5);
+        let path = std::env::temp_dir().join(format!("crosstalk-test-{}-image.shm", std::process::id()));
+        let image = |value| {
+            // samples of several MiB never land on the stack of the test thread
+            // safety: every bit pattern, including zeroes, is a valid image
+            let mut image = unsafe { Arc::<[u8; IMAGE]>::new_zeroed().assume_init() };
+            Arc::get_mut(&mut image).unwrap().fill(value);
+            image
+        };
+        let mut node_a = BoundedNode::<ShmTestTopic>::new(10);
+        let mut node_b = BoundedNode::<ShmTestTopic>::new(10);
+        let publisher = node_a.publisher::<Arc<[u8; IMAGE]>>(ShmTestTopic::Image).await.unwrap();
+        let mut subscriber = node_b.subscriber::<Arc<[u8; IMAGE]>>(ShmTestTopic::Image).await.unwrap();
+        let _shm_a = SharedMemory::new(&node_a).slots(2).publish::<[u8; IMAGE]>(ShmTestTopic::Image, &path).await.unwrap();
+        let shm_b = SharedMemory::new(&node_b).subscribe::<[u8; IMAGE]>(ShmTestTopic::Image, &path).await.unwrap();
 
-        let messages = vec!["msg1", "msg2", "msg3", "msg4", "msg5"];
-        for msg in &messages {
-            publisher.write(msg.to_string());
+        // every sample is read whole, and samples which are still held are not overwritten
+        let mut read = Vec::new();
+        for value in 1..=3 {
+            publisher.write(image(value));
+            let sample = tokio::time::timeout(wait, subscriber.read()).await.unwrap().unwrap();
+            assert!(sample.iter().all(|&pixel| pixel == value));
+            read.push(sample);
         }
+        assert!(read.iter().zip(1..).all(|(sample, value)| sample.iter().all(|&pixel| pixel == value)));
+        assert_eq!(shm_b.lagged(), 0);
+    }
 
-        for expected in messages {
-            assert_eq!(subscriber.read().await.unwrap(), expected);
+    #[cfg(feature = "discovery")]
+    #[tokio::test]
+    async fn test_discovery() {
+        use std::net::{Ipv4Addr, SocketAddrV4};
+        let wait = Duration::from_secs(5);
+        let group = SocketAddrV4::new(Ipv4Addr::new(239, 255, 0, 77), 17000 + (std::process::id() % 10000) as u16);
+        let interval = Duration::from_millis(20);
+        let discovery = |node| Discovery::new(node).group(group).interface(Ipv4Addr::LOCALHOST).interval(interval);
+        let mut node_a = BoundedNode::<TestTopic>::new(10);
+        let mut node_b = BoundedNode::<TestTopic>::new(10);
+        let _a = node_a.subscriber::<String>(TestTopic::A).await.unwrap();
+        let _c = node_a.subscriber::<i32>(TestTopic::C).await.unwrap();
+        let _a = node_b.publisher::<String>(TestTopic::A).await.unwrap();
+        let _b = node_b.publisher::<bool>(TestTopic::B).await.unwrap();
+
+        // a zero interval is rejected
+        let zero = Discovery::new(&node_a).interval(Duration::ZERO).start().await;
+        assert!(matches!(zero, Err(e) if e.kind() == std::io::ErrorKind::InvalidInput));
+
+        // peers are reported with the topics they share
+        let discovery_a = discovery(&node_a).start().await.unwrap();
+        let discovery_b = discovery(&node_b).start().await.unwrap();
+        let peer = tokio::time::timeout(wait, discovery_a.wait_for_peer()).await.unwrap();
+        assert!(peer.id == discovery_b.id() && peer.bridge.is_none() && peer.topics == vec![TestTopic::A]);
+        let peer = tokio::time::timeout(wait, discovery_b.wait_for_peer()).await.unwrap();
+        assert!(peer.id == discovery_a.id() && peer.topics == vec![TestTopic::A]);
+
+        // peers which disagree on a data type, or have another topic enum, are ignored
+        let socket = socket2::Socket::new(socket2::Domain::IPV4, socket2::Type::DGRAM, None).unwrap();
+        socket.set_multicast_if_v4(&Ipv4Addr::LOCALHOST).unwrap();
+        let socket = std::net::UdpSocket::from(socket);
+        let conflicting = format!("XTLK 1\nid\t1\nenum\t{}\ntopic\tTestTopic::A\tu8\n", std::any::type_name::<TestTopic>());
+        let foreign = format!("XTLK 1\nid\t2\nenum\tHouse\ntopic\tTestTopic::A\t{}\n", std::any::type_name::<String>());
+        let matching = format!("XTLK 1\nid\t3\nenum\t{}\nbridge\t7\ntopic\tTestTopic::A\t{}\n", std::any::type_name::<TestTopic>(), std::any::type_name::<String>());
+        for _ in 0..3 {
+            socket.send_to(conflicting.as_bytes(), group).unwrap();
+            socket.send_to(foreign.as_bytes(), group).unwrap();
+            socket.send_to(matching.as_bytes(), group).unwrap();
+            tokio::time::sleep(interval).await;
         }
+        let mut peers = discovery_a.peers().iter().map(|peer| (peer.id, peer.bridge.map(|addr| addr.port()))).collect::<Vec<_>>();
+        peers.sort();
+        let mut expected = vec![(3, Some(7)), (discovery_b.id(), None)];
+        expected.sort();
+        assert_eq!(peers, expected);
+
+        // peers which stop announcing themselves are forgotten
+        discovery_b.close();
+        tokio::time::timeout(wait, async {
+            while !discovery_a.peers().is_empty() {
+                tokio::time::sleep(interval).await;
+            }
+        }).await.unwrap();
     }
 
-    #[test]
-    fn test_error_handling_lagged_messages() {
-        let mut node = BoundedNode::<TestTopic>::new(2);
-        let (publisher, mut subscriber) = node.pubsub_blocking(TestTopic::A).unwrap();
+    #[cfg(all(feature = "discovery", feature = "bridge"))]
+    #[tokio::test]
+    async fn test_discovery_link() {
+        use std::net::{Ipv4Addr, SocketAddrV4};
+        let wait = Duration::from_secs(5);
+        let group = SocketAddrV4::new(Ipv4Addr::new(239, 255, 0, 78), 27000 + (std::process::id() % 10000) as u16);
+        let discovery = |node| Discovery::new(node)
+            .group(group)
+            .interface(Ipv4Addr::LOCALHOST)
+            .interval(Duration::from_millis(20))
+            .link(SerdeTestTopic::Json, Direction::Both);
+        let mut node_a = BoundedNode::<SerdeTestTopic>::new(10);
+        let mut node_b = BoundedNode::<SerdeTestTopic>::new(10);
+        let publisher = node_a.publisher::<Vec<String>>(SerdeTestTopic::Json).await.unwrap();
+        let mut subscriber = node_b.subscriber::<Vec<String>>(SerdeTestTopic::Json).await.unwrap();
 
-        for i in 0..5 {
-            publisher.write(format!("msg{}", i));
-        }
+        // matching peers are linked, and announce the port they listen on
+        let discovery_a = discovery(&node_a).start().await.unwrap();
+        let discovery_b = discovery(&node_b).start().await.unwrap();
+        let peer = tokio::time::timeout(wait, discovery_b.wait_for_peer()).await.unwrap();
+        assert!(peer.bridge.is_some_and(|addr| addr.ip() == Ipv4Addr::LOCALHOST));
+        let received = tokio::time::timeout(wait, async {
+            loop {
+                publisher.write(vec!["linked".to_string()]);
+                tokio::time::sleep(Duration::from_millis(10)).await;
+                if let Some(sample) = subscriber.try_read() {
+                    return sample;
+                }
+            }
+        }).await.unwrap();
+        assert_eq!(received, vec!["linked".to_string()]);
+        drop(discovery_a);
+    }
 
-        let mut received = Vec::new();
-        while let Some(msg) = subscriber.try_read() {
-            received.push(msg);
-        }
-        assert_eq!(received, vec!["msg3", "msg4"]);
+    #[tokio::test(start_paused = true)]
+    async fn test_liveliness() {
+        let mut node = BoundedNode::<TestTopic>::new(10);
+        let subscriber = node.subscriber::<String>(TestTopic::A).await.unwrap();
+        assert_eq!(subscriber.publisher_count(), 0);
+        assert!(!subscriber.publisher_alive(Duration::from_secs(1)));
+
+        // a blocked supervisor is woken by the first publisher
+        let waiting = tokio::task::spawn_blocking(move || {
+            subscriber.wait_for_publisher_blocking();
+            subscriber
+        });
+        let publisher = node.publisher::<String>(TestTopic::A).await.unwrap();
+        let mut subscriber = waiting.await.unwrap();
+        assert_eq!(subscriber.publisher_count(), 1);
+        subscriber.wait_for_publisher().await;
+
+        // publishers are alive while they publish or send heartbeats
+        let lease = Duration::from_millis(20);
+        assert!(subscriber.publisher_alive(lease));
+        tokio::time::advance(Duration::from_millis(30)).await;
+        assert!(!subscriber.publisher_alive(lease));
+        publisher.write("hello".to_string());
+        assert!(subscriber.publisher_alive(lease));
+        tokio::time::advance(Duration::from_millis(30)).await;
+        publisher.heartbeat();
+        assert!(subscriber.publisher_alive(lease));
+
+        // and dead once dropped, regardless of the lease
+        let clone = publisher.clone();
+        assert_eq!(subscriber.publisher_count(), 2);
+        drop(publisher);
+        drop(clone);
+        assert_eq!(subscriber.publisher_count(), 0);
+        assert!(!subscriber.publisher_alive(lease));
+        assert_eq!(subscriber.read().await, Some("hello".to_string()));
+        assert_eq!(subscriber.read().await, None);
+
+        // a restarted publisher reopens the topic, and wakes the supervisor
+        // waiting on the closed one, which then subscribes again
+        let waiting = tokio::spawn(async move {
+            subscriber.wait_for_publisher().await;
+            subscriber
+        });
+        tokio::task::yield_now().await;
+        let publisher = node.publisher::<String>(TestTopic::A).await.unwrap();
+        let subscriber = waiting.await.unwrap();
+        assert_eq!(subscriber.publisher_count(), 1);
+        assert!(subscriber.publisher_alive(lease));
+        let mut subscriber = subscriber.clone();
+        publisher.write("again".to_string());
+        assert_eq!(subscriber.read().await, Some("again".to_string()));
     }
 }
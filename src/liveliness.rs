@@ -0,0 +1,229 @@
+// --------------------------------------------------
+// external
+// --------------------------------------------------
+use std::time::Duration;
+use std::sync::atomic::{
+    AtomicU64,
+    AtomicUsize,
+    Ordering,
+};
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use crate::{
+    Publisher,
+    Subscriber,
+    block_on,
+};
+
+/// The publishers of a topic, and when they last asserted their liveliness
+/// 
+/// The publishers are kept when the topic is reopened, see [`crate::Sender`]
+/// 
+/// # Attributes
+/// 
+/// * `count` - the number of live publishers
+/// * `notify` - notified whenever a publisher is created
+/// * `epoch` - the reference point of `asserted`
+/// * `asserted` - the nanoseconds since `epoch` when a publisher last published,
+///   sent a heartbeat or was created, plus one (zero if never)
+pub(crate) struct Publishers {
+    count: AtomicUsize,
+    notify: Notify,
+    epoch: Instant,
+    asserted: AtomicU64,
+}
+/// [`Publishers`] implementation
+impl Publishers {
+    #[inline(always)]
+    /// Constructs a new [`Publishers`] without any publishers
+    pub(crate) fn new() -> Self {
+        Self {
+            count: AtomicUsize::new(0),
+            notify: Notify::new(),
+            epoch: Instant::now(),
+            asserted: AtomicU64::new(0),
+        }
+    }
+
+    #[inline(always)]
+    /// Registers a new publisher, waking every subscriber waiting for one
+    pub(crate) fn add(&self) {
+        self.count.fetch_add(1, Ordering::AcqRel);
+        self.assert_alive();
+        self.notify.notify_waiters();
+    }
+
+    #[inline(always)]
+    /// Unregisters a publisher, returning whether it was the last one
+    pub(crate) fn remove(&self) -> bool {
+        self.count.fetch_sub(1, Ordering::AcqRel) == 1
+    }
+
+    #[inline(always)]
+    /// The number of live publishers
+    pub(crate) fn count(&self) -> usize {
+        self.count.load(Ordering::Acquire)
+    }
+
+    #[inline(always)]
+    /// Records that a publisher is alive, as of now
+    pub(crate) fn assert_alive(&self) {
+        let nanos = self.epoch.elapsed().as_nanos() as u64;
+        self.asserted.fetch_max(nanos + 1, Ordering::AcqRel);
+    }
+
+    #[inline(always)]
+    /// Whether any publisher exists and asserted its liveliness within `lease`
+    fn alive(&self, lease: Duration) -> bool {
+        match self.asserted.load(Ordering::Acquire) {
+            0 => false,
+            asserted => {
+                let asserted = self.epoch + Duration::from_nanos(asserted - 1);
+                self.count() > 0 && asserted.elapsed() <= lease
+            },
+        }
+    }
+
+    /// Waits until the topic has at least one publisher
+    async fn wait(&self) {
+        loop {
+            // the notification is enabled before checking, so
+            // that it is not missed if it happens in between
+            let notified = self.notify.notified();
+            let mut notified = std::pin::pin!(notified);
+            notified.as_mut().enable();
+            if self.count() > 0 {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// [`Publisher`] implementation of liveliness
+impl<D, T> Publisher<D, T> {
+    #[inline(always)]
+    /// Asserts that the publisher is alive without publishing a sample,
+    /// see [`Subscriber::publisher_alive`]
+    /// 
+    /// Publishing a sample asserts liveliness as well, so this is only needed
+    /// by publishers which can be alive for a while without publishing
+    pub fn heartbeat(&self) {
+        self.buf.publishers.assert_alive();
+    }
+}
+
+/// [`Subscriber`] implementation of liveliness
+impl<D: Clone, T: Clone> Subscriber<D, T> {
+    #[inline(always)]
+    /// The number of [`Publisher`]s of the topic which currently exist
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bathroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bathroom => u8,
+    /// }
+    /// 
+    /// let mut node = crosstalk::BoundedNode::<House>::new(10);
+    /// let (publisher, subscriber) = node.pubsub_blocking::<u8>(House::Bathroom).unwrap();
+    /// assert_eq!(subscriber.publisher_count(), 1);
+    /// drop(publisher);
+    /// assert_eq!(subscriber.publisher_count(), 0);
+    /// ```
+    pub fn publisher_count(&self) -> usize {
+        self.sndr.publishers.count()
+    }
+
+    /// Waits until the topic has at least one [`Publisher`],
+    /// returning immediately if it already has one
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bathroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bathroom => u8,
+    /// }
+    /// 
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut node = crosstalk::BoundedNode::<House>::new(10);
+    ///     let subscriber = node.subscriber::<u8>(House::Bathroom).await.unwrap();
+    ///     let waiting = tokio::spawn(async move {
+    ///         subscriber.wait_for_publisher().await;
+    ///         subscriber.publisher_count()
+    ///     });
+    ///     let _publisher = node.publisher::<u8>(House::Bathroom).await.unwrap();
+    ///     assert_eq!(waiting.await.unwrap(), 1);
+    /// }
+    /// ```
+    pub async fn wait_for_publisher(&self) {
+        self.sndr.publishers.wait().await
+    }
+
+    #[inline(always)]
+    /// Blocks the current thread until the topic has at least one
+    /// [`Publisher`], returning immediately if it already has one
+    /// 
+    /// See [`Subscriber::wait_for_publisher`]
+    pub fn wait_for_publisher_blocking(&self) {
+        block_on(self.sndr.publishers.wait())
+    }
+
+    #[inline(always)]
+    /// Whether a [`Publisher`] of the topic exists and asserted its liveliness within
+    /// `lease`, by being created, publishing or sending a heartbeat (see [`Publisher::heartbeat`])
+    /// 
+    /// # Arguments
+    /// 
+    /// * `lease` - how long a publisher is considered alive after asserting its liveliness
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// use std::time::Duration;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bathroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bathroom => u8,
+    /// }
+    /// 
+    /// #[tokio::main(flavor = "current_thread", start_paused = true)]
+    /// async fn main() {
+    ///     let mut node = crosstalk::BoundedNode::<House>::new(10);
+    ///     let (publisher, subscriber) = node.pubsub::<u8>(House::Bathroom).await.unwrap();
+    ///     assert!(subscriber.publisher_alive(Duration::from_millis(100)));
+    /// 
+    ///     tokio::time::advance(Duration::from_millis(20)).await;
+    ///     assert!(!subscriber.publisher_alive(Duration::from_millis(10)));
+    ///     publisher.heartbeat();
+    ///     assert!(subscriber.publisher_alive(Duration::from_millis(10)));
+    /// }
+    /// ```
+    pub fn publisher_alive(&self, lease: Duration) -> bool {
+        self.sndr.publishers.alive(lease)
+    }
+}
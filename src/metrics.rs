@@ -0,0 +1,148 @@
+// --------------------------------------------------
+// external
+// --------------------------------------------------
+use metrics::{
+    Gauge,
+    Counter,
+    gauge,
+    counter,
+};
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use crate::RecvError;
+
+/// The metrics of a topic, shared by all of its publishers
+/// 
+/// Every metric is labelled with `topic`, see [`crate::CrosstalkTopic::name`]
+/// 
+/// The metrics are registered with the recorder installed when the node first
+/// uses the topic, and keep recording there until the topic is closed by its
+/// node. A recorder installed later only sees topics used after it
+/// 
+/// # Attributes
+/// 
+/// * `published` - `crosstalk_published_total`, the samples delivered to at least one subscriber
+/// * `no_subscribers` - `crosstalk_send_failed_total{reason="no_subscribers"}`, the samples published without any subscribers
+/// * `closed` - `crosstalk_send_failed_total{reason="closed"}`, the samples published after the topic was closed
+/// * `queued` - `crosstalk_queue_depth`, the number of samples buffered in the topic
+/// * `subscribers` - `crosstalk_subscribers`, the number of subscribers of the topic
+/// * `deadline_missed` - `crosstalk_publisher_deadline_missed_total`, the samples published after the deadline of their publisher
+/// * `subscriber` - the metrics shared by every subscriber of the topic, see [`SubscriberMetrics`]
+#[derive(Clone)]
+pub(crate) struct TopicMetrics {
+    published: Counter,
+    no_subscribers: Counter,
+    closed: Counter,
+    queued: Gauge,
+    subscribers: Gauge,
+    deadline_missed: Counter,
+    subscriber: SubscriberMetrics,
+}
+/// [`TopicMetrics`] implementation
+impl TopicMetrics {
+    #[inline(always)]
+    /// Registers the metrics of a topic with the global recorder
+    pub(crate) fn new(topic: &'static str) -> Self {
+        Self {
+            published: counter!("crosstalk_published_total", "topic" => topic),
+            no_subscribers: counter!("crosstalk_send_failed_total", "topic" => topic, "reason" => "no_subscribers"),
+            closed: counter!("crosstalk_send_failed_total", "topic" => topic, "reason" => "closed"),
+            queued: gauge!("crosstalk_queue_depth", "topic" => topic),
+            subscribers: gauge!("crosstalk_subscribers", "topic" => topic),
+            deadline_missed: counter!("crosstalk_publisher_deadline_missed_total", "topic" => topic),
+            subscriber: SubscriberMetrics {
+                received: counter!("crosstalk_received_total", "topic" => topic),
+                lagged: counter!("crosstalk_lagged_total", "topic" => topic),
+                expired: counter!("crosstalk_expired_total", "topic" => topic),
+                deadline_missed: counter!("crosstalk_subscriber_deadline_missed_total", "topic" => topic),
+            },
+        }
+    }
+
+    #[inline(always)]
+    /// Records a sample published to `subscribers` subscribers, along
+    /// with the number of samples `queued` in the topic afterwards
+    /// 
+    /// The queue depth is left untouched if it is unknown
+    pub(crate) fn published(&self, subscribers: usize, queued: Option<usize>) {
+        match subscribers {
+            0 => self.no_subscribers.increment(1),
+            _ => self.published.increment(1),
+        }
+        self.subscribers.set(subscribers as f64);
+        if let Some(queued) = queued {
+            self.queued.set(queued as f64);
+        }
+    }
+
+    #[inline(always)]
+    /// Records a sample published after the topic was closed
+    pub(crate) fn closed(&self) {
+        self.closed.increment(1);
+        self.subscribers.set(0.0);
+        self.queued.set(0.0);
+    }
+
+    #[inline(always)]
+    /// Records a sample published after the deadline of its publisher
+    pub(crate) fn deadline_missed(&self) {
+        self.deadline_missed.increment(1);
+    }
+
+    #[inline(always)]
+    /// The metrics of a new subscriber of the topic, which
+    /// add up with those of the other subscribers
+    pub(crate) fn subscriber(&self) -> SubscriberMetrics {
+        self.subscriber.clone()
+    }
+}
+
+/// The metrics of the subscribers of a topic
+/// 
+/// Every metric is labelled with `topic` only, and adds up the activity of
+/// every subscriber of the topic, so that their number does not grow with
+/// the subscribers created over the lifetime of the process
+/// 
+/// # Attributes
+/// 
+/// * `received` - `crosstalk_received_total`, the samples read by the subscribers
+/// * `lagged` - `crosstalk_lagged_total`, the samples lost by falling behind, regardless of the [`crate::LagPolicy`]
+/// * `expired` - `crosstalk_expired_total`, the samples skipped because they outlived their lifespan
+/// * `deadline_missed` - `crosstalk_subscriber_deadline_missed_total`, the deadlines passed without receiving any sample
+#[derive(Clone)]
+pub(crate) struct SubscriberMetrics {
+    received: Counter,
+    lagged: Counter,
+    expired: Counter,
+    deadline_missed: Counter,
+}
+/// [`SubscriberMetrics`] implementation
+impl SubscriberMetrics {
+    #[inline(always)]
+    /// Records the result of a read
+    pub(crate) fn received<D>(&self, res: &Result<D, RecvError>) {
+        if res.is_ok() {
+            self.received.increment(1);
+        }
+    }
+
+    #[inline(always)]
+    /// Records `n` samples lost by falling behind
+    pub(crate) fn lagged(&self, n: u64) {
+        self.lagged.increment(n);
+    }
+
+    #[inline(always)]
+    /// Records a sample skipped because it outlived its lifespan
+    pub(crate) fn expired(&self) {
+        self.expired.increment(1);
+    }
+
+    #[inline(always)]
+    /// Records a deadline passed without receiving any sample
+    pub(crate) fn deadline_missed(&self) {
+        self.deadline_missed.increment(1);
+    }
+}
@@ -0,0 +1,152 @@
+// --------------------------------------------------
+// external
+// --------------------------------------------------
+use std::time::{
+    Instant,
+    Duration,
+    SystemTime,
+};
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use crate::{
+    Envelope,
+    Subscriber,
+    ok_or_log,
+};
+
+#[derive(Clone, Debug)]
+/// A sample read by a [`Subscriber`], along with where and when it was published
+/// 
+/// Produced with [`Subscriber::read_sample`], [`Subscriber::try_read_sample`]
+/// or [`Subscriber::read_blocking_sample`]
+/// 
+/// # Attributes
+/// 
+/// * `data` - the sample
+/// * `publisher` - the id of the [`crate::Publisher`] of the sample, see [`crate::Publisher::id`]
+/// * `stamp` - the sequence number of the sample and when it was published, or [`None`]
+///   if the topic is not stamped, see [`crate::TopicConfig`]
+pub struct Sample<D> {
+    pub data: D,
+    pub publisher: u64,
+    pub stamp: Option<Stamp>,
+}
+/// [`Sample`] implementation
+impl<D> Sample<D> {
+    #[inline(always)]
+    /// Unwraps the sample, dropping where and when it was published
+    pub fn into_inner(self) -> D {
+        self.data
+    }
+
+    #[inline(always)]
+    /// The time elapsed since the sample was published, or [`None`]
+    /// if the topic is not stamped
+    pub fn age(&self) -> Option<Duration> {
+        self.stamp.as_ref().map(Stamp::age)
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+/// When a sample of a topic declared with `#[stamp]` was published, see [`Sample`]
+/// 
+/// # Attributes
+/// 
+/// * `sequence` - the sequence number of the sample within its topic, starting at 0.
+///   A jump between consecutive samples means the subscriber missed samples in between,
+///   except for `TopicKind::Queue` topics where samples are shared between subscribers.
+///   Samples of concurrent publishers may be received slightly out of sequence
+/// * `published` - when the sample was published, as read from the clock of [`tokio::time`]
+///   so that it follows paused time in tests
+/// * `timestamp` - the system time when the sample was published
+pub struct Stamp {
+    pub sequence: u64,
+    pub published: Instant,
+    pub timestamp: SystemTime,
+}
+/// [`Stamp`] implementation
+impl Stamp {
+    #[inline(always)]
+    /// The time elapsed since the sample was published
+    pub fn age(&self) -> Duration {
+        tokio::time::Instant::from_std(self.published).elapsed()
+    }
+}
+
+/// [`Envelope`] implementation of samples
+impl<D> Envelope<D> {
+    #[inline(always)]
+    /// Unwraps the sample along with where and when it was published
+    fn into_sample(self) -> Sample<D> {
+        Sample {
+            data: self.data,
+            publisher: self.publisher,
+            stamp: self.stamp.map(|stamp| *stamp),
+        }
+    }
+}
+
+/// [`Subscriber`] implementation of samples
+impl<D: Clone, T: Clone> Subscriber<D, T> {
+    #[inline(always)]
+    /// Asynchronous blocking read, along with where and when the sample was published
+    /// 
+    /// See [`Subscriber::read`] and [`Sample`]
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bathroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     #[stamp]
+    ///     House::Bathroom => u8,
+    /// }
+    /// 
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut node = crosstalk::BoundedNode::<House>::new(10);
+    ///     let (publisher, mut subscriber) = node.pubsub(House::Bathroom).await.unwrap();
+    ///     publisher.write(0u8);
+    ///     publisher.write(1u8);
+    /// 
+    ///     let first = subscriber.read_sample().await.unwrap();
+    ///     let second = subscriber.read_sample().await.unwrap();
+    ///     assert_eq!((first.data, second.data), (0, 1));
+    ///     assert_eq!(second.publisher, publisher.id());
+    /// 
+    ///     let (first, second) = (first.stamp.unwrap(), second.stamp.unwrap());
+    ///     assert_eq!(second.sequence, first.sequence + 1);
+    ///     assert!(second.published >= first.published);
+    /// }
+    /// ```
+    pub async fn read_sample(&mut self) -> Option<Sample<D>> {
+        let res = self.rcvr.read(&mut self.lag).await;
+        ok_or_log(self.observe(res)).map(Envelope::into_sample)
+    }
+
+    #[inline(always)]
+    /// Non-blocking read, along with where and when the sample was published
+    /// 
+    /// See [`Subscriber::try_read`] and [`Sample`]
+    pub fn try_read_sample(&mut self) -> Option<Sample<D>> {
+        let res = self.rcvr.try_read(&mut self.lag);
+        ok_or_log(self.observe(res)).map(Envelope::into_sample)
+    }
+
+    #[inline(always)]
+    /// Sequential blocking read, along with where and when the sample was published
+    /// 
+    /// See [`Subscriber::read_blocking`] and [`Sample`]
+    pub fn read_blocking_sample(&mut self) -> Option<Sample<D>> {
+        let res = self.rcvr.read_blocking(&mut self.lag);
+        ok_or_log(self.observe(res)).map(Envelope::into_sample)
+    }
+}
@@ -0,0 +1,795 @@
+// --------------------------------------------------
+// external
+// --------------------------------------------------
+use std::sync::Arc;
+use std::pin::Pin;
+use std::future::Future;
+use std::task::Poll;
+use std::sync::atomic::{
+    AtomicU64,
+    Ordering,
+};
+use async_channel::{
+    Sender as AsyncSender,
+    Receiver as AsyncReceiver,
+};
+use tokio::sync::oneshot::{
+    Sender as OneshotSender,
+    Receiver as OneshotReceiver,
+};
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use crate::{
+    Observer,
+    Observers,
+    TopicKind,
+    TopicInfo,
+    AnyChannel,
+    TopicConfig,
+    BoundedNode,
+    UnboundedNode,
+    CrosstalkData,
+    CrosstalkTopic,
+    ImplementedBoundedNode,
+    ImplementedUnboundedNode,
+};
+
+/// A trait to define a [`CrosstalkService`]
+/// 
+/// This is used to implement the [`CrosstalkService`] trait
+/// using the [`crosstalk_macros::init!`] macro
+/// for the [`ImplementedBoundedNode`] and [`ImplementedUnboundedNode`] structs
+/// 
+/// This is not meant to be used directly, and is automatically
+/// implemented when calling [`crosstalk_macros::init!`]
+pub trait CrosstalkService<T> {
+    fn service_server<Req: CrosstalkData, Resp: CrosstalkData>(&mut self, topic: T) -> Result<ServiceServer<Req, Resp, T>, crate::Error>;
+
+    fn service_client<Req: CrosstalkData, Resp: CrosstalkData>(&mut self, topic: T) -> Result<ServiceClient<Req, Resp, T>, crate::Error>;
+}
+
+/// [`BoundedNode`] implementation of services
+impl<T> BoundedNode<T>
+where
+    T: CrosstalkTopic,
+    ImplementedBoundedNode<T>: CrosstalkService<T>,
+{
+    #[inline(always)]
+    /// Creates a new service server for the given topic `T`
+    /// 
+    /// Multiple servers can exist for the same topic, in which
+    /// case each request is handled by exactly one of them
+    /// 
+    /// # Arguments
+    /// 
+    /// * `topic` - the topic to create a service server for
+    /// 
+    /// # Returns
+    /// 
+    /// A service server for the topic `T`
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bedroom,
+    ///     Doorbell,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bedroom => String,
+    ///     House::Doorbell => String -> bool,
+    /// }
+    /// 
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut node = crosstalk::BoundedNode::<House>::new(10);
+    ///     assert!(node.service_server::<String, bool>(House::Doorbell).await.is_ok());
+    ///     assert!(node.service_server::<String, bool>(House::Bedroom).await.is_err());
+    /// }
+    /// ```
+    pub async fn service_server<Req: CrosstalkData, Resp: CrosstalkData>(&mut self, topic: T) -> Result<ServiceServer<Req, Resp, T>, crate::Error> {
+        self.node.lock().await.service_server(topic)
+    }
+
+    #[inline(always)]
+    /// Creates a new service server for the given topic `T`
+    /// 
+    /// Multiple servers can exist for the same topic, in which
+    /// case each request is handled by exactly one of them
+    /// 
+    /// # Arguments
+    /// 
+    /// * `topic` - the topic to create a service server for
+    /// 
+    /// # Returns
+    /// 
+    /// A service server for the topic `T`
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bedroom,
+    ///     Doorbell,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bedroom => String,
+    ///     House::Doorbell => String -> bool,
+    /// }
+    /// 
+    /// let mut node = crosstalk::BoundedNode::<House>::new(10);
+    /// assert!(node.service_server_blocking::<String, bool>(House::Doorbell).is_ok());
+    /// assert!(node.service_server_blocking::<String, bool>(House::Bedroom).is_err());
+    /// ```
+    pub fn service_server_blocking<Req: CrosstalkData, Resp: CrosstalkData>(&mut self, topic: T) -> Result<ServiceServer<Req, Resp, T>, crate::Error> {
+        self.node.blocking_lock().service_server(topic)
+    }
+
+    #[inline(always)]
+    /// Creates a new service client for the given topic `T`
+    /// 
+    /// # Arguments
+    /// 
+    /// * `topic` - the topic to create a service client for
+    /// 
+    /// # Returns
+    /// 
+    /// A service client for the topic `T`
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bedroom,
+    ///     Doorbell,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bedroom => String,
+    ///     House::Doorbell => String -> bool,
+    /// }
+    /// 
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut node = crosstalk::BoundedNode::<House>::new(10);
+    ///     let mut server = node.service_server::<String, bool>(House::Doorbell).await.unwrap();
+    ///     let client = node.service_client::<String, bool>(House::Doorbell).await.unwrap();
+    ///     tokio::spawn(async move { server.serve(|visitor| visitor == "friend").await; });
+    ///     assert_eq!(client.call("friend".to_string()).await.unwrap(), true);
+    ///     assert_eq!(client.call("stranger".to_string()).await.unwrap(), false);
+    /// }
+    /// ```
+    pub async fn service_client<Req: CrosstalkData, Resp: CrosstalkData>(&mut self, topic: T) -> Result<ServiceClient<Req, Resp, T>, crate::Error> {
+        self.node.lock().await.service_client(topic)
+    }
+
+    #[inline(always)]
+    /// Creates a new service client for the given topic `T`
+    /// 
+    /// # Arguments
+    /// 
+    /// * `topic` - the topic to create a service client for
+    /// 
+    /// # Returns
+    /// 
+    /// A service client for the topic `T`
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bedroom,
+    ///     Doorbell,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bedroom => String,
+    ///     House::Doorbell => String -> bool,
+    /// }
+    /// 
+    /// let mut node = crosstalk::BoundedNode::<House>::new(10);
+    /// let client = node.service_client_blocking::<String, bool>(House::Doorbell).unwrap();
+    /// assert!(matches!(client.call_blocking("friend".to_string()), Err(crosstalk::Error::NoServer)));
+    /// ```
+    pub fn service_client_blocking<Req: CrosstalkData, Resp: CrosstalkData>(&mut self, topic: T) -> Result<ServiceClient<Req, Resp, T>, crate::Error> {
+        self.node.blocking_lock().service_client(topic)
+    }
+}
+
+/// [`UnboundedNode`] implementation of services
+impl<T> UnboundedNode<T>
+where
+    T: CrosstalkTopic,
+    ImplementedUnboundedNode<T>: CrosstalkService<T>,
+{
+    #[inline(always)]
+    /// Creates a new service server for the given topic `T`
+    /// 
+    /// See [`BoundedNode::service_server`]
+    pub async fn service_server<Req: CrosstalkData, Resp: CrosstalkData>(&mut self, topic: T) -> Result<ServiceServer<Req, Resp, T>, crate::Error> {
+        self.node.lock().await.service_server(topic)
+    }
+
+    #[inline(always)]
+    /// Creates a new service server for the given topic `T`
+    /// 
+    /// See [`BoundedNode::service_server_blocking`]
+    pub fn service_server_blocking<Req: CrosstalkData, Resp: CrosstalkData>(&mut self, topic: T) -> Result<ServiceServer<Req, Resp, T>, crate::Error> {
+        self.node.blocking_lock().service_server(topic)
+    }
+
+    #[inline(always)]
+    /// Creates a new service client for the given topic `T`
+    /// 
+    /// See [`BoundedNode::service_client`]
+    pub async fn service_client<Req: CrosstalkData, Resp: CrosstalkData>(&mut self, topic: T) -> Result<ServiceClient<Req, Resp, T>, crate::Error> {
+        self.node.lock().await.service_client(topic)
+    }
+
+    #[inline(always)]
+    /// Creates a new service client for the given topic `T`
+    /// 
+    /// See [`BoundedNode::service_client_blocking`]
+    pub fn service_client_blocking<Req: CrosstalkData, Resp: CrosstalkData>(&mut self, topic: T) -> Result<ServiceClient<Req, Resp, T>, crate::Error> {
+        self.node.blocking_lock().service_client(topic)
+    }
+}
+
+/// [`ImplementedBoundedNode`] implementation of services
+impl<T> ImplementedBoundedNode<T>
+where
+    T: CrosstalkTopic,
+{
+    /// Gets the [`Service`] of the given topic, creating
+    /// the underlying channel if it does not exist yet
+    /// 
+    /// This is not meant to be used directly, and is called
+    /// from the arms generated by [`crosstalk_macros::init!`]
+    /// 
+    /// # Arguments
+    /// 
+    /// * `topic` - the topic to get the [`Service`] of
+    /// * `config` - the configuration of the topic, used if the channel is created
+    /// * `err` - the error to return if the existing [`Service`] is not of type `Req` and `Resp`
+    pub fn service<Req: CrosstalkData, Resp: CrosstalkData>(&mut self, topic: T, config: TopicConfig, err: crate::Error) -> Result<Service<Req, Resp>, crate::Error> {
+        if self.closed {
+            return Err(crate::Error::NodeShutdown);
+        }
+        // closed services are replaced by a new channel
+        match self.channels.get(&topic).filter(|service| !service.is_closed()) {
+            Some(service) => service.as_any().downcast_ref::<Service<Req, Resp>>().cloned().ok_or(err),
+            None => {
+                // pending requests are bounded by the capacity of the topic,
+                // or the size defined during crosstalk::BoundedNode::new(size)
+                let (sender, receiver) = async_channel::bounded(config.capacity.unwrap_or(self.size));
+                let service = Service::new(sender, receiver);
+                self.channels.insert(topic, Box::new(service.clone()));
+                Ok(service)
+            },
+        }
+    }
+}
+
+/// [`ImplementedUnboundedNode`] implementation of services
+impl<T> ImplementedUnboundedNode<T>
+where
+    T: CrosstalkTopic,
+{
+    /// Gets the [`Service`] of the given topic, creating
+    /// the underlying channel if it does not exist yet
+    /// 
+    /// This is not meant to be used directly, and is called
+    /// from the arms generated by [`crosstalk_macros::init!`]
+    /// 
+    /// # Arguments
+    /// 
+    /// * `topic` - the topic to get the [`Service`] of
+    /// * `config` - the configuration of the topic, used if the channel is created. The capacity is ignored
+    /// * `err` - the error to return if the existing [`Service`] is not of type `Req` and `Resp`
+    pub fn service<Req: CrosstalkData, Resp: CrosstalkData>(&mut self, topic: T, _config: TopicConfig, err: crate::Error) -> Result<Service<Req, Resp>, crate::Error> {
+        if self.closed {
+            return Err(crate::Error::NodeShutdown);
+        }
+        // closed services are replaced by a new channel
+        match self.channels.get(&topic).filter(|service| !service.is_closed()) {
+            Some(service) => service.as_any().downcast_ref::<Service<Req, Resp>>().cloned().ok_or(err),
+            None => {
+                let (sender, receiver) = async_channel::unbounded();
+                let service = Service::new(sender, receiver);
+                self.channels.insert(topic, Box::new(service.clone()));
+                Ok(service)
+            },
+        }
+    }
+}
+
+/// The channel of a service
+/// 
+/// One [`Service`] exists per service topic within a node, and
+/// is split into every [`ServiceServer`] and [`ServiceClient`] of that topic
+/// 
+/// # Attributes
+/// 
+/// * `requests` - the sending half of the pending requests, used by clients
+/// * `servers` - the receiving half of the pending requests, used by servers.
+///   The node keeps one, so that the channel stays open while there are no servers
+/// * `ids` - the next correlation id of the service
+/// * `observers` - the servers of the service which have not yet observed its close
+/// 
+/// This is not meant to be used directly, please
+/// use the [`crosstalk_macros::init!`] macro instead
+pub struct Service<Req, Resp> {
+    requests: AsyncSender<ServiceRequest<Req, Resp>>,
+    servers: AsyncReceiver<ServiceRequest<Req, Resp>>,
+    ids: Arc<AtomicU64>,
+    observers: Arc<Observers>,
+}
+/// [`Service`] implementation
+impl<Req, Resp> Service<Req, Resp> {
+    #[inline(always)]
+    /// Constructs a new [`Service`]
+    fn new(
+        requests: AsyncSender<ServiceRequest<Req, Resp>>,
+        servers: AsyncReceiver<ServiceRequest<Req, Resp>>,
+    ) -> Self {
+        Self { requests, servers, ids: Arc::new(AtomicU64::new(0)), observers: Arc::new(Observers::default()) }
+    }
+}
+/// [`Service`] implementation of [`AnyChannel`]
+impl<Req: 'static, Resp: 'static> AnyChannel for Service<Req, Resp> {
+    /// Closes the service, so that servers stop once they have
+    /// handled every pending request, and clients fail with [`crate::Error::NoServer`]
+    fn close(&self) -> bool {
+        self.requests.close()
+    }
+
+    /// Closes the service, returning a [`crate::Shutdown`] which resolves once every
+    /// server has handled the pending requests and observed the close, or has been dropped
+    fn shutdown(&self) -> crate::Shutdown {
+        self.requests.close();
+        crate::Shutdown::new(self.observers.clone())
+    }
+
+    fn is_closed(&self) -> bool {
+        self.requests.is_closed()
+    }
+
+    fn info(&self) -> TopicInfo {
+        TopicInfo {
+            kind: TopicKind::Queue,
+            service: true,
+            data_type: std::any::type_name::<(Req, Resp)>(),
+            capacity: self.requests.capacity(),
+            // the node holds a sender and a receiver of its own
+            publishers: self.requests.sender_count().saturating_sub(1),
+            subscribers: self.requests.receiver_count().saturating_sub(1),
+            queued: Some(self.requests.len()),
+            closed: self.requests.is_closed(),
+        }
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+}
+/// [`Service`] implementation of [`Clone`]
+impl<Req, Resp> Clone for Service<Req, Resp> {
+    fn clone(&self) -> Self {
+        Self {
+            requests: self.requests.clone(),
+            servers: self.servers.clone(),
+            ids: self.ids.clone(),
+            observers: self.observers.clone(),
+        }
+    }
+}
+
+/// A request received by a [`ServiceServer`]
+/// 
+/// # Attributes
+/// 
+/// * `id` - the correlation id of the request, unique per service
+/// * `request` - the request sent by the [`ServiceClient`]
+/// * `reply` - the channel to respond on
+/// 
+/// # Type Parameters
+/// 
+/// * `Req` - the request type of the service
+/// * `Resp` - the response type of the service
+pub struct ServiceRequest<Req, Resp> {
+    pub id: u64,
+    pub request: Req,
+    reply: OneshotSender<Resp>,
+}
+/// [`ServiceRequest`] implementation
+impl<Req, Resp> ServiceRequest<Req, Resp> {
+    #[inline(always)]
+    /// Responds to the request
+    /// 
+    /// # Arguments
+    /// 
+    /// * `response` - the response to send to the [`ServiceClient`]
+    /// 
+    /// # Returns
+    /// 
+    /// `true` if the response was delivered, or `false` if the
+    /// [`ServiceClient`] stopped waiting (e.g. timed out)
+    pub fn respond(self, response: Resp) -> bool {
+        self.reply.send(response).is_ok()
+    }
+}
+
+/// A `crosstalk` [`ServiceServer`]
+/// 
+/// # Attributes
+/// 
+/// * `topic` - the topic of the service
+/// * `buf` - the receiver of pending requests
+/// * `observer` - the handle of the server on the `Observers` of the service,
+///   which lets waiting clients know once every server is gone
+/// 
+/// # Type Parameters
+/// 
+/// * `Req` - the request type of the service
+/// * `Resp` - the response type of the service
+/// * `T` - the topic of the service
+/// 
+/// This is not meant to be used directly, please
+/// use the [`crosstalk_macros::init!`] macro instead
+/// and produce a [`ServiceServer`] with [`BoundedNode::service_server`]
+pub struct ServiceServer<Req, Resp, T> {
+    pub topic: T,
+    buf: AsyncReceiver<ServiceRequest<Req, Resp>>,
+    observer: Observer,
+}
+/// [`ServiceServer`] implementation
+impl<Req, Resp, T> ServiceServer<Req, Resp, T> {
+    #[inline(always)]
+    /// See [`BoundedNode::service_server`]
+    pub fn new(topic: T, service: Service<Req, Resp>) -> Self {
+        Self { topic, buf: service.servers, observer: service.observers.observer() }
+    }
+
+    #[inline(always)]
+    /// Asynchronous blocking read of the next request
+    /// 
+    /// Returns [`None`] if the service is closed
+    pub async fn read(&mut self) -> Option<ServiceRequest<Req, Resp>> {
+        let res = self.buf.recv().await.map_err(|_| crate::RecvError::Closed);
+        self.observer.observe(res).ok()
+    }
+
+    #[inline(always)]
+    /// Non-blocking read of the next request
+    /// 
+    /// Returns [`None`] if there are no pending requests
+    pub fn try_read(&mut self) -> Option<ServiceRequest<Req, Resp>> {
+        let res = self.buf.try_recv().map_err(|e| match e {
+            async_channel::TryRecvError::Empty => crate::RecvError::Empty,
+            async_channel::TryRecvError::Closed => crate::RecvError::Closed,
+        });
+        self.observer.observe(res).ok()
+    }
+
+    #[inline(always)]
+    /// Sequential blocking read of the next request
+    /// 
+    /// Returns [`None`] if the service is closed
+    pub fn read_blocking(&mut self) -> Option<ServiceRequest<Req, Resp>> {
+        let res = self.buf.recv_blocking().map_err(|_| crate::RecvError::Closed);
+        self.observer.observe(res).ok()
+    }
+
+    /// Asynchronously responds to every request with the given handler,
+    /// until the service is closed
+    /// 
+    /// # Arguments
+    /// 
+    /// * `handler` - the function producing a response for each request
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Doorbell,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Doorbell => String -> bool,
+    /// }
+    /// 
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut node = crosstalk::BoundedNode::<House>::new(10);
+    ///     let mut server = node.service_server::<String, bool>(House::Doorbell).await.unwrap();
+    ///     let client = node.service_client::<String, bool>(House::Doorbell).await.unwrap();
+    ///     tokio::spawn(async move { server.serve(|visitor| visitor == "friend").await; });
+    ///     assert!(client.call("friend".to_string()).await.unwrap());
+    /// }
+    /// ```
+    pub async fn serve<F>(&mut self, mut handler: F)
+    where
+        F: FnMut(Req) -> Resp,
+    {
+        while let Some(ServiceRequest { request, reply, .. }) = self.read().await {
+            let _ = reply.send(handler(request));
+        }
+    }
+
+    /// Sequentially responds to every request with the given handler,
+    /// until the service is closed
+    /// 
+    /// The asynchronous equivalent to this function is [`ServiceServer::serve`]
+    /// 
+    /// # Arguments
+    /// 
+    /// * `handler` - the function producing a response for each request
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Doorbell,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Doorbell => String -> bool,
+    /// }
+    /// 
+    /// let mut node = crosstalk::BoundedNode::<House>::new(10);
+    /// let mut server = node.service_server_blocking::<String, bool>(House::Doorbell).unwrap();
+    /// let client = node.service_client_blocking::<String, bool>(House::Doorbell).unwrap();
+    /// std::thread::spawn(move || server.serve_blocking(|visitor| visitor == "friend"));
+    /// assert!(client.call_blocking("friend".to_string()).unwrap());
+    /// ```
+    pub fn serve_blocking<F>(&mut self, mut handler: F)
+    where
+        F: FnMut(Req) -> Resp,
+    {
+        while let Some(ServiceRequest { request, reply, .. }) = self.read_blocking() {
+            let _ = reply.send(handler(request));
+        }
+    }
+}
+/// [`ServiceServer`] implementation of [`Clone`]
+impl<Req, Resp, T: Clone> Clone for ServiceServer<Req, Resp, T> {
+    #[inline(always)]
+    /// Clones a [`ServiceServer`], which competes with
+    /// the original for requests
+    fn clone(&self) -> Self {
+        Self {
+            topic: self.topic.clone(),
+            buf: self.buf.clone(),
+            observer: self.observer.clone(),
+        }
+    }
+}
+/// [`ServiceServer`] implementation of [`Drop`]
+impl<Req, Resp, T> Drop for ServiceServer<Req, Resp, T> {
+    /// If this is the last server, drops all pending requests so that waiting
+    /// clients are not left hanging
+    /// 
+    /// Servers are counted by their `Observer` rather than by the receivers of the
+    /// channel, as the node no longer holds a receiver once the service is closed,
+    /// and the remaining servers still handle the pending requests then. Draining
+    /// stops if a new server is created in the meantime
+    fn drop(&mut self) {
+        if self.observer.release() {
+            while !self.observer.others() && self.buf.try_recv().is_ok() {}
+        }
+    }
+}
+
+/// A `crosstalk` [`ServiceClient`]
+/// 
+/// # Attributes
+/// 
+/// * `topic` - the topic of the service
+/// * `buf` - the sender of pending requests
+/// * `ids` - the next correlation id of the service
+/// * `servers` - the `Observers` of the service, to stop waiting for
+///   a response once there are no servers left to respond
+/// 
+/// # Type Parameters
+/// 
+/// * `Req` - the request type of the service
+/// * `Resp` - the response type of the service
+/// * `T` - the topic of the service
+/// 
+/// This is not meant to be used directly, please
+/// use the [`crosstalk_macros::init!`] macro instead
+/// and produce a [`ServiceClient`] with [`BoundedNode::service_client`]
+pub struct ServiceClient<Req, Resp, T> {
+    pub topic: T,
+    buf: AsyncSender<ServiceRequest<Req, Resp>>,
+    ids: Arc<AtomicU64>,
+    servers: Arc<Observers>,
+}
+/// [`ServiceClient`] implementation
+impl<Req, Resp, T> ServiceClient<Req, Resp, T> {
+    #[inline(always)]
+    /// See [`BoundedNode::service_client`]
+    pub fn new(topic: T, service: Service<Req, Resp>) -> Self {
+        Self { topic, buf: service.requests, ids: service.ids, servers: service.observers }
+    }
+
+    #[inline(always)]
+    /// The number of [`ServiceServer`]s currently registered for the service
+    pub fn server_count(&self) -> usize {
+        // the node holds one receiver to keep the channel open
+        self.buf.receiver_count().saturating_sub(1)
+    }
+
+    /// Prepares a request with a new correlation id
+    fn request(&self, request: Req) -> Result<(ServiceRequest<Req, Resp>, OneshotReceiver<Resp>), crate::Error> {
+        if self.server_count() == 0 {
+            return Err(crate::Error::NoServer);
+        }
+        let (reply, response) = tokio::sync::oneshot::channel();
+        let id = self.ids.fetch_add(1, Ordering::Relaxed);
+        Ok((ServiceRequest { id, request, reply }, response))
+    }
+
+    /// Waits for the response to a request which has been sent
+    /// 
+    /// The last server can be dropped between the check in [`ServiceClient::request`]
+    /// and the send, leaving the request in the queue with nobody to respond
+    /// to it, so this stops waiting once every server is gone
+    async fn response(&self, mut response: OneshotReceiver<Resp>) -> Result<Resp, crate::Error> {
+        let mut servers = std::pin::pin!(self.servers.wait());
+        std::future::poll_fn(|cx| {
+            // a response is preferred over the servers being gone,
+            // since the last server may respond right before it is dropped
+            if let Poll::Ready(res) = Pin::new(&mut response).poll(cx) {
+                return Poll::Ready(res.map_err(|_| crate::Error::NoResponse));
+            }
+            servers.as_mut().poll(cx).map(|_| Err(crate::Error::NoServer))
+        }).await
+    }
+
+    /// Asynchronously calls the service, waiting for the response
+    /// 
+    /// # Arguments
+    /// 
+    /// * `request` - the request to send to a [`ServiceServer`]
+    /// 
+    /// # Errors
+    /// 
+    /// * [`crate::Error::NoServer`] - if there is no [`ServiceServer`] for the service,
+    ///   or every [`ServiceServer`] was dropped before responding
+    /// * [`crate::Error::NoResponse`] - if the [`ServiceServer`] dropped the request without responding
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Doorbell,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Doorbell => String -> bool,
+    /// }
+    /// 
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut node = crosstalk::BoundedNode::<House>::new(10);
+    ///     let mut server = node.service_server::<String, bool>(House::Doorbell).await.unwrap();
+    ///     let client = node.service_client::<String, bool>(House::Doorbell).await.unwrap();
+    ///     tokio::spawn(async move {
+    ///         while let Some(request) = server.read().await {
+    ///             let answer = request.request == "friend";
+    ///             request.respond(answer);
+    ///         }
+    ///     });
+    ///     assert!(client.call("friend".to_string()).await.unwrap());
+    /// }
+    /// ```
+    pub async fn call(&self, request: Req) -> Result<Resp, crate::Error> {
+        let (request, response) = self.request(request)?;
+        self.buf.send(request).await.map_err(|_| crate::Error::NoServer)?;
+        self.response(response).await
+    }
+
+    /// Asynchronously calls the service, waiting for the
+    /// response for at most the given timeout
+    /// 
+    /// This must be called within a [`tokio`] runtime
+    /// 
+    /// # Arguments
+    /// 
+    /// * `request` - the request to send to a [`ServiceServer`]
+    /// * `timeout` - the maximum time to wait for a response
+    /// 
+    /// # Errors
+    /// 
+    /// * [`crate::Error::NoServer`] - if there is no [`ServiceServer`] for the service
+    /// * [`crate::Error::NoResponse`] - if the [`ServiceServer`] dropped the request without responding
+    /// * [`crate::Error::Timeout`] - if no response was received within the timeout
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Doorbell,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Doorbell => String -> bool,
+    /// }
+    /// 
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut node = crosstalk::BoundedNode::<House>::new(10);
+    ///     let server = node.service_server::<String, bool>(House::Doorbell).await.unwrap();
+    ///     let client = node.service_client::<String, bool>(House::Doorbell).await.unwrap();
+    ///     let timeout = std::time::Duration::from_millis(100);
+    ///     // the server never reads the request
+    ///     let res = client.call_timeout("friend".to_string(), timeout).await;
+    ///     assert!(matches!(res, Err(crosstalk::Error::Timeout)));
+    /// }
+    /// ```
+    pub async fn call_timeout(&self, request: Req, timeout: std::time::Duration) -> Result<Resp, crate::Error> {
+        match tokio::time::timeout(timeout, self.call(request)).await {
+            Ok(res) => res,
+            Err(_) => Err(crate::Error::Timeout),
+        }
+    }
+
+    /// Sequentially calls the service, blocking until the response
+    /// 
+    /// The asynchronous equivalent to this function is [`ServiceClient::call`]
+    /// 
+    /// # Arguments
+    /// 
+    /// * `request` - the request to send to a [`ServiceServer`]
+    /// 
+    /// # Errors
+    /// 
+    /// * [`crate::Error::NoServer`] - if there is no [`ServiceServer`] for the service,
+    ///   or every [`ServiceServer`] was dropped before responding
+    /// * [`crate::Error::NoResponse`] - if the [`ServiceServer`] dropped the request without responding
+    pub fn call_blocking(&self, request: Req) -> Result<Resp, crate::Error> {
+        let (request, response) = self.request(request)?;
+        self.buf.send_blocking(request).map_err(|_| crate::Error::NoServer)?;
+        crate::block_on(self.response(response))
+    }
+}
+/// [`ServiceClient`] implementation of [`Clone`]
+impl<Req, Resp, T: Clone> Clone for ServiceClient<Req, Resp, T> {
+    fn clone(&self) -> Self {
+        Self {
+            topic: self.topic.clone(),
+            buf: self.buf.clone(),
+            ids: self.ids.clone(),
+            servers: self.servers.clone(),
+        }
+    }
+}
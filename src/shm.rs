@@ -0,0 +1,797 @@
+// --------------------------------------------------
+// external
+// --------------------------------------------------
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+use std::marker::PhantomData;
+use std::fs::OpenOptions;
+use std::path::{
+    Path,
+    PathBuf,
+};
+use std::sync::atomic::{
+    AtomicU64,
+    Ordering,
+    fence,
+};
+use bytemuck::Pod;
+use memmap2::{
+    Mmap,
+    MmapMut,
+};
+use tokio::task::JoinHandle;
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use crate::{
+    BoundedNode,
+    CrosstalkTopic,
+    CrosstalkPubSub,
+    ImplementedBoundedNode,
+};
+
+/// Written at the start of every ring, followed by the version of the layout
+const MAGIC: u64 = u64::from_le_bytes(*b"XTLKSHM\x02");
+
+/// The size of the header of a ring, followed by its slots
+const HEADER: usize = 64;
+
+/// The offsets of the fields of the header of a ring
+const MAGIC_AT: usize = 0;
+const SIZE_AT: usize = 8;
+const SLOTS_AT: usize = 16;
+const ALIGN_AT: usize = 24;
+const WRITTEN_AT: usize = 32;
+const CLOSED_AT: usize = 40;
+const TAG_AT: usize = 48;
+
+/// Links topics of a [`BoundedNode`] to the nodes of other processes on the same host
+/// through shared memory, with the `shm` feature
+/// 
+/// A topic is published to a ring of slots in a memory-mapped file with
+/// [`SharedMemory::publish`], and subscribed to from that file with [`SharedMemory::subscribe`],
+/// so that processes keep using the normal [`crate::Publisher`]s and [`crate::Subscriber`]s of
+/// the topic. Samples are plain bytes ([`bytemuck::Pod`]), which are copied straight into the
+/// ring and out of it, without serialization, sockets or system calls.
+/// 
+/// The topic carries the samples as [`Arc`]s, so that samples of several megabytes (e.g.
+/// camera frames) are only ever copied between the ring and the heap, and shared by every
+/// subscriber of the topic rather than cloned or moved by value.
+/// 
+/// Subscribing processes poll the ring, and skip the samples which were overwritten
+/// before they read them (see [`ShmHandle::lagged`]).
+/// 
+/// # Attributes
+/// 
+/// * `node` - the local node
+/// * `slots` - the number of samples a ring holds
+/// * `poll` - the time between polls of a ring with no new samples
+/// * `tag` - identifies the data type of the samples of a ring
+/// * `mode` - the permissions of a ring, on Unix
+/// 
+/// # Examples
+/// 
+/// ```
+/// use std::sync::Arc;
+/// use crosstalk::AsTopic;
+/// use crosstalk::SharedMemory;
+/// 
+/// #[derive(AsTopic)]
+/// enum House {
+///     Camera,
+/// }
+/// 
+/// crosstalk::init! {
+///     House::Camera => Arc<[u8; 4096]>,
+/// }
+/// 
+/// #[tokio::main]
+/// async fn main() {
+///     let path = std::env::temp_dir().join(format!("crosstalk-camera-{}", std::process::id()));
+/// 
+///     // process A
+///     let mut node_a = crosstalk::BoundedNode::<House>::new(10);
+///     let publisher = node_a.publisher::<Arc<[u8; 4096]>>(House::Camera).await.unwrap();
+///     let _shm_a = SharedMemory::new(&node_a)
+///         .publish::<[u8; 4096]>(House::Camera, &path)
+///         .await
+///         .unwrap();
+/// 
+///     // process B
+///     let mut node_b = crosstalk::BoundedNode::<House>::new(10);
+///     let mut subscriber = node_b.subscriber::<Arc<[u8; 4096]>>(House::Camera).await.unwrap();
+///     let _shm_b = SharedMemory::new(&node_b)
+///         .subscribe::<[u8; 4096]>(House::Camera, &path)
+///         .await
+///         .unwrap();
+/// 
+///     publisher.write(Arc::new([7; 4096]));
+///     assert_eq!(subscriber.read().await.as_deref(), Some(&[7; 4096]));
+/// }
+/// ```
+pub struct SharedMemory<T> {
+    node: BoundedNode<T>,
+    slots: usize,
+    poll: Duration,
+    tag: u64,
+    #[cfg(unix)]
+    mode: u32,
+}
+/// [`SharedMemory`] implementation
+impl<T> SharedMemory<T>
+where
+    T: CrosstalkTopic + Send + Sync + 'static,
+    ImplementedBoundedNode<T>: CrosstalkPubSub<T>,
+{
+    #[inline(always)]
+    /// Constructs a new [`SharedMemory`] of a node
+    /// 
+    /// # Arguments
+    /// 
+    /// * `node` - the local node
+    pub fn new(node: &BoundedNode<T>) -> Self {
+        Self {
+            node: BoundedNode {
+                node: node.node.clone(),
+                size: node.size,
+            },
+            slots: 16,
+            poll: Duration::from_millis(1),
+            tag: 0,
+            #[cfg(unix)]
+            mode: 0o600,
+        }
+    }
+
+    #[inline(always)]
+    /// Sets the number of samples a ring created by [`SharedMemory::publish`] holds,
+    /// which defaults to 16
+    /// 
+    /// # Arguments
+    /// 
+    /// * `slots` - the number of samples
+    /// 
+    /// # Panics
+    /// 
+    /// If `slots` is 0
+    pub fn slots(mut self, slots: usize) -> Self {
+        assert!(slots > 0, "a ring needs at least one slot");
+        self.slots = slots;
+        self
+    }
+
+    #[inline(always)]
+    /// Sets the time between polls of a ring subscribed to with
+    /// [`SharedMemory::subscribe`] with no new samples, which defaults to 1ms
+    /// 
+    /// # Arguments
+    /// 
+    /// * `interval` - the time between polls
+    pub fn poll(mut self, interval: Duration) -> Self {
+        self.poll = interval;
+        self
+    }
+
+    #[inline(always)]
+    /// Sets the tag of a ring, which defaults to 0
+    /// 
+    /// A ring is only opened if it has the same tag, and holds samples of the same size
+    /// and alignment. Data types are not compared otherwise, so both ends should e.g. use
+    /// a version of the data type as tag, to tell apart rings of different data types
+    /// 
+    /// # Arguments
+    /// 
+    /// * `tag` - the tag of the ring
+    pub fn tag(mut self, tag: u64) -> Self {
+        self.tag = tag;
+        self
+    }
+
+    #[cfg(unix)]
+    #[inline(always)]
+    /// Sets the permissions of a ring created by [`SharedMemory::publish`], which
+    /// defaults to `0o600` so that only processes of the same user can read it
+    /// 
+    /// # Arguments
+    /// 
+    /// * `mode` - the permissions of the ring
+    pub fn mode(mut self, mode: u32) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Publishes every sample of a topic of [`Arc`]s of `D` to a ring at `path`, until the
+    /// returned [`ShmHandle`] is closed or dropped or the topic is closed, which also removes the file
+    /// 
+    /// The file is replaced if it exists, and should preferably be on a memory-backed
+    /// filesystem such as `/dev/shm`. Access to it is controlled by its permissions,
+    /// see [`SharedMemory::mode`]
+    /// 
+    /// # Arguments
+    /// 
+    /// * `topic` - the topic to publish
+    /// * `path` - the path of the ring
+    /// 
+    /// # Errors
+    /// 
+    /// * if the file can not be created or mapped
+    /// * if the topic can not be subscribed to, wrapping a [`crate::Error`] (e.g. [`crate::Error::SubscriberMismatch`])
+    pub async fn publish<D>(self, topic: T, path: impl AsRef<Path>) -> io::Result<ShmHandle>
+    where
+        D: Pod + Send + Sync,
+    {
+        let mut node = BoundedNode {
+            node: self.node.node.clone(),
+            size: self.node.size,
+        };
+        let mut subscriber = node.subscriber::<Arc<D>>(topic).await.map_err(io::Error::other)?;
+        let writer = self.writer::<D>(path)?;
+        let ring = writer.ring.clone();
+        let task = tokio::spawn(async move {
+            while let Some(sample) = subscriber.read().await {
+                writer.write(&sample);
+            }
+        });
+        Ok(ShmHandle {
+            ring,
+            lagged: Arc::new(AtomicU64::new(0)),
+            task,
+        })
+    }
+
+    /// Publishes the samples written to a ring at `path` by another process (see
+    /// [`SharedMemory::publish`]) to a topic of [`Arc`]s of `D`, until the returned
+    /// [`ShmHandle`] is closed or dropped, or the ring is closed
+    /// 
+    /// Each sample is copied from the ring into an [`Arc`] on the heap, which is reused
+    /// for the next sample once every subscriber of the topic dropped it.
+    /// Only samples written after subscribing are published. Once the ring is closed, the
+    /// publisher of the topic is dropped, which closes the topic if it was the last one
+    /// 
+    /// # Arguments
+    /// 
+    /// * `topic` - the topic to publish the samples to
+    /// * `path` - the path of the ring
+    /// 
+    /// # Errors
+    /// 
+    /// * if the file can not be opened or mapped
+    /// * if the file is not a ring with the same tag, of samples of the same size and alignment
+    /// * if the topic can not be published to, wrapping a [`crate::Error`] (e.g. [`crate::Error::PublisherMismatch`])
+    pub async fn subscribe<D>(self, topic: T, path: impl AsRef<Path>) -> io::Result<ShmHandle>
+    where
+        D: Pod + Send + Sync,
+    {
+        let mut node = BoundedNode {
+            node: self.node.node.clone(),
+            size: self.node.size,
+        };
+        let mut reader = self.reader::<D>(path)?;
+        let publisher = node.publisher::<Arc<D>>(topic).await.map_err(io::Error::other)?;
+        let ring = reader.ring.clone();
+        let lagged = reader.lagged.clone();
+        let task = tokio::spawn(async move {
+            // samples written before the ring was closed are read first
+            while reader.read().await.is_some() {
+                publisher.write(reader.sample.clone());
+            }
+        });
+        Ok(ShmHandle {
+            ring,
+            lagged,
+            task,
+        })
+    }
+
+    /// Creates a ring at `path` to write samples to directly, without a topic,
+    /// until the returned [`ShmWriter`] is closed or dropped, which also removes the file
+    /// 
+    /// See [`SharedMemory::publish`] for the file
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - the path of the ring
+    /// 
+    /// # Errors
+    /// 
+    /// If the file can not be created or mapped
+    pub fn writer<D: Pod>(&self, path: impl AsRef<Path>) -> io::Result<ShmWriter<D>> {
+        let ring = Ring::create(
+            path.as_ref(),
+            Layout::of::<D>(self.tag),
+            self.slots,
+            #[cfg(unix)]
+            self.mode,
+        )?;
+        Ok(ShmWriter {
+            ring: Arc::new(ring),
+            data: PhantomData,
+        })
+    }
+
+    /// Opens a ring at `path` created by another process to read samples from directly,
+    /// without a topic
+    /// 
+    /// Only samples written after opening the ring are read
+    /// 
+    /// # Arguments
+    /// 
+    /// * `path` - the path of the ring
+    /// 
+    /// # Errors
+    /// 
+    /// * if the file can not be opened or mapped
+    /// * if the file is not a ring with the same tag, of samples of the same size and alignment
+    pub fn reader<D: Pod>(&self, path: impl AsRef<Path>) -> io::Result<ShmReader<D>> {
+        let ring = Ring::open(path.as_ref(), Layout::of::<D>(self.tag))?;
+        let next = ring.written();
+        Ok(ShmReader {
+            ring: Arc::new(ring),
+            next,
+            poll: self.poll,
+            lagged: Arc::new(AtomicU64::new(0)),
+            sample: zeroed(),
+        })
+    }
+}
+
+/// Closes a link of a [`SharedMemory`] when dropped, see
+/// [`SharedMemory::publish`] and [`SharedMemory::subscribe`]
+/// 
+/// # Attributes
+/// 
+/// * `ring` - the ring, which is closed and removed if it was created by this link
+/// * `lagged` - the samples overwritten before they were read
+/// * `task` - the task writing to or reading from the ring
+pub struct ShmHandle {
+    ring: Arc<Ring>,
+    lagged: Arc<AtomicU64>,
+    task: JoinHandle<()>,
+}
+/// [`ShmHandle`] implementation
+impl ShmHandle {
+    #[inline(always)]
+    /// The path of the ring
+    pub fn path(&self) -> &Path {
+        &self.ring.path
+    }
+
+    #[inline(always)]
+    /// The number of samples written to the ring so far
+    pub fn written(&self) -> u64 {
+        self.ring.written()
+    }
+
+    #[inline(always)]
+    /// The number of samples which were overwritten before they could be read,
+    /// and were therefore skipped. Always `0` for [`SharedMemory::publish`]
+    pub fn lagged(&self) -> u64 {
+        self.lagged.load(Ordering::Relaxed)
+    }
+
+    #[inline(always)]
+    /// Closes the link
+    /// 
+    /// Equivalent to dropping the handle
+    pub fn close(self) {}
+}
+/// [`ShmHandle`] implementation of [`Drop`]
+impl Drop for ShmHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+        self.ring.remove();
+    }
+}
+
+/// Writes samples to a ring created with [`SharedMemory::writer`],
+/// which is closed and removed when dropped
+/// 
+/// # Attributes
+/// 
+/// * `ring` - the ring
+/// * `data` - the data type of the samples
+pub struct ShmWriter<D> {
+    ring: Arc<Ring>,
+    data: PhantomData<fn(&D)>,
+}
+/// [`ShmWriter`] implementation
+impl<D: Pod> ShmWriter<D> {
+    #[inline(always)]
+    /// The path of the ring
+    pub fn path(&self) -> &Path {
+        &self.ring.path
+    }
+
+    #[inline(always)]
+    /// The number of samples written to the ring so far
+    pub fn written(&self) -> u64 {
+        self.ring.written()
+    }
+
+    #[inline(always)]
+    /// Writes a sample to the ring, overwriting the oldest one
+    /// 
+    /// # Arguments
+    /// 
+    /// * `sample` - the sample to write
+    pub fn write(&self, sample: &D) {
+        self.ring.write(bytemuck::bytes_of(sample));
+    }
+
+    #[inline(always)]
+    /// Closes the ring
+    /// 
+    /// Equivalent to dropping the writer
+    pub fn close(self) {}
+}
+/// [`ShmWriter`] implementation of [`Drop`]
+impl<D> Drop for ShmWriter<D> {
+    fn drop(&mut self) {
+        self.ring.remove();
+    }
+}
+
+/// Reads samples from a ring opened with [`SharedMemory::reader`]
+/// 
+/// Every sample is copied from the ring into a buffer on the heap,
+/// which is borrowed until the next sample is read
+/// 
+/// # Attributes
+/// 
+/// * `ring` - the ring
+/// * `next` - the number of the next sample to read
+/// * `poll` - the time between polls of the ring with no new samples
+/// * `lagged` - the samples overwritten before they were read
+/// * `sample` - the last sample read
+pub struct ShmReader<D> {
+    ring: Arc<Ring>,
+    next: u64,
+    poll: Duration,
+    lagged: Arc<AtomicU64>,
+    sample: Arc<D>,
+}
+/// [`ShmReader`] implementation
+impl<D: Pod> ShmReader<D> {
+    #[inline(always)]
+    /// The path of the ring
+    pub fn path(&self) -> &Path {
+        &self.ring.path
+    }
+
+    #[inline(always)]
+    /// The number of samples which were overwritten before they could be read,
+    /// and were therefore skipped
+    pub fn lagged(&self) -> u64 {
+        self.lagged.load(Ordering::Relaxed)
+    }
+
+    /// Reads the next sample, skipping the samples which were overwritten
+    /// 
+    /// Returns [`None`] once the ring is closed and every sample written before was read
+    pub async fn read(&mut self) -> Option<&D> {
+        loop {
+            if self.advance() {
+                return Some(&self.sample);
+            }
+            if self.ring.closed() && self.next >= self.ring.written() {
+                return None;
+            }
+            tokio::time::sleep(self.poll).await;
+        }
+    }
+
+    /// Reads the next sample without waiting, skipping the samples which were overwritten
+    /// 
+    /// Returns [`None`] if no new sample was written
+    pub fn try_read(&mut self) -> Option<&D> {
+        match self.advance() {
+            true => Some(&self.sample),
+            false => None,
+        }
+    }
+
+    /// Copies the next sample into the buffer, returning whether there was one
+    /// 
+    /// The buffer is replaced if it is still shared, see [`SharedMemory::subscribe`]
+    fn advance(&mut self) -> bool {
+        if self.next >= self.ring.written() {
+            return false;
+        }
+        if Arc::get_mut(&mut self.sample).is_none() {
+            self.sample = zeroed();
+        }
+        let Some(sample) = Arc::get_mut(&mut self.sample) else {
+            unreachable!("the buffer was just allocated")
+        };
+        loop {
+            match self.ring.read(&mut self.next, bytemuck::bytes_of_mut(sample)) {
+                Some(Ok(())) => return true,
+                Some(Err(n)) => { self.lagged.fetch_add(n, Ordering::Relaxed); },
+                None => return false,
+            }
+        }
+    }
+}
+
+#[inline(always)]
+/// Allocates a zeroed sample on the heap, without it ever being on the stack
+fn zeroed<D: Pod>() -> Arc<D> {
+    // safety: every bit pattern, including zeroes, is a valid `D`
+    unsafe { Arc::<D>::new_zeroed().assume_init() }
+}
+
+/// The layout of the samples of a [`Ring`], which both ends must agree on
+/// 
+/// # Attributes
+/// 
+/// * `size` - the size of a sample
+/// * `align` - the alignment of a sample
+/// * `tag` - see [`SharedMemory::tag`]
+#[derive(Clone, Copy)]
+struct Layout {
+    size: usize,
+    align: usize,
+    tag: u64,
+}
+/// [`Layout`] implementation
+impl Layout {
+    #[inline(always)]
+    /// The layout of samples of `D`
+    fn of<D>(tag: u64) -> Self {
+        Self {
+            size: size_of::<D>(),
+            align: align_of::<D>(),
+            tag,
+        }
+    }
+}
+
+/// The mapping of a [`Ring`], which is only writable by the process which created it
+/// 
+/// The writer keeps the device and inode of the file on Unix, so that it only removes
+/// the file while it is still the ring (see [`Ring::remove`])
+enum Map {
+    #[cfg(unix)]
+    Writer(MmapMut, (u64, u64)),
+    #[cfg(not(unix))]
+    Writer(MmapMut),
+    Reader(Mmap),
+}
+
+/// A ring of samples in a memory-mapped file
+/// 
+/// The ring starts with a header of [`HEADER`] bytes, holding (as native-endian `u64`s)
+/// [`MAGIC`], the size of a sample, the number of slots, the alignment of a sample, the
+/// number of samples written, whether the ring is closed and its tag. Each slot then holds
+/// a sequence number followed by a sample, where the sequence is odd while sample `n` is
+/// being written (`2n + 1`) and even once it is written (`2n + 2`), so that readers can
+/// detect samples which were overwritten while they read them
+/// 
+/// Every field and sample is accessed a `u64` at a time through atomics, as the writer may
+/// overwrite a slot while it is being read. Readers only ever load from their read-only
+/// mapping, which is allowed for atomics of up to 16 bytes
+/// 
+/// # Attributes
+/// 
+/// * `path` - the path of the ring
+/// * `size` - the size of a sample
+/// * `slots` - the number of slots
+/// * `map` - the mapping, unmapped when dropped
+struct Ring {
+    path: PathBuf,
+    size: usize,
+    slots: u64,
+    map: Map,
+}
+/// [`Ring`] implementation
+impl Ring {
+    #[inline(always)]
+    /// The size of a slot, rounded up to keep every sequence number aligned
+    fn stride(size: usize) -> usize {
+        8 + size.next_multiple_of(8)
+    }
+
+    /// Creates a ring, replacing any file at `path`
+    /// 
+    /// The ring is created next to `path` with its permissions, and only renamed to `path`
+    /// once its header is written, so that processes which still map a replaced ring keep
+    /// reading from their own file
+    fn create(path: &Path, layout: Layout, slots: usize, #[cfg(unix)] mode: u32) -> io::Result<Self> {
+        static STAGING: AtomicU64 = AtomicU64::new(0);
+        let name = path.file_name().ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "the ring path has no file name"))?;
+        let staging = path.with_file_name(format!(
+            ".{}.{}.{}",
+            name.to_string_lossy(),
+            std::process::id(),
+            STAGING.fetch_add(1, Ordering::Relaxed),
+        ));
+        let mut options = OpenOptions::new();
+        options.read(true).write(true).create_new(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, mode);
+        let file = options.open(&staging)?;
+        let created = (|| {
+            #[cfg(unix)]
+            let inode = {
+                use std::os::unix::fs::{MetadataExt, PermissionsExt};
+                // the mode of a new file is restricted by the umask
+                file.set_permissions(std::fs::Permissions::from_mode(mode))?;
+                let metadata = file.metadata()?;
+                (metadata.dev(), metadata.ino())
+            };
+            file.set_len((HEADER + slots * Self::stride(layout.size)) as u64)?;
+            // safety: the file was just created by this process,
+            // and is only ever mapped as a ring by other processes
+            let map = unsafe { MmapMut::map_mut(&file)? };
+            let ring = Self {
+                path: path.to_path_buf(),
+                size: layout.size,
+                slots: slots as u64,
+                #[cfg(unix)]
+                map: Map::Writer(map, inode),
+                #[cfg(not(unix))]
+                map: Map::Writer(map),
+            };
+            ring.field(SIZE_AT).store(layout.size as u64, Ordering::Relaxed);
+            ring.field(SLOTS_AT).store(slots as u64, Ordering::Relaxed);
+            ring.field(ALIGN_AT).store(layout.align as u64, Ordering::Relaxed);
+            ring.field(TAG_AT).store(layout.tag, Ordering::Relaxed);
+            ring.field(MAGIC_AT).store(MAGIC, Ordering::Release);
+            std::fs::rename(&staging, path)?;
+            Ok(ring)
+        })();
+        if created.is_err() {
+            let _ = std::fs::remove_file(&staging);
+        }
+        created
+    }
+
+    /// Opens a ring created by another process, checking that
+    /// it holds samples of the expected layout
+    fn open(path: &Path, layout: Layout) -> io::Result<Self> {
+        let invalid = |msg| io::Error::new(io::ErrorKind::InvalidData, msg);
+        let file = std::fs::File::open(path)?;
+        // safety: the mapping is only ever loaded from through atomics, see [`Ring`]
+        let map = unsafe { Mmap::map(&file)? };
+        if map.len() < HEADER {
+            return Err(invalid("not a crosstalk ring"));
+        }
+        let len = map.len();
+        let mut ring = Self {
+            path: path.to_path_buf(),
+            size: layout.size,
+            slots: 0,
+            map: Map::Reader(map),
+        };
+        if ring.field(MAGIC_AT).load(Ordering::Acquire) != MAGIC {
+            return Err(invalid("not a crosstalk ring"));
+        }
+        if ring.field(SIZE_AT).load(Ordering::Relaxed) != layout.size as u64
+        || ring.field(ALIGN_AT).load(Ordering::Relaxed) != layout.align as u64 {
+            return Err(invalid("the ring holds samples of another size or alignment"));
+        }
+        if ring.field(TAG_AT).load(Ordering::Relaxed) != layout.tag {
+            return Err(invalid("the ring has another tag"));
+        }
+        ring.slots = ring.field(SLOTS_AT).load(Ordering::Relaxed);
+        if ring.slots == 0 || (len as u64) < HEADER as u64 + ring.slots * Self::stride(layout.size) as u64 {
+            return Err(invalid("the ring is truncated"));
+        }
+        Ok(ring)
+    }
+
+    #[inline(always)]
+    /// A `u64` of the ring, at `offset` bytes from its start
+    fn field(&self, offset: usize) -> &AtomicU64 {
+        // only the writer ever stores through the pointer
+        let base = match &self.map {
+            #[cfg(unix)]
+            Map::Writer(map, _) => map.as_ptr(),
+            #[cfg(not(unix))]
+            Map::Writer(map) => map.as_ptr(),
+            Map::Reader(map) => map.as_ptr(),
+        };
+        // safety: every `u64` is within the mapping and 8-byte aligned, as the mapping
+        // is page-aligned, and readers only load from it (see [`Ring`])
+        unsafe { AtomicU64::from_ptr(base.add(offset) as *mut u64) }
+    }
+
+    #[inline(always)]
+    /// The offset of the slot of sample `n`
+    fn slot(&self, n: u64) -> usize {
+        HEADER + (n % self.slots) as usize * Self::stride(self.size)
+    }
+
+    #[inline(always)]
+    /// The number of samples written to the ring
+    fn written(&self) -> u64 {
+        self.field(WRITTEN_AT).load(Ordering::Acquire)
+    }
+
+    #[inline(always)]
+    /// Whether the ring was closed by its writer
+    fn closed(&self) -> bool {
+        self.field(CLOSED_AT).load(Ordering::Acquire) != 0
+    }
+
+    /// Closes and removes the ring if it was created by this process,
+    /// unless `path` was replaced by another ring since
+    fn remove(&self) {
+        match &self.map {
+            Map::Reader(_) => return,
+            #[cfg(unix)]
+            Map::Writer(_, inode) => {
+                use std::os::unix::fs::MetadataExt;
+                self.field(CLOSED_AT).store(1, Ordering::Release);
+                match std::fs::metadata(&self.path) {
+                    Ok(metadata) if (metadata.dev(), metadata.ino()) == *inode => {},
+                    _ => return,
+                }
+            },
+            #[cfg(not(unix))]
+            Map::Writer(_) => self.field(CLOSED_AT).store(1, Ordering::Release),
+        }
+        let _ = std::fs::remove_file(&self.path);
+    }
+
+    /// Writes the next sample, overwriting the oldest one
+    /// 
+    /// Only called by the single writer of the ring
+    fn write(&self, data: &[u8]) {
+        debug_assert_eq!(data.len(), self.size);
+        let n = self.field(WRITTEN_AT).load(Ordering::Relaxed);
+        let slot = self.slot(n);
+        let seq = self.field(slot);
+        seq.store(2 * n + 1, Ordering::Relaxed);
+        fence(Ordering::Release);
+        for (i, chunk) in data.chunks(8).enumerate() {
+            let mut word = [0; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            self.field(slot + 8 + 8 * i).store(u64::from_ne_bytes(word), Ordering::Relaxed);
+        }
+        seq.store(2 * n + 2, Ordering::Release);
+        self.field(WRITTEN_AT).store(n + 1, Ordering::Release);
+    }
+
+    /// Reads sample `next` into `data`, advancing `next` past it
+    /// 
+    /// Returns [`None`] if the sample was not written yet, or the number of samples
+    /// skipped if the sample was overwritten before it could be read
+    fn read(&self, next: &mut u64, data: &mut [u8]) -> Option<Result<(), u64>> {
+        debug_assert_eq!(data.len(), self.size);
+        let written = self.written();
+        if *next >= written {
+            return None;
+        }
+        if written - *next > self.slots {
+            return Some(Err(self.skip(next, written)));
+        }
+        let slot = self.slot(*next);
+        let seq = self.field(slot);
+        let before = seq.load(Ordering::Acquire);
+        for (i, chunk) in data.chunks_mut(8).enumerate() {
+            let word = self.field(slot + 8 + 8 * i).load(Ordering::Relaxed).to_ne_bytes();
+            chunk.copy_from_slice(&word[..chunk.len()]);
+        }
+        fence(Ordering::Acquire);
+        if before == 2 * *next + 2 && seq.load(Ordering::Relaxed) == before {
+            *next += 1;
+            Some(Ok(()))
+        } else {
+            // the writer lapped the reader while it was reading
+            Some(Err(self.skip(next, self.written())))
+        }
+    }
+
+    #[inline(always)]
+    /// Skips `next` to the oldest sample still in the ring,
+    /// returning the number of samples skipped
+    fn skip(&self, next: &mut u64, written: u64) -> u64 {
+        let oldest = written.saturating_sub(self.slots).max(*next + 1);
+        let skipped = oldest - *next;
+        *next = oldest;
+        skipped
+    }
+}
@@ -0,0 +1,196 @@
+// --------------------------------------------------
+// external
+// --------------------------------------------------
+use std::sync::Arc;
+use std::pin::Pin;
+use std::future::{
+    Future,
+    IntoFuture,
+};
+use std::sync::atomic::{
+    AtomicUsize,
+    Ordering,
+};
+use tokio::sync::Notify;
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use crate::RecvError;
+
+/// The subscribers of a topic which have not yet observed its close
+/// 
+/// # Attributes
+/// 
+/// * `pending` - the number of subscribers which have not yet observed the close
+/// * `notify` - notified once every subscriber has observed the close
+#[derive(Default)]
+pub(crate) struct Observers {
+    pending: AtomicUsize,
+    notify: Notify,
+}
+/// [`Observers`] implementation
+impl Observers {
+    #[inline(always)]
+    /// Registers a new subscriber, which observes the
+    /// close of the topic through the returned [`Observer`]
+    pub(crate) fn observer(self: &Arc<Self>) -> Observer {
+        self.pending.fetch_add(1, Ordering::AcqRel);
+        Observer { observers: self.clone(), observed: false }
+    }
+
+    #[inline(always)]
+    /// Marks a subscriber as having observed the close,
+    /// returning whether it was the last one to
+    fn done(&self) -> bool {
+        let last = self.pending.fetch_sub(1, Ordering::AcqRel) == 1;
+        if last {
+            self.notify.notify_waiters();
+        }
+        last
+    }
+
+    #[inline(always)]
+    /// The number of subscribers which have not yet observed the close
+    fn pending(&self) -> usize {
+        self.pending.load(Ordering::Acquire)
+    }
+
+    /// Waits until every subscriber has observed the close
+    pub(crate) async fn wait(&self) {
+        loop {
+            // the notification is enabled before checking, so
+            // that it is not missed if it happens in between
+            let notified = self.notify.notified();
+            let mut notified = std::pin::pin!(notified);
+            notified.as_mut().enable();
+            if self.pending.load(Ordering::Acquire) == 0 {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// The handle of a [`crate::Subscriber`] on the [`Observers`] of its topic
+/// 
+/// Dropping the subscriber counts as observing the close
+/// 
+/// # Attributes
+/// 
+/// * `observers` - the [`Observers`] of the topic
+/// * `observed` - whether the subscriber has observed the close
+pub(crate) struct Observer {
+    observers: Arc<Observers>,
+    observed: bool,
+}
+/// [`Observer`] implementation
+impl Observer {
+    #[inline(always)]
+    /// Observes the result of a read, passing it through
+    pub(crate) fn observe<D>(&mut self, res: Result<D, RecvError>) -> Result<D, RecvError> {
+        if !self.observed && matches!(res, Err(RecvError::Closed)) {
+            self.observed = true;
+            self.observers.done();
+        }
+        res
+    }
+
+    #[cfg(feature = "queue")]
+    #[inline(always)]
+    /// Unregisters the subscriber ahead of its drop, returning whether it was the
+    /// last one which had not observed the close. Exactly one of several subscribers
+    /// dropped at the same time is the last one
+    pub(crate) fn release(&mut self) -> bool {
+        !std::mem::replace(&mut self.observed, true) && self.observers.done()
+    }
+
+    #[cfg(feature = "queue")]
+    #[inline(always)]
+    /// Whether any other subscriber has not yet observed the close,
+    /// such as one registered after this one was released
+    pub(crate) fn others(&self) -> bool {
+        self.observers.pending() > 0
+    }
+}
+/// [`Observer`] implementation of [`Clone`]
+impl Clone for Observer {
+    #[inline(always)]
+    /// Registers another subscriber on the same [`Observers`]
+    fn clone(&self) -> Self {
+        self.observers.observer()
+    }
+}
+/// [`Observer`] implementation of [`Drop`]
+impl Drop for Observer {
+    fn drop(&mut self) {
+        if !self.observed {
+            self.observers.done();
+        }
+    }
+}
+
+#[derive(Default)]
+/// The shutdown of a node, returned by [`crate::BoundedNode::shutdown`]
+/// 
+/// Awaiting a [`Shutdown`] resolves once every subscriber of the node
+/// has observed the close of its topic, i.e. it has read every sample
+/// still buffered and then [`RecvError::Closed`], or it has been dropped.
+/// Likewise, every service server must have read every pending request
+/// and then [`None`], or have been dropped
+/// 
+/// # Attributes
+/// 
+/// * `observers` - the `Observers` of every closed topic
+pub struct Shutdown {
+    observers: Vec<Arc<Observers>>,
+}
+/// [`Shutdown`] implementation
+impl Shutdown {
+    #[inline(always)]
+    /// Constructs a new [`Shutdown`] of the given [`Observers`]
+    pub(crate) fn new(observers: Arc<Observers>) -> Self {
+        Self { observers: vec![observers] }
+    }
+
+    #[inline(always)]
+    /// Adds the [`Observers`] of another [`Shutdown`]
+    pub(crate) fn merge(mut self, other: Shutdown) -> Self {
+        self.observers.extend(other.observers);
+        self
+    }
+
+    #[inline(always)]
+    /// The number of subscribers which have not yet observed the close
+    pub fn pending(&self) -> usize {
+        self.observers.iter().map(|o| o.pending()).sum()
+    }
+
+    /// Asynchronously waits until every subscriber has observed the close
+    /// 
+    /// This is the same as awaiting the [`Shutdown`]
+    pub async fn wait(self) {
+        for observers in self.observers {
+            observers.wait().await;
+        }
+    }
+
+    /// Blocks until every subscriber has observed the close
+    /// 
+    /// The asynchronous equivalent to this function is [`Shutdown::wait`]
+    /// 
+    /// Like [`crate::Subscriber::read_blocking`], this must not be called
+    /// within an asynchronous context
+    pub fn wait_blocking(self) {
+        crate::block_on(self.wait())
+    }
+}
+/// [`Shutdown`] implementation of [`IntoFuture`]
+impl IntoFuture for Shutdown {
+    type Output = ();
+    type IntoFuture = Pin<Box<dyn Future<Output = ()> + Send>>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(self.wait())
+    }
+}
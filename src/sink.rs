@@ -0,0 +1,140 @@
+// --------------------------------------------------
+// external
+// --------------------------------------------------
+use std::pin::Pin;
+use std::task::{
+    Poll,
+    Context,
+};
+use futures_sink::Sink;
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use crate::Publisher;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+/// How a [`Publisher`] used as a [`Sink`] handles
+/// samples when there are no subscribers
+/// 
+/// Set with [`Publisher::set_sink_policy`]
+pub enum SinkPolicy {
+    #[default]
+    /// Drop the sample, like [`Publisher::write`]
+    Drop,
+    /// Drop the sample and fail with [`crate::Error::NoSubscribers`],
+    /// like [`Publisher::try_write`]
+    Error,
+}
+
+/// [`Publisher`] implementation of sinks
+impl<D, T> Publisher<D, T> {
+    #[inline(always)]
+    /// The [`SinkPolicy`] of the publisher
+    pub fn sink_policy(&self) -> SinkPolicy {
+        self.sink
+    }
+
+    #[inline(always)]
+    /// Sets how the publisher handles samples sent through
+    /// [`Sink`] when there are no subscribers
+    /// 
+    /// Clones of the publisher inherit its [`SinkPolicy`]
+    /// 
+    /// # Arguments
+    /// 
+    /// * `policy` - the [`SinkPolicy`] of the publisher
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// use futures::SinkExt;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bathroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bathroom => u8,
+    /// }
+    /// 
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut node = crosstalk::BoundedNode::<House>::new(10);
+    ///     let mut publisher = node.publisher::<u8>(House::Bathroom).await.unwrap();
+    ///     assert!(publisher.send(0u8).await.is_ok());
+    /// 
+    ///     publisher.set_sink_policy(crosstalk::SinkPolicy::Error);
+    ///     let res = publisher.send(0u8).await;
+    ///     assert!(matches!(res, Err(crosstalk::Error::NoSubscribers)));
+    /// }
+    /// ```
+    pub fn set_sink_policy(&mut self, policy: SinkPolicy) {
+        self.sink = policy;
+    }
+}
+
+/// [`Publisher`] implementation of [`Unpin`]
+/// 
+/// The `topic` is held by value, so the [`Publisher`] would otherwise only be
+/// [`Unpin`] for topics which are. The [`Publisher`] never pins its fields
+/// (the channel of the topic is behind an [`std::sync::Arc`]), so it can be moved freely
+impl<D, T> Unpin for Publisher<D, T> {}
+
+/// [`Publisher`] implementation of [`Sink`]
+/// 
+/// Publishing never waits, so the [`Publisher`] is always ready
+/// and there is nothing to flush
+/// 
+/// # Examples
+/// 
+/// ```
+/// use crosstalk::AsTopic;
+/// use futures::StreamExt;
+/// 
+/// #[derive(AsTopic)]
+/// enum House {
+///     Bathroom,
+/// }
+/// 
+/// crosstalk::init! {
+///     House::Bathroom => u8,
+/// }
+/// 
+/// #[tokio::main]
+/// async fn main() {
+///     let mut node = crosstalk::BoundedNode::<House>::new(10);
+///     let (publisher, mut subscriber) = node.pubsub(House::Bathroom).await.unwrap();
+///     futures::stream::iter((0..3u8).map(Ok)).forward(publisher).await.unwrap();
+///     assert_eq!(subscriber.read().await, Some(0));
+///     assert_eq!(subscriber.read().await, Some(1));
+///     assert_eq!(subscriber.read().await, Some(2));
+/// }
+/// ```
+impl<D, T> Sink<D> for Publisher<D, T> {
+    type Error = crate::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, sample: D) -> Result<(), Self::Error> {
+        match self.sink {
+            SinkPolicy::Drop => {
+                self.write(sample);
+                Ok(())
+            },
+            SinkPolicy::Error => self.try_write(sample).map(|_| ()).map_err(crate::Error::from),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
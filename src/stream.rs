@@ -0,0 +1,211 @@
+// --------------------------------------------------
+// external
+// --------------------------------------------------
+use std::pin::Pin;
+use std::sync::atomic::{
+    Ordering,
+    AtomicBool,
+};
+use std::task::{
+    Poll,
+    Waker,
+    Context,
+};
+use futures_core::Stream;
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use crate::{
+    RecvError,
+    Subscriber,
+};
+
+#[derive(Default)]
+/// The tasks polling the [`Subscriber`]s of a topic as [`Stream`]s,
+/// waiting for the next sample or for the topic to close
+/// 
+/// # Attributes
+/// 
+/// * `waiting` - whether any task is waiting, so that writes only
+///   lock the wakers while a stream is waiting for them
+/// * `wakers` - the wakers of the waiting tasks
+pub(crate) struct Wakers {
+    waiting: AtomicBool,
+    wakers: std::sync::Mutex<Vec<Waker>>,
+}
+/// [`Wakers`] implementation
+impl Wakers {
+    #[inline(always)]
+    /// Registers a task to be woken by the next
+    /// sample published to the topic, or its close
+    fn register(&self, waker: &Waker) {
+        let mut wakers = self.wakers.lock().unwrap_or_else(|e| e.into_inner());
+        if !wakers.iter().any(|w| w.will_wake(waker)) {
+            wakers.push(waker.clone());
+        }
+        self.waiting.store(true, Ordering::SeqCst);
+    }
+
+    #[inline(always)]
+    /// Wakes every waiting task, after a sample
+    /// was published or the topic was closed
+    pub(crate) fn wake(&self) {
+        if !self.waiting.load(Ordering::SeqCst) {
+            return;
+        }
+        let wakers = {
+            let mut wakers = self.wakers.lock().unwrap_or_else(|e| e.into_inner());
+            self.waiting.store(false, Ordering::SeqCst);
+            std::mem::take(&mut *wakers)
+        };
+        wakers.into_iter().for_each(Waker::wake);
+    }
+}
+
+/// [`Subscriber`] implementation of streams
+impl<D, T> Subscriber<D, T> {
+    #[inline(always)]
+    /// Converts the [`Subscriber`] into a [`Stream`] of samples
+    /// 
+    /// Unlike the [`Subscriber`] itself, which yields [`Result`]s, the stream yields
+    /// the samples alone, and ends when the topic is closed. Lost samples are skipped,
+    /// and added to [`Subscriber::lagged_total`] following the [`crate::LagPolicy`]
+    /// of the subscriber, including under [`crate::LagPolicy::Error`]
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// use futures::StreamExt;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bathroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bathroom => u8,
+    /// }
+    /// 
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let mut node = crosstalk::BoundedNode::<House>::new(10);
+    ///     let (publisher, subscriber) = node.pubsub(House::Bathroom).await.unwrap();
+    ///     for i in 0..3u8 {
+    ///         publisher.write(i);
+    ///     }
+    ///     drop(publisher);
+    ///     let samples = subscriber.into_stream().collect::<Vec<_>>().await;
+    ///     assert_eq!(samples, vec![0, 1, 2]);
+    /// }
+    /// ```
+    pub fn into_stream(self) -> SubscriberStream<D, T> {
+        SubscriberStream { subscriber: self }
+    }
+}
+
+/// A [`Stream`] of the samples of a [`Subscriber`]
+/// 
+/// Produced with [`Subscriber::into_stream`]
+/// 
+/// # Attributes
+/// 
+/// * `subscriber` - the subscriber the samples are read from
+pub struct SubscriberStream<D, T> {
+    subscriber: Subscriber<D, T>,
+}
+/// [`SubscriberStream`] implementation
+impl<D, T> SubscriberStream<D, T> {
+    #[inline(always)]
+    /// Converts the stream back into its [`Subscriber`], e.g.
+    /// to check on [`Subscriber::lagged_total`]
+    pub fn into_inner(self) -> Subscriber<D, T> {
+        self.subscriber
+    }
+}
+/// [`SubscriberStream`] implementation of [`Stream`]
+impl<D: Clone, T: Clone> Stream for SubscriberStream<D, T> {
+    type Item = D;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<D>> {
+        let subscriber = &mut self.get_mut().subscriber;
+        loop {
+            match std::task::ready!(Pin::new(&mut *subscriber).poll_next(cx)) {
+                Some(Ok(sample)) => return Poll::Ready(Some(sample)),
+                // the lost samples were already counted by the subscriber
+                Some(Err(_)) => continue,
+                None => return Poll::Ready(None),
+            }
+        }
+    }
+}
+
+/// [`Subscriber`] implementation of [`Unpin`]
+/// 
+/// The `topic` is held by value, so the [`Subscriber`] would otherwise only be
+/// [`Unpin`] for topics which are. The [`Subscriber`] never pins its fields,
+/// so it can be moved freely
+impl<D, T> Unpin for Subscriber<D, T> {}
+
+/// [`Subscriber`] implementation of [`Stream`]
+/// 
+/// The stream yields the same samples as [`Subscriber::read`], following
+/// the [`crate::LagPolicy`] of the subscriber, and ends when the topic is closed.
+/// Under [`crate::LagPolicy::Error`], lost samples are yielded as
+/// [`RecvError::Lagged`], after which the stream continues from the oldest
+/// sample still in the buffer. Missed deadlines are counted (see
+/// [`Subscriber::deadlines_missed`]) when the stream is polled, but not yielded
+/// 
+/// # Examples
+/// 
+/// ```
+/// use crosstalk::AsTopic;
+/// use futures::StreamExt;
+/// 
+/// #[derive(AsTopic)]
+/// enum House {
+///     Bathroom,
+/// }
+/// 
+/// crosstalk::init! {
+///     House::Bathroom => u8,
+/// }
+/// 
+/// #[tokio::main]
+/// async fn main() {
+///     let mut node = crosstalk::BoundedNode::<House>::new(10);
+///     let (publisher, subscriber) = node.pubsub(House::Bathroom).await.unwrap();
+///     for i in 0..3u8 {
+///         publisher.write(i);
+///     }
+///     drop(publisher);
+///     let samples = subscriber.collect::<Vec<_>>().await;
+///     assert_eq!(samples, vec![Ok(0), Ok(1), Ok(2)]);
+/// }
+/// ```
+impl<D: Clone, T: Clone> Stream for Subscriber<D, T> {
+    type Item = Result<D, RecvError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let mut registered = false;
+        loop {
+            let res = this.rcvr.try_read(&mut this.lag);
+            match this.observe(res) {
+                Ok(sample) => return Poll::Ready(Some(Ok(sample.data))),
+                Err(RecvError::Closed) => return Poll::Ready(None),
+                // --------------------------------------------------
+                // check again once registered, in case the sample
+                // was published before the waker was
+                // --------------------------------------------------
+                Err(RecvError::Empty) if !registered => {
+                    this.sndr.wakers.register(cx.waker());
+                    registered = true;
+                },
+                Err(RecvError::Empty) => return Poll::Pending,
+                Err(e) => return Poll::Ready(Some(Err(e))),
+            }
+        }
+    }
+}
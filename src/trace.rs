@@ -0,0 +1,199 @@
+// --------------------------------------------------
+// external
+// --------------------------------------------------
+use tracing::Span;
+
+// --------------------------------------------------
+// local
+// --------------------------------------------------
+use crate::{
+    Envelope,
+    Publisher,
+    RecvError,
+    Subscriber,
+    CrosstalkTopic,
+    ok_or_log,
+};
+
+/// A sample read by a [`Subscriber`], along with a span of the read
+/// 
+/// The span follows from the span of the [`Publisher`] which published
+/// the sample if it propagates its span (see [`Publisher::set_trace`]),
+/// linking the traces of both sides of the topic
+/// 
+/// Produced by the `_traced` variants of the reads and receives of a [`Subscriber`],
+/// such as [`Subscriber::read_traced`] or [`Subscriber::recv_traced`]. Samples
+/// read with [`Subscriber::try_read_raw`] or [`Subscriber::latest`] are not traced
+/// 
+/// # Attributes
+/// 
+/// * `data` - the sample
+/// * `span` - the `crosstalk.read` span of the sample
+pub struct Traced<D> {
+    pub data: D,
+    pub span: Span,
+}
+/// [`Traced`] implementation
+impl<D> Traced<D> {
+    #[inline(always)]
+    /// Unwraps the sample, dropping its span
+    pub fn into_inner(self) -> D {
+        self.data
+    }
+
+    #[inline(always)]
+    /// Handles the sample within its span
+    /// 
+    /// # Arguments
+    /// 
+    /// * `f` - the function handling the sample
+    pub fn in_scope<R>(self, f: impl FnOnce(D) -> R) -> R {
+        let Self { data, span } = self;
+        span.in_scope(|| f(data))
+    }
+}
+
+/// [`Publisher`] implementation of tracing
+impl<D, T> Publisher<D, T> {
+    #[inline(always)]
+    /// Whether the publisher propagates its span to subscribers
+    pub fn trace(&self) -> bool {
+        self.trace
+    }
+
+    #[inline(always)]
+    /// Sets whether the publisher propagates its span to subscribers
+    /// 
+    /// When enabled, every sample carries the id of the span which was current
+    /// when it was published, which [`Subscriber::read_traced`] links to. Clones
+    /// of the publisher inherit this setting
+    /// 
+    /// Only the id of the span is carried, so that buffered samples do not keep
+    /// the span open. A sample read after its span was closed is linked by id
+    /// all the same, which a `tracing` subscriber reusing the ids of closed spans
+    /// may attribute to another span
+    /// 
+    /// # Arguments
+    /// 
+    /// * `trace` - whether to propagate the span of the publisher
+    /// 
+    /// # Examples
+    /// 
+    /// ```
+    /// use crosstalk::AsTopic;
+    /// 
+    /// #[derive(AsTopic)]
+    /// enum House {
+    ///     Bathroom,
+    /// }
+    /// 
+    /// crosstalk::init! {
+    ///     House::Bathroom => u8,
+    /// }
+    /// 
+    /// let mut node = crosstalk::BoundedNode::<House>::new(10);
+    /// let (mut publisher, mut subscriber) = node.pubsub_blocking(House::Bathroom).unwrap();
+    /// publisher.set_trace(true);
+    /// tracing::info_span!("producer").in_scope(|| publisher.write(1u8));
+    /// 
+    /// let sample = subscriber.try_read_traced().unwrap();
+    /// sample.in_scope(|data| {
+    ///     // handled within the `crosstalk.read` span,
+    ///     // which follows from the `producer` span
+    ///     assert_eq!(data, 1);
+    /// });
+    /// ```
+    pub fn set_trace(&mut self, trace: bool) {
+        self.trace = trace;
+    }
+}
+
+/// [`Subscriber`] implementation of tracing
+impl<D: Clone, T: CrosstalkTopic> Subscriber<D, T> {
+    #[inline(always)]
+    /// Asynchronous blocking read, along with a span of the read
+    /// 
+    /// See [`Subscriber::read`] and [`Traced`]
+    pub async fn read_traced(&mut self) -> Option<Traced<D>> {
+        let res = self.rcvr.read(&mut self.lag).await;
+        ok_or_log(self.observe(res)).map(|envelope| self.traced(envelope))
+    }
+
+    #[inline(always)]
+    /// Non-blocking read, along with a span of the read
+    /// 
+    /// See [`Subscriber::try_read`] and [`Traced`]
+    pub fn try_read_traced(&mut self) -> Option<Traced<D>> {
+        let res = self.rcvr.try_read(&mut self.lag);
+        ok_or_log(self.observe(res)).map(|envelope| self.traced(envelope))
+    }
+
+    #[inline(always)]
+    /// Sequential blocking read, along with a span of the read
+    /// 
+    /// See [`Subscriber::read_blocking`] and [`Traced`]
+    pub fn read_blocking_traced(&mut self) -> Option<Traced<D>> {
+        let res = self.rcvr.read_blocking(&mut self.lag);
+        ok_or_log(self.observe(res)).map(|envelope| self.traced(envelope))
+    }
+
+    #[inline(always)]
+    /// Asynchronous blocking read with a timeout, along with a span of the read
+    /// 
+    /// See [`Subscriber::read_timeout`] and [`Traced`]
+    pub async fn read_timeout_traced(&mut self, timeout: std::time::Duration) -> Option<Traced<D>> {
+        let res = self.rcvr.read_timeout(timeout, &mut self.lag).await;
+        ok_or_log(self.observe(res)).map(|envelope| self.traced(envelope))
+    }
+
+    #[inline(always)]
+    /// Asynchronous blocking receive, along with a span of the read
+    /// 
+    /// See [`Subscriber::recv`] and [`Traced`]
+    pub async fn recv_traced(&mut self) -> Result<Traced<D>, RecvError> {
+        let res = self.rcvr.recv(&mut self.lag).await;
+        let res = self.lag.count(res);
+        self.observe(res).map(|envelope| self.traced(envelope))
+    }
+
+    #[inline(always)]
+    /// Non-blocking receive, along with a span of the read
+    /// 
+    /// See [`Subscriber::try_recv`] and [`Traced`]
+    pub fn try_recv_traced(&mut self) -> Result<Traced<D>, RecvError> {
+        let res = self.rcvr.try_recv(&mut self.lag);
+        let res = self.lag.count(res);
+        self.observe(res).map(|envelope| self.traced(envelope))
+    }
+
+    #[inline(always)]
+    /// Sequential blocking receive, along with a span of the read
+    /// 
+    /// See [`Subscriber::recv_blocking`] and [`Traced`]
+    pub fn recv_blocking_traced(&mut self) -> Result<Traced<D>, RecvError> {
+        let res = self.rcvr.recv_blocking(&mut self.lag);
+        let res = self.lag.count(res);
+        self.observe(res).map(|envelope| self.traced(envelope))
+    }
+
+    #[inline(always)]
+    /// Asynchronous blocking receive with a timeout, along with a span of the read
+    /// 
+    /// See [`Subscriber::recv_timeout`] and [`Traced`]
+    pub async fn recv_timeout_traced(&mut self, timeout: std::time::Duration) -> Result<Traced<D>, RecvError> {
+        let res = self.rcvr.recv_timeout(timeout, &mut self.lag).await;
+        let res = self.lag.count(res);
+        self.observe(res).map(|envelope| self.traced(envelope))
+    }
+
+    #[inline(always)]
+    /// Opens the `crosstalk.read` span of a sample, following
+    /// from the span of its publisher if it was propagated
+    fn traced(&self, envelope: Envelope<D>) -> Traced<D> {
+        let span = tracing::info_span!("crosstalk.read", topic = self.topic.name());
+        if let Some(publisher) = &envelope.span {
+            span.follows_from(publisher);
+        }
+        Traced { data: envelope.data, span }
+    }
+}